@@ -12,6 +12,7 @@ use crate::app::{AUTH_STATE, IS_INITIAL_AUTH_CHECK_COMPLETE};
 struct RegisterInfo {
     username: String,
     password: String,
+    email: String,
 }
 
 // backend의 generate_password 결과 구조체
@@ -43,6 +44,7 @@ pub fn Register() -> impl IntoView {
             // input field 값 상태 관리(Signals)
             let (username, set_username) = create_signal("".to_string());
             let (password, set_password) = create_signal("".to_string());
+            let (email, set_email) = create_signal("".to_string());
             // register request 상태 관리(로딩 중, 성공, 실패 등 표시)
             let (register_status, set_register_status) = create_signal("".to_string());
             // 패스워드 추천 요청 상태 관리
@@ -56,6 +58,7 @@ pub fn Register() -> impl IntoView {
                 let user_info = RegisterInfo {  // 현재 상태 값으로 구조체 생성
                     username: username.get(),
                     password: password.get(),
+                    email: email.get(),
                 };
                 set_register_status.set("Processing...".to_string());   // 상태 업데이트
                 
@@ -74,7 +77,7 @@ pub fn Register() -> impl IntoView {
                             if status.is_success() {
                                 set_register_status.set(format!("Registration successful! {}", body_text));
                                 info!("회원가입 성공!");
-                                // 회원가입 성공 시 로그인 페이지로 이동
+                                // 회원가입 성공 시 로그인 페이지로 이동(메일로 받은 토큰으로 인증 후 로그인 가능)
                                 navigate_for_async("/login", Default::default());
                             } else {
                                 set_register_status.set(format!("Registration failed...: {} - {}", status, body_text));
@@ -143,6 +146,15 @@ pub fn Register() -> impl IntoView {
                         prop:value=username
                     />
                 </div>
+                // 이메일 입력 필드(인증 메일 수신 주소)
+                <div>
+                    <label for="email">"Email: "</label>
+                    <input
+                        id="email" type="email"
+                        on:input=move |ev| { set_email.set(event_target_value(&ev)); }
+                        prop:value=email
+                    />
+                </div>
                 // 패스워드 입력 필드 및 추천 버튼
                 <div>
                     <label for="password">"Password: "</label>
@@ -0,0 +1,211 @@
+-- PostgreSQL 스키마(migrations/sqlite의 202508111000까지 적용한 상태와 같은 구조)
+-- 시각은 timestamptz로 저장하고 조회 시 "YYYY-MM-DD HH:MM:SS"(UTC) 문자열로 변환
+-- 애플리케이션이 i64로 읽는 정수 컬럼은 모두 bigint
+
+create table if not exists users (
+    id bigint generated by default as identity primary key,
+    username text not null unique,
+    password_hash text not null,
+    created_at timestamptz default current_timestamp,
+    disabled_at timestamptz,            -- 관리자에 의해 비활성화된 계정(null이면 활성)
+    email text,                         -- 저장 시 소문자로 정규화
+    email_verified_at timestamptz,      -- null이면 미인증 계정(로그인 불가)
+    constraint users_email_key unique (email)
+);
+
+create table if not exists todos (
+    id bigint generated by default as identity primary key,
+    owner_id bigint not null references users(id) on delete cascade,
+    title text not null,
+    completed boolean not null default false,
+    due_date date,
+    created_at timestamptz not null default current_timestamp,
+    updated_at timestamptz not null default current_timestamp
+);
+
+create index if not exists idx_todos_owner_id on todos(owner_id);
+
+-- refresh 토큰(원문 대신 SHA-256 해시만 저장)
+create table if not exists refresh_tokens (
+    id bigint generated by default as identity primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    family_id text not null,
+    token_hash text not null unique,
+    created_at timestamptz not null default current_timestamp,
+    expires_at timestamptz not null,
+    used_at timestamptz,
+    revoked_at timestamptz
+);
+
+create index if not exists idx_refresh_tokens_family_id on refresh_tokens(family_id);
+create index if not exists idx_refresh_tokens_user_id on refresh_tokens(user_id);
+
+-- 폐기된 access 토큰 목록(jti 기준)
+create table if not exists revoked_tokens (
+    jti text primary key,
+    expires_at timestamptz not null,
+    revoked_at timestamptz not null default current_timestamp
+);
+
+create index if not exists idx_revoked_tokens_expires_at on revoked_tokens(expires_at);
+
+-- 로그인 세션(id는 refresh 토큰 family_id 및 access 토큰의 sid 클레임과 동일)
+create table if not exists sessions (
+    id text primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    device_label text,
+    ip text,
+    user_agent text,
+    created_at timestamptz not null default current_timestamp,
+    last_seen_at timestamptz not null default current_timestamp,
+    revoked_at timestamptz
+);
+
+create index if not exists idx_sessions_user_id on sessions(user_id);
+
+-- 로그인 실패 기록 및 잠금 상태(key: 'user:<username>' 또는 'ip:<client ip>')
+create table if not exists login_attempts (
+    key text primary key,
+    failures bigint not null default 0,
+    last_failure_at timestamptz not null default current_timestamp,
+    locked_until timestamptz
+);
+
+-- 비밀번호 재설정 토큰(일회용)
+create table if not exists password_reset_tokens (
+    id bigint generated by default as identity primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    token_hash text not null unique,
+    created_at timestamptz not null default current_timestamp,
+    expires_at timestamptz not null,
+    used_at timestamptz
+);
+
+create index if not exists idx_password_reset_tokens_user_id on password_reset_tokens(user_id);
+
+-- TOTP 2단계 인증 설정(enabled_at이 비어 있으면 확인 전 상태)
+create table if not exists user_totp (
+    user_id bigint primary key references users(id) on delete cascade,
+    secret text not null,
+    created_at timestamptz not null default current_timestamp,
+    enabled_at timestamptz,
+    last_used_step bigint
+);
+
+create table if not exists mfa_recovery_codes (
+    id bigint generated by default as identity primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    code_hash text not null,
+    used_at timestamptz
+);
+
+create index if not exists idx_mfa_recovery_codes_user_id on mfa_recovery_codes(user_id);
+
+-- 역할 기반 접근 제어(RBAC)
+create table if not exists roles (
+    id bigint generated by default as identity primary key,
+    name text not null unique
+);
+
+create table if not exists permissions (
+    id bigint generated by default as identity primary key,
+    name text not null unique
+);
+
+create table if not exists role_permissions (
+    role_id bigint not null references roles(id) on delete cascade,
+    permission_id bigint not null references permissions(id) on delete cascade,
+    primary key (role_id, permission_id)
+);
+
+create table if not exists user_roles (
+    user_id bigint not null references users(id) on delete cascade,
+    role_id bigint not null references roles(id) on delete cascade,
+    primary key (user_id, role_id)
+);
+
+-- OAuth2 / OpenID Connect 제공자 모드
+create table if not exists oauth_clients (
+    id bigint generated by default as identity primary key,
+    client_id text not null unique,
+    client_secret_hash text,    -- 공개 클라이언트는 null
+    name text not null,
+    created_at timestamptz not null default current_timestamp
+);
+
+create table if not exists oauth_redirect_uris (
+    client_id bigint not null references oauth_clients(id) on delete cascade,
+    redirect_uri text not null,
+    primary key (client_id, redirect_uri)
+);
+
+create table if not exists oauth_authorization_codes (
+    code_hash text primary key,
+    client_id bigint not null references oauth_clients(id) on delete cascade,
+    user_id bigint not null references users(id) on delete cascade,
+    redirect_uri text not null,
+    scope text not null,
+    code_challenge text not null,
+    nonce text,
+    expires_at timestamptz not null,
+    used_at timestamptz
+);
+
+create index if not exists idx_oauth_authorization_codes_expires_at on oauth_authorization_codes(expires_at);
+
+-- 이메일 인증 토큰(일회용)
+create table if not exists email_verification_tokens (
+    id bigint generated by default as identity primary key,
+    user_id bigint not null references users(id) on delete cascade,
+    token_hash text not null unique,
+    expires_at timestamptz not null,
+    used_at timestamptz,
+    created_at timestamptz not null default current_timestamp
+);
+
+create index if not exists idx_email_verification_tokens_user_id on email_verification_tokens(user_id, created_at);
+
+-- 보안 감사 로그(사용자가 삭제되어도 기록은 남아야 하므로 users에 대한 외래 키 없음)
+create table if not exists audit_events (
+    id bigint generated by default as identity primary key,
+    created_at timestamptz not null default current_timestamp,
+    event text not null,
+    outcome text not null,
+    actor_id bigint,
+    actor text,
+    ip text,
+    user_agent text,
+    detail text
+);
+
+create index if not exists idx_audit_events_created_at on audit_events(created_at);
+create index if not exists idx_audit_events_actor on audit_events(actor);
+create index if not exists idx_audit_events_event on audit_events(event, outcome);
+
+-- 추가만 가능(append-only): 기록 수정 및 삭제 금지
+create or replace function audit_events_append_only() returns trigger as $$
+begin
+    raise exception 'audit_events is append-only';
+end;
+$$ language plpgsql;
+
+create trigger audit_events_no_update before update or delete on audit_events
+    for each row execute function audit_events_append_only();
+
+create trigger audit_events_no_truncate before truncate on audit_events
+    for each statement execute function audit_events_append_only();
+
+-- 기본 역할 및 권한(admin 역할은 모든 권한 보유, user 역할은 관리 권한 없음)
+insert into roles(name) values ('admin'), ('user') on conflict do nothing;
+insert into permissions(name) values
+    ('users.read'),
+    ('users.write'),
+    ('users.unlock'),
+    ('sessions.revoke'),
+    ('roles.write'),
+    ('oauth_clients.write'),
+    ('audit.read')
+on conflict do nothing;
+insert into role_permissions(role_id, permission_id)
+    select r.id, p.id from roles r, permissions p where r.name = 'admin'
+on conflict do nothing;
@@ -0,0 +1,28 @@
+-- todo 메모(notes), 태그, 전문 검색 추가(migrations/sqlite의 202508251000과 같은 기능)
+
+alter table todos add column if not exists notes text;
+
+-- 제목과 메모 전문 검색(SQLite FTS5처럼 형태소 분석 없이 단어 단위로 일치하도록 simple 설정 사용)
+alter table todos add column if not exists search tsvector
+    generated always as (to_tsvector('simple', coalesce(title, '') || ' ' || coalesce(notes, ''))) stored;
+
+create index if not exists idx_todos_search on todos using gin(search);
+
+-- 마감일 범위 조건 및 마감일 정렬
+create index if not exists idx_todos_owner_id_due_date on todos(owner_id, due_date);
+
+-- 태그(사용자별 이름 유일)
+create table if not exists tags (
+    id bigint generated by default as identity primary key,
+    owner_id bigint not null references users(id) on delete cascade,
+    name text not null,
+    unique (owner_id, name)
+);
+
+create table if not exists todo_tags (
+    todo_id bigint not null references todos(id) on delete cascade,
+    tag_id bigint not null references tags(id) on delete cascade,
+    primary key (todo_id, tag_id)
+);
+
+create index if not exists idx_todo_tags_tag_id on todo_tags(tag_id);
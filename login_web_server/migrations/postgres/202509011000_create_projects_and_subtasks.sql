@@ -0,0 +1,21 @@
+-- 프로젝트, 하위 todo(subtask), 태그 색상 추가(migrations/sqlite의 202509011000과 같은 구조)
+
+-- 프로젝트(사용자별 이름 유일)
+create table if not exists projects (
+    id bigint generated by default as identity primary key,
+    owner_id bigint not null references users(id) on delete cascade,
+    name text not null,
+    created_at timestamptz not null default current_timestamp,
+    unique (owner_id, name)
+);
+
+-- 프로젝트 삭제 시 소속 todo는 애플리케이션에서 함께 삭제하거나 다른 프로젝트로 옮김
+alter table todos add column if not exists project_id bigint references projects(id);
+-- 상위 todo가 삭제되면 하위 todo도 함께 삭제
+alter table todos add column if not exists parent_id bigint references todos(id) on delete cascade;
+
+create index if not exists idx_todos_project_id on todos(project_id);
+create index if not exists idx_todos_parent_id on todos(parent_id);
+
+-- 태그 색상(#rrggbb)
+alter table tags add column if not exists color text not null default '#9e9e9e';
@@ -0,0 +1,7 @@
+-- 요청 횟수 제한(고정 기간 카운터)
+-- key: '<용도>:<대상>' 형식, 예) 'password_reset:ip:<client ip>'
+create table if not exists rate_limits (
+    key text primary key,
+    hits bigint not null default 0,
+    resets_at timestamptz not null  -- 이 시각이 지나면 다음 요청부터 1로 다시 계산
+);
@@ -0,0 +1,22 @@
+-- todos 테이블에 소유자(owner_id), 완료 상태(completed), 마감일(due_date) 추가
+-- SQLite는 ALTER TABLE로 외래 키 제약을 추가할 수 없으므로 새 테이블 생성 후 기존 행을 이관
+create table todos_new (
+    id integer primary key autoincrement,
+    owner_id integer references users(id) on delete cascade,   -- 기존 행만 null(소유자 없음)
+    title text not null,
+    completed boolean not null default 0,
+    due_date date,
+    created_at datetime not null default current_timestamp,
+    updated_at datetime not null default current_timestamp
+);
+
+-- 소유자 개념이 없던 기존 행은 임의의 사용자에게 넘기지 않고 소유자 없이(owner_id null) 모두 이관
+-- 모든 todo API는 소유자로 거르므로 이관 전까지는 조회되지 않으며,
+-- 관리자가 POST /api/admin/users/{username}/legacy-todos로 지정한 사용자에게 한 번에 넘길 수 있음
+insert into todos_new (id, owner_id, title, created_at, updated_at)
+    select id, null, title, created_at, created_at from todos;
+
+drop table todos;
+alter table todos_new rename to todos;
+
+create index if not exists idx_todos_owner_id on todos(owner_id);
@@ -0,0 +1,15 @@
+-- refresh 토큰 저장 테이블(원문 대신 SHA-256 해시만 저장)
+-- family_id: 한 번의 로그인에서 교체(rotation)되며 이어지는 토큰 묶음, 재사용 탐지 시 family 단위로 폐기
+create table if not exists refresh_tokens (
+    id integer primary key autoincrement,
+    user_id integer not null references users(id) on delete cascade,
+    family_id text not null,
+    token_hash text not null unique,
+    created_at datetime not null default current_timestamp,
+    expires_at datetime not null,
+    used_at datetime,
+    revoked_at datetime
+);
+
+create index if not exists idx_refresh_tokens_family_id on refresh_tokens(family_id);
+create index if not exists idx_refresh_tokens_user_id on refresh_tokens(user_id);
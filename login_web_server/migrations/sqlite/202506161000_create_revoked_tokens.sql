@@ -0,0 +1,9 @@
+-- 로그아웃 등으로 폐기된 access 토큰 목록(jti 기준)
+-- expires_at(토큰 exp)이 지나면 토큰 자체가 검증을 통과하지 못하므로 정리 대상
+create table if not exists revoked_tokens (
+    jti text primary key,
+    expires_at datetime not null,
+    revoked_at datetime not null default current_timestamp
+);
+
+create index if not exists idx_revoked_tokens_expires_at on revoked_tokens(expires_at);
@@ -0,0 +1,20 @@
+-- 로그인 세션(기기) 테이블
+-- id는 refresh 토큰 family_id 및 access 토큰의 sid 클레임과 동일
+create table if not exists sessions (
+    id text primary key,
+    user_id integer not null references users(id) on delete cascade,
+    device_label text,
+    ip text,
+    user_agent text,
+    created_at datetime not null default current_timestamp,
+    last_seen_at datetime not null default current_timestamp,
+    revoked_at datetime
+);
+
+create index if not exists idx_sessions_user_id on sessions(user_id);
+
+-- 이미 발급된 refresh 토큰 family 중 폐기되지 않은 것은 세션으로 이관(기기 정보는 알 수 없음)
+insert or ignore into sessions (id, user_id, created_at, last_seen_at)
+    select family_id, user_id, min(created_at), max(created_at) from refresh_tokens
+    group by family_id, user_id
+    having sum(revoked_at is null) > 0;
@@ -0,0 +1,8 @@
+-- 로그인 실패 기록 및 잠금 상태
+-- key: 'user:<username>' 또는 'ip:<client ip>'
+create table if not exists login_attempts (
+    key text primary key,
+    failures integer not null default 0,
+    last_failure_at datetime not null default current_timestamp,
+    locked_until datetime
+);
@@ -0,0 +1,12 @@
+-- 비밀번호 재설정 토큰(일회용, 유효 기간 제한)
+-- 토큰 원문은 메일로만 전달하고 DB에는 SHA-256 해시만 저장
+create table if not exists password_reset_tokens (
+    id integer primary key autoincrement,
+    user_id integer not null references users(id) on delete cascade,
+    token_hash text not null unique,
+    created_at datetime not null default current_timestamp,
+    expires_at datetime not null,
+    used_at datetime
+);
+
+create index if not exists idx_password_reset_tokens_user_id on password_reset_tokens(user_id);
@@ -0,0 +1,20 @@
+-- TOTP 2단계 인증 설정(사용자당 하나)
+-- enabled_at이 비어 있으면 등록 후 확인 코드를 아직 제출하지 않은 상태
+-- last_used_step: 마지막으로 사용된 코드의 시간 단계(같은 코드 재사용 방지)
+create table if not exists user_totp (
+    user_id integer primary key references users(id) on delete cascade,
+    secret text not null,
+    created_at datetime not null default current_timestamp,
+    enabled_at datetime,
+    last_used_step integer
+);
+
+-- 인증 앱을 사용할 수 없을 때 사용하는 일회용 복구 코드(SHA-256 해시로만 저장)
+create table if not exists mfa_recovery_codes (
+    id integer primary key autoincrement,
+    user_id integer not null references users(id) on delete cascade,
+    code_hash text not null,
+    used_at datetime
+);
+
+create index if not exists idx_mfa_recovery_codes_user_id on mfa_recovery_codes(user_id);
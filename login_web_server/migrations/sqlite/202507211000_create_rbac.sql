@@ -0,0 +1,43 @@
+-- 역할 기반 접근 제어(RBAC)
+-- 역할(role)은 권한(permission)의 묶음이며, 사용자는 여러 역할을 가질 수 있음
+create table if not exists roles (
+    id integer primary key autoincrement,
+    name text not null unique
+);
+
+create table if not exists permissions (
+    id integer primary key autoincrement,
+    name text not null unique
+);
+
+create table if not exists role_permissions (
+    role_id integer not null references roles(id) on delete cascade,
+    permission_id integer not null references permissions(id) on delete cascade,
+    primary key (role_id, permission_id)
+);
+
+create table if not exists user_roles (
+    user_id integer not null references users(id) on delete cascade,
+    role_id integer not null references roles(id) on delete cascade,
+    primary key (user_id, role_id)
+);
+
+-- 관리자에 의해 비활성화된 계정(null이면 활성)
+alter table users add column disabled_at datetime;
+
+-- 기본 역할 및 권한
+insert or ignore into roles(name) values ('admin'), ('user');
+insert or ignore into permissions(name) values
+    ('users.read'),         -- 사용자 목록 조회
+    ('users.write'),        -- 사용자 비활성화/활성화/삭제
+    ('users.unlock'),       -- 로그인 잠금 해제
+    ('sessions.revoke'),    -- 다른 사용자의 세션 강제 로그아웃
+    ('roles.write');        -- 사용자 역할 부여/회수
+
+-- admin 역할은 모든 권한 보유, user 역할은 관리 권한 없음(본인 리소스만 접근)
+insert or ignore into role_permissions(role_id, permission_id)
+    select r.id, p.id from roles r, permissions p where r.name = 'admin';
+
+-- 기존 사용자는 모두 user 역할
+insert or ignore into user_roles(user_id, role_id)
+    select u.id, r.id from users u, roles r where r.name = 'user';
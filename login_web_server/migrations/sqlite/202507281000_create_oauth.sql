@@ -0,0 +1,36 @@
+-- OAuth2 / OpenID Connect 제공자(provider) 모드
+-- 다른 내부 앱(클라이언트)이 이 서버의 계정으로 로그인할 수 있도록 authorization code + PKCE 흐름 지원
+create table if not exists oauth_clients (
+    id integer primary key autoincrement,
+    client_id text not null unique,
+    client_secret_hash text,    -- 공개 클라이언트(SPA, 모바일 앱 등)는 null, PKCE만으로 인증
+    name text not null,         -- 세션 목록에 표시할 앱 이름
+    created_at datetime not null default current_timestamp
+);
+
+-- 클라이언트별 허용 redirect_uri(정확히 일치하는 값만 허용)
+create table if not exists oauth_redirect_uris (
+    client_id integer not null references oauth_clients(id) on delete cascade,
+    redirect_uri text not null,
+    primary key (client_id, redirect_uri)
+);
+
+-- 인가 코드(원문 대신 SHA-256 해시 저장, 일회용)
+create table if not exists oauth_authorization_codes (
+    code_hash text primary key,
+    client_id integer not null references oauth_clients(id) on delete cascade,
+    user_id integer not null references users(id) on delete cascade,
+    redirect_uri text not null,
+    scope text not null,
+    code_challenge text not null,   -- PKCE S256 code_challenge
+    nonce text,                     -- id_token에 그대로 담아 재전송 공격 방지
+    expires_at datetime not null,
+    used_at datetime
+);
+
+create index if not exists idx_oauth_authorization_codes_expires_at on oauth_authorization_codes(expires_at);
+
+-- 클라이언트 등록 관리 권한(admin 역할에 부여)
+insert or ignore into permissions(name) values ('oauth_clients.write');
+insert or ignore into role_permissions(role_id, permission_id)
+    select r.id, p.id from roles r, permissions p where r.name = 'admin' and p.name = 'oauth_clients.write';
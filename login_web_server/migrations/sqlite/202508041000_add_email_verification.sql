@@ -0,0 +1,23 @@
+-- 이메일 주소 및 인증 상태
+-- email_verified_at이 null이면 미인증 계정(로그인 불가)
+alter table users add column email text;
+alter table users add column email_verified_at datetime;
+
+-- 같은 주소로 여러 계정을 만들 수 없음(null은 중복 허용, 저장 시 소문자로 정규화)
+create unique index if not exists idx_users_email on users(email);
+
+-- 이메일 인증 도입 이전에 가입한 계정은 로그인할 수 있도록 인증된 것으로 간주
+update users set email_verified_at = current_timestamp where email_verified_at is null;
+
+-- 이메일 인증 토큰(원문 대신 SHA-256 해시 저장, 일회용)
+create table if not exists email_verification_tokens (
+    id integer primary key autoincrement,
+    user_id integer not null references users(id) on delete cascade,
+    token_hash text not null unique,
+    expires_at datetime not null,
+    used_at datetime,
+    created_at datetime not null default current_timestamp
+);
+
+-- 재발송 횟수 제한 조회용
+create index if not exists idx_email_verification_tokens_user_id on email_verification_tokens(user_id, created_at);
@@ -0,0 +1,33 @@
+-- 보안 감사 로그(회원가입, 로그인 성공/실패, 로그아웃, 탈퇴, 토큰 검증 실패, 비밀번호 변경 등)
+-- 사용자가 삭제되어도 기록은 남아야 하므로 users에 대한 외래 키 없음
+create table if not exists audit_events (
+    id integer primary key autoincrement,
+    created_at datetime not null default current_timestamp,
+    event text not null,        -- 이벤트 종류(register, login, logout 등)
+    outcome text not null,      -- success 또는 failure
+    actor_id integer,           -- 사용자 id(알 수 없으면 null)
+    actor text,                 -- 사용자 이름(로그인 실패 시에는 입력된 이름)
+    ip text,
+    user_agent text,
+    detail text                 -- 실패 사유 등(에러 code)
+);
+
+create index if not exists idx_audit_events_created_at on audit_events(created_at);
+create index if not exists idx_audit_events_actor on audit_events(actor);
+create index if not exists idx_audit_events_event on audit_events(event, outcome);
+
+-- 추가만 가능(append-only): 기록 수정 및 삭제 금지
+create trigger if not exists audit_events_no_update before update on audit_events
+begin
+    select raise(abort, 'audit_events is append-only');
+end;
+
+create trigger if not exists audit_events_no_delete before delete on audit_events
+begin
+    select raise(abort, 'audit_events is append-only');
+end;
+
+-- 감사 로그 조회 권한(admin 역할에 부여)
+insert or ignore into permissions(name) values ('audit.read');
+insert or ignore into role_permissions(role_id, permission_id)
+    select r.id, p.id from roles r, permissions p where r.name = 'admin' and p.name = 'audit.read';
@@ -0,0 +1,42 @@
+-- todo 메모(notes), 태그, 전문 검색(FTS5) 추가
+
+alter table todos add column notes text;
+
+-- 마감일 범위 조건 및 마감일 정렬
+create index if not exists idx_todos_owner_id_due_date on todos(owner_id, due_date);
+
+-- 태그(사용자별 이름 유일)
+create table if not exists tags (
+    id integer primary key autoincrement,
+    owner_id integer not null references users(id) on delete cascade,
+    name text not null,
+    unique (owner_id, name)
+);
+
+create table if not exists todo_tags (
+    todo_id integer not null references todos(id) on delete cascade,
+    tag_id integer not null references tags(id) on delete cascade,
+    primary key (todo_id, tag_id)
+);
+
+create index if not exists idx_todo_tags_tag_id on todo_tags(tag_id);
+
+-- 제목과 메모 전문 검색 색인(todos 테이블을 원본으로 하는 external content 테이블)
+create virtual table if not exists todos_fts using fts5(title, notes, content='todos', content_rowid='id');
+
+-- todos 변경 시 색인 갱신
+create trigger if not exists todos_fts_insert after insert on todos begin
+    insert into todos_fts(rowid, title, notes) values (new.id, new.title, new.notes);
+end;
+
+create trigger if not exists todos_fts_delete after delete on todos begin
+    insert into todos_fts(todos_fts, rowid, title, notes) values ('delete', old.id, old.title, old.notes);
+end;
+
+create trigger if not exists todos_fts_update after update of title, notes on todos begin
+    insert into todos_fts(todos_fts, rowid, title, notes) values ('delete', old.id, old.title, old.notes);
+    insert into todos_fts(rowid, title, notes) values (new.id, new.title, new.notes);
+end;
+
+-- 기존 todo 색인
+insert into todos_fts(todos_fts) values ('rebuild');
@@ -0,0 +1,21 @@
+-- 프로젝트, 하위 todo(subtask), 태그 색상 추가
+
+-- 프로젝트(사용자별 이름 유일)
+create table if not exists projects (
+    id integer primary key autoincrement,
+    owner_id integer not null references users(id) on delete cascade,
+    name text not null,
+    created_at datetime not null default current_timestamp,
+    unique (owner_id, name)
+);
+
+-- 프로젝트 삭제 시 소속 todo는 애플리케이션에서 함께 삭제하거나 다른 프로젝트로 옮김
+alter table todos add column project_id integer references projects(id);
+-- 상위 todo가 삭제되면 하위 todo도 함께 삭제
+alter table todos add column parent_id integer references todos(id) on delete cascade;
+
+create index if not exists idx_todos_project_id on todos(project_id);
+create index if not exists idx_todos_parent_id on todos(parent_id);
+
+-- 태그 색상(#rrggbb)
+alter table tags add column color text not null default '#9e9e9e';
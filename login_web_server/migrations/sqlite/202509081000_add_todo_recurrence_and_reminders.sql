@@ -0,0 +1,27 @@
+-- 반복 todo와 마감 알림 추가
+
+-- 반복 규칙(정규화한 RRULE, 완료하면 다음 occurrence로 옮겨짐)
+alter table todos add column recurrence text;
+-- 알림 시각: 마감일 0시(UTC)로부터의 분(음수면 그 전날 이전), 알림 이벤트를 만든 시각
+alter table todos add column reminder_offset_minutes integer;
+alter table todos add column reminded_at datetime;
+
+create index if not exists idx_todos_pending_reminders on todos(due_date)
+    where reminder_offset_minutes is not null and reminded_at is null and completed = 0;
+
+-- todo 이벤트(outbox): 알림 작업이 기록하고 전달 작업이 notifier로 보낸 뒤 delivered_at 기록
+-- 이벤트 발생 시점의 제목과 마감일을 함께 저장(todo가 삭제되어도 이벤트는 남음)
+create table if not exists todo_events (
+    id integer primary key autoincrement,
+    owner_id integer not null references users(id) on delete cascade,
+    todo_id integer references todos(id) on delete set null,
+    kind text not null,
+    title text not null,
+    due_date date not null,
+    remind_at datetime not null,
+    created_at datetime not null default current_timestamp,
+    delivered_at datetime
+);
+
+create index if not exists idx_todo_events_owner_id on todo_events(owner_id, id);
+create index if not exists idx_todo_events_undelivered on todo_events(id) where delivered_at is null;
@@ -0,0 +1,11 @@
+-- OAuth 클라이언트 앱 로그인으로 만든 세션의 클라이언트 및 승인된 scope
+-- oauth_client_id가 null이면 이 서버에 직접 로그인한 세션(/api/login)
+-- refresh 토큰은 세션을 만든 클라이언트에서만 교체 가능, 클라이언트 삭제 시 세션도 삭제되어 발급된 토큰이 모두 무효화됨
+alter table sessions add column oauth_client_id integer references oauth_clients(id) on delete cascade;
+alter table sessions add column scope text;
+
+-- 기존 클라이언트 앱 세션(기기 이름이 앱 이름)은 해당 클라이언트에 연결, 승인된 scope는 알 수 없으므로 openid만 허용
+update sessions set
+    oauth_client_id = (select min(c.id) from oauth_clients c where c.name = sessions.device_label),
+    scope = 'openid'
+    where device_label in (select name from oauth_clients);
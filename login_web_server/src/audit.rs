@@ -0,0 +1,123 @@
+use serde::Serialize;
+use crate::error::ApiError;
+use crate::metrics::metrics;
+use crate::repository::AuditRepository;
+use crate::session::ClientInfo;
+
+// 보안 감사 로그(audit_events, 추가만 가능)
+// 기록 실패가 요청 처리에 영향을 주지 않도록 에러는 로그만 남기고 무시
+
+// 이벤트 종류
+pub const REGISTER: &str = "register";
+pub const LOGIN: &str = "login";
+pub const LOGIN_MFA: &str = "login_mfa";
+pub const OAUTH_AUTHORIZE: &str = "oauth_authorize"; // OAuth 인가 화면에서의 로그인
+pub const LOGOUT: &str = "logout";
+pub const DELETE_USER: &str = "delete_user";
+pub const VERIFY_TOKEN: &str = "verify_token";
+pub const CHANGE_PASSWORD: &str = "change_password";
+pub const RESET_PASSWORD: &str = "reset_password";
+// 관리자에 의한 계정 및 역할 변경(행위자는 관리자, detail에 대상 사용자와 역할)
+pub const ADMIN_DISABLE_USER: &str = "admin_disable_user";
+pub const ADMIN_ENABLE_USER: &str = "admin_enable_user";
+pub const ADMIN_DELETE_USER: &str = "admin_delete_user";
+pub const ADMIN_UNLOCK_USER: &str = "admin_unlock_user";
+pub const ADMIN_FORCE_LOGOUT: &str = "admin_force_logout";
+pub const ADMIN_ASSIGN_ROLE: &str = "admin_assign_role";
+pub const ADMIN_REMOVE_ROLE: &str = "admin_remove_role";
+pub const ADMIN_ASSIGN_LEGACY_TODOS: &str = "admin_assign_legacy_todos";
+
+pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
+
+// 기록할 이벤트
+pub struct Entry<'a> {
+    pub(crate) event: &'static str,
+    pub(crate) outcome: &'static str,
+    pub(crate) detail: Option<&'a str>,
+    pub(crate) actor_id: Option<i64>,
+    pub(crate) actor: Option<&'a str>,
+}
+
+impl<'a> Entry<'a> {
+    pub fn success(event: &'static str) -> Self {
+        Entry { event, outcome: SUCCESS, detail: None, actor_id: None, actor: None }
+    }
+
+    // detail: 실패 사유(에러 code 등)
+    pub fn failure(event: &'static str, detail: &'a str) -> Self {
+        Entry { event, outcome: FAILURE, detail: Some(detail), actor_id: None, actor: None }
+    }
+
+    // 핸들러 결과로 성공/실패 판단(실패 사유는 에러 code)
+    pub fn from_result<T>(event: &'static str, result: &Result<T, ApiError>) -> Self {
+        match result {
+            Ok(_) => Entry::success(event),
+            Err(e) => Entry::failure(event, e.code()),
+        }
+    }
+
+    // 부가 정보(관리 작업의 대상 등)
+    pub fn detail(mut self, detail: &'a str) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+
+    // 행위자(사용자 id를 모르면 이름만 기록)
+    pub fn actor(mut self, actor_id: Option<i64>, actor: &'a str) -> Self {
+        self.actor_id = actor_id;
+        self.actor = Some(actor);
+        self
+    }
+}
+
+// 이벤트 기록(요청의 IP 및 User-Agent 포함)
+// 로그인 결과는 메트릭(auth_login_attempts_total)으로도 집계
+pub async fn record(repo: &dyn AuditRepository, client: &ClientInfo, entry: Entry<'_>) {
+    if entry.event==LOGIN || entry.event==LOGIN_MFA || entry.event==OAUTH_AUTHORIZE {
+        metrics().login_attempts.with_label_values(&[entry.event, entry.outcome, entry.detail.unwrap_or("")]).inc();
+    }
+    if let Err(e) = repo.insert_audit_event(client, &entry).await {
+        tracing::error!("Error recording audit event {} ({}): {:?}", entry.event, entry.outcome, e);
+    }
+}
+
+// 조회 조건(모두 선택, 시각은 "YYYY-MM-DD HH:MM:SS" UTC)
+#[derive(Default, Debug)]
+pub struct Filter {
+    pub event: Option<String>,
+    pub outcome: Option<String>,
+    pub actor: Option<String>,
+    pub actor_id: Option<i64>,
+    pub ip: Option<String>,
+    pub since: Option<String>,  // 이 시각 이후(포함)
+    pub until: Option<String>,  // 이 시각 이전(미포함)
+}
+
+#[derive(Serialize, Debug)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub created_at: String,
+    pub event: String,
+    pub outcome: String,
+    pub actor_id: Option<i64>,
+    pub actor: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub detail: Option<String>,
+}
+
+// 조건에 맞는 이벤트 한 페이지(최신순)
+pub async fn list(repo: &dyn AuditRepository, filter: &Filter, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, sqlx::Error> {
+    repo.list_audit_events(filter, limit, offset).await
+}
+
+// 조건에 맞는 이벤트 수
+pub async fn count(repo: &dyn AuditRepository, filter: &Filter) -> Result<i64, sqlx::Error> {
+    repo.count_audit_events(filter).await
+}
+
+// 조건에 맞는 모든 이벤트(기록순, 내보내기용)
+pub async fn export(repo: &dyn AuditRepository, filter: &Filter) -> Result<Vec<AuditEvent>, sqlx::Error> {
+    repo.export_audit_events(filter).await
+}
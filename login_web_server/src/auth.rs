@@ -1,51 +1,268 @@
 use serde::{Serialize, Deserialize};
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
-use anyhow::Result;
-use std::{env, sync::OnceLock ,time::{SystemTime, UNIX_EPOCH}};    // 현재 시간 및 Unix epoch 시간 가져오기
+use jsonwebtoken::{encode, decode, decode_header, Header, Validation};
+use anyhow::{anyhow, Result};
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use futures_util::future::{ready, Ready};
+use crate::config;
+use crate::error::ApiError;
+use crate::keys::KeySet;
+use crate::opaque_token;
+use crate::rbac::Grants;
+use std::{sync::OnceLock ,time::{SystemTime, UNIX_EPOCH}};    // 현재 시간 및 Unix epoch 시간 가져오기
 
-// JWT 서명 및 검증에 사용할 비밀 키를 로드하기 위한 OnceLock
+// JWT 서명 및 검증에 사용할 키 목록을 보관하는 OnceLock
     // 프로그램 실행 중 한 번만 초기화될 수 있는 컨테이너
     // 'static 라이프타임을 가진 값을 안전하게 한 번만 초기화하여 여러 곳에서 접근 가능하게 함
-static JWT_SECRET: OnceLock<String> = OnceLock::new(); 
+    // 비대칭 키(EdDSA/RS256)로 서명하므로 다른 서비스는 JWKS의 공개 키만으로 토큰 검증 가능
+static JWT_KEYS: OnceLock<KeySet> = OnceLock::new();
 
-fn get_jwt_secret_key() -> Result<&'static str> {
-    let secret_key = JWT_SECRET.get_or_init(|| {    // 초기화되어 있지 않으면 클로저 실행 후 값 초기화하고 그 참조 반환. 초기화되어 있으면 저장된 값의 참조 즉시 반환
-        env::var("JWT_SECRET_KEY").expect("JWT_SECRET_KEY not set in .env or environment...")
-    });
-    Ok(&secret_key)
+// 서버 시작 시(main) 또는 통합 테스트에서 키 목록 등록
+// 이미 초기화되어 있으면 기존 값을 유지
+pub fn init_keys(keys: KeySet) {
+    let _ = JWT_KEYS.set(keys);
 }
 
-#[derive(Serialize, Deserialize)]
+pub fn key_set() -> Result<&'static KeySet> {
+    JWT_KEYS.get().ok_or_else(|| anyhow!("JWT keys are not initialized"))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 // JWT Payroad에 담길 Claim 정보 정의
-struct Claims {
-    sub: String,    // sub: 토큰 주체(Subject), 사용자 이름 저장용
-    exp: usize,     // exp: 토큰 만료 시간(Expiration Time), Unix Timestamp(초)
+pub struct Claims {
+    pub iss: String,    // iss: 토큰 발급자(Issuer)
+    pub aud: String,    // aud: 토큰 사용 대상(Audience)
+    pub sub: String,    // sub: 토큰 주체(Subject), 사용자 이름 저장용
+    pub uid: i64,       // uid: 사용자 id(users.id), 핸들러에서 사용자 조회 없이 사용
+    pub iat: usize,     // iat: 토큰 발급 시간(Issued At), Unix Timestamp(초)
+    pub nbf: usize,     // nbf: 이 시간 이전에는 토큰 사용 불가(Not Before)
+    pub exp: usize,     // exp: 토큰 만료 시간(Expiration Time), Unix Timestamp(초)
+    pub jti: String,    // jti: 토큰 고유 식별자(JWT ID), 토큰 단위 폐기(revocation)에 사용
+    pub sid: String,    // sid: 로그인 세션 식별자(refresh 토큰 family id와 동일)
+    // 비밀번호만 확인되고 2단계 인증이 남은 토큰(true면 AuthMiddleware에서 거부, /api/login/mfa에서만 사용)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mfa_pending: bool,
+    // 발급 시점의 역할 및 권한(RequirePermission 가드에서 사용)
+    // 역할 부여는 다음 로그인 또는 토큰 갱신 시 반영되고(최대 tokens.access_ttl_secs),
+    // 역할 회수는 사용자의 모든 세션을 폐기하므로 즉시 반영됨(AuthMiddleware의 세션 확인에서 거부)
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub perms: Vec<String>,
+    // OAuth 클라이언트에 발급한 access 토큰의 승인된 scope(역할 및 권한 없음)
+    // scope가 있는 토큰은 /oauth/userinfo에서만 사용 가능(AuthMiddleware에서 거부)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+// 검증을 통과한 access 토큰의 인증 정보
+// AuthMiddleware가 RequestExtensions에 저장하며, 핸들러는 AuthContext 타입 인자로 추출
+#[derive(Clone, Debug)]
+pub struct AuthContext {
+    pub user_id: i64,
+    pub username: String,
+    pub session_id: String,
+    pub token_id: String,   // jti(로그아웃 시 폐기 목록에 등록)
+    pub expires_at: usize,  // exp(폐기 기록 보관 기한)
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+    pub scope: Option<String>,  // OAuth 클라이언트에 발급한 토큰이면 승인된 scope
+}
+
+impl AuthContext {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r==role)
+    }
+
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p==permission)
+    }
+
+    // OAuth 클라이언트에 발급한 토큰인지 확인(AuthMiddleware와 토큰 검증 API에서 이 서버의 API 접근을 거부할 때 사용)
+    pub fn is_client_token(&self) -> bool {
+        self.scope.is_some()
+    }
+
+    // OAuth 클라이언트 토큰이면 승인된 scope 포함 여부, 이 서버에 직접 로그인한 토큰이면 항상 true
+    pub fn has_scope(&self, scope: &str) -> bool {
+        match &self.scope {
+            Some(granted) => granted.split(' ').any(|s| s==scope),
+            None => true,
+        }
+    }
+}
+
+impl From<Claims> for AuthContext {
+    fn from(claims: Claims) -> Self {
+        AuthContext {
+            user_id: claims.uid,
+            username: claims.sub,
+            session_id: claims.sid,
+            token_id: claims.jti,
+            expires_at: claims.exp,
+            roles: claims.roles,
+            permissions: claims.perms,
+            scope: claims.scope,
+        }
+    }
+}
+
+// AuthMiddleware를 거치지 않았거나 설정 오류로 인증 정보가 없는 경우
+pub fn auth_context_missing() -> ApiError {
+    ApiError::internal("auth_context_missing", "Authentication context missing.")
+}
+
+// 핸들러 인자로 AuthContext를 받을 수 있도록 RequestExtensions에서 복제
+impl FromRequest for AuthContext {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(req.extensions().get::<AuthContext>().cloned().ok_or_else(auth_context_missing))
+    }
+}
+
+// 2단계 인증 대기 토큰을 access 토큰으로 사용하려 한 경우(decode_jwt 에러)
+#[derive(Debug)]
+pub struct MfaPendingToken;
+
+impl std::fmt::Display for MfaPendingToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Two-factor authentication required")
+    }
+}
+
+impl std::error::Error for MfaPendingToken {}
+
+// 토큰 고유 식별자(jti) 생성: 128비트 난수의 hex 문자열
+fn generate_jti() -> String {
+    opaque_token::generate(16)
 }
 
-pub fn create_jwt(username: &str) -> Result<String> {
-    // 토큰 만료 시간 계산(Unix Timestamp)
+// 공통 클레임(iss, aud, iat, nbf, exp, jti)을 채워 Claims 생성
+fn new_claims(user_id: i64, username: &str, ttl_secs: u64) -> Result<Claims> {
+    // 현재 시간(Unix Timestamp)
     // SystemTime::now(): 현재 시스템 시간
     // duration_since(UNIX_EPOCH): 1970/01/01 00:00:00UTC 이후 경과 시간 계산
-    // +3600: 현재 시간 + 3600초(1시간)
-    let expiration = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 3600;    // 1시간 유효
-    let claims = Claims {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let settings = &config::get().tokens;
+    Ok(Claims {
+        iss: settings.issuer.clone(),
+        aud: settings.audience.clone(),
         sub: username.to_string(),  // 사용자 이름 복제 후 String 저장
-        exp: expiration as usize,   // 토큰 만료 시간 u64 -> usize 저장
+        uid: user_id,
+        iat: now as usize,
+        nbf: now as usize,
+        exp: (now+ttl_secs) as usize,   // 토큰 만료 시간 u64 -> usize 저장
+        jti: generate_jti(),    // 토큰마다 새 식별자 부여
+        sid: String::new(),
+        mfa_pending: false,
+        roles: Vec::new(),
+        perms: Vec::new(),
+        scope: None,
+    })
+}
+
+pub fn create_jwt(user_id: i64, username: &str, session_id: &str, grants: &Grants) -> Result<String> {
+    let claims = Claims {
+        sid: session_id.to_string(),
+        roles: grants.roles.clone(),
+        perms: grants.permissions.clone(),
+        ..new_claims(user_id, username, config::get().tokens.access_ttl_secs)?    // 기본 1시간 유효
+    };
+    encode_claims(&claims)
+}
+
+// OAuth 클라이언트에 발급하는 access 토큰 생성
+// 사용자의 역할 및 권한 대신 승인된 scope만 담으므로 이 서버의 API에는 사용할 수 없음
+pub fn create_oauth_access_token(user_id: i64, username: &str, session_id: &str, scope: &str) -> Result<String> {
+    let claims = Claims {
+        sid: session_id.to_string(),
+        scope: Some(scope.to_string()),
+        ..new_claims(user_id, username, config::get().tokens.access_ttl_secs)?
+    };
+    encode_claims(&claims)
+}
+
+// 2단계 인증 대기(challenge) 토큰 생성
+// 아직 세션이 없으므로 sid는 비워 두고, 코드 확인 후 새 세션과 access 토큰을 발급
+// 2단계 인증 전에는 역할 및 권한 없음
+pub fn create_mfa_challenge(user_id: i64, username: &str) -> Result<String> {
+    let claims = Claims {
+        mfa_pending: true,
+        ..new_claims(user_id, username, config::get().tokens.mfa_challenge_ttl_secs)?   // 기본 5분 안에 /api/login/mfa로 코드 제출
+    };
+    encode_claims(&claims)
+}
+
+// OpenID Connect id_token 클레임(클라이언트 앱에 로그인한 사용자 정보 전달용)
+// access 토큰과 달리 aud는 클라이언트 id이며, API 접근에는 사용할 수 없음
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,    // 사용자 id(사용자 이름은 삭제 후 재사용될 수 있으므로 고정 식별자 사용)
+    pub aud: String,    // 클라이언트 id
+    pub iat: usize,
+    pub exp: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,  // 인가 요청의 nonce(재전송 공격 방지)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>, // profile scope를 요청한 경우
+}
+
+// id_token 생성(access 토큰과 같은 서명 키 및 유효 기간 사용)
+pub fn create_id_token(user_id: i64, username: Option<&str>, client_id: &str, nonce: Option<&str>) -> Result<String> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let settings = &config::get().tokens;
+    let claims = IdTokenClaims {
+        iss: settings.issuer.clone(),
+        sub: user_id.to_string(),
+        aud: client_id.to_string(),
+        iat: now as usize,
+        exp: (now+settings.access_ttl_secs) as usize,
+        nonce: nonce.map(str::to_string),
+        preferred_username: username.map(str::to_string),
     };
-    let secret_key = get_jwt_secret_key()?;
+    encode_claims(&claims)
+}
+
+fn encode_claims<T: Serialize>(claims: &T) -> Result<String> {
+    let key = key_set()?.signing_key();
     
     // JWT 토큰 생성
-    // 기본 헤더, 생성한 클레임, 정의한 비밀 키를 사용하여 토큰 인코딩
-    let token = encode(&Header::default(), &claims, &EncodingKey::from_secret(secret_key.as_bytes()))?;
+    // 서명 키의 알고리즘과 kid를 헤더에 담아 토큰 인코딩(검증 시 kid로 공개 키 선택)
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+    let token = encode(&header, claims, &key.encoding)?;
     Ok(token)
 }
 
-pub fn decode_jwt(token: &str) -> Result<String> {  // 검증할 JWT 토큰 문자열 참조 매개변수
-    let secret_key = get_jwt_secret_key()?;
+// 서명, 발급자, 대상, 유효 기간(nbf~exp)을 검증하고 전체 클레임 반환
+pub fn decode_claims(token: &str) -> Result<Claims> {  // 검증할 JWT 토큰 문자열 참조 매개변수
+    // 헤더의 kid로 검증 키 선택(교체 이전 키로 서명된 토큰도 키가 남아 있는 동안 유효)
+    let kid = decode_header(token)?.kid.ok_or_else(|| anyhow!("Token has no kid"))?;
+    let key = key_set()?.verification_key(&kid).ok_or_else(|| anyhow!("Unknown signing key: {}", kid))?;
+    let settings = &config::get().tokens;
+    // 키의 알고리즘만 허용(알고리즘 혼동 공격 방지)
+    let mut validation = Validation::new(key.algorithm);
+    validation.set_issuer(&[&settings.issuer]);
+    validation.set_audience(&[&settings.audience]);
+    validation.set_required_spec_claims(&["exp", "nbf", "iss", "aud", "sub"]);
+    validation.validate_nbf = true;
+    validation.leeway = settings.leeway_secs;
     let data = decode::<Claims>(    // JWT 토큰을 Claims 구조체 타입으로 디코딩
         token,  // 디코딩할 토큰 문자열
-        &DecodingKey::from_secret(secret_key.as_bytes()),  // 복호화 비밀 키
-        &Validation::default()  // 만료 시간 검증
+        &key.decoding,  // 공개 키
+        &validation
     )?;
-    Ok(data.claims.sub) // 디코딩된 데이터에서 클레임의 주체 필드 값 추출 반환
-}
\ No newline at end of file
+    Ok(data.claims)
+}
+
+// access 토큰을 검증하고 인증 정보 반환
+// 2단계 인증 대기 토큰이면 MfaPendingToken 에러
+pub fn decode_jwt(token: &str) -> Result<AuthContext> {
+    let claims = decode_claims(token)?;
+    if claims.mfa_pending {
+        return Err(MfaPendingToken.into());
+    }
+    Ok(claims.into())
+}
@@ -0,0 +1,486 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use crate::password_hasher::{Algorithm, Argon2Hasher};
+use crate::repository::Backend;
+use web_tracing::LogConfig;
+
+// 서버 설정
+// TOML 파일(--config로 지정, 없으면 ./login_web_server.toml이 있을 때만 사용)을 읽은 뒤
+// 환경 변수(.env 포함)로 개별 항목을 재정의하고, 서버 시작 시 한 번에 검증
+//
+// 환경 변수 이름은 기존 이름을 유지(DATABASE_URL, JWT_ISSUER, PASSWORD_MIN_LENGTH, BCRYPT_TEST_* 등)
+
+// --config를 지정하지 않았을 때 찾는 설정 파일
+pub const DEFAULT_CONFIG_PATH: &str = "login_web_server.toml";
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub tokens: TokenConfig,
+    pub password_hash: PasswordHashConfig,
+    pub bcrypt: BcryptConfig,
+    pub argon2: Argon2Config,
+    pub password_policy: PasswordPolicyConfig,
+    pub mail: MailConfig,
+    pub notifications: NotificationsConfig,
+    pub admin: AdminConfig,
+    // 로그 수준(LOG_LEVEL, 없으면 RUST_LOG)과 출력 형식(LOG_FORMAT, text 또는 json)
+    pub logging: LogConfig,
+    pub metrics: MetricsConfig,
+    pub jobs: JobsConfig,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind: String,   // BIND_ADDRESS
+    // CORS 허용 출처(CORS_ALLOWED_ORIGINS, 쉼표 구분)
+    // 기본값은 빈 목록(다른 출처의 브라우저 요청 거부), 프론트엔드 출처를 지정("*"는 모든 출처 허용, 개발용)
+    pub cors_allowed_origins: Vec<String>,
+    // SHUTDOWN_TIMEOUT_SECS, 종료 신호(SIGINT/SIGTERM) 수신 후 처리 중인 요청을 기다리는 최대 시간
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind: "127.0.0.1:8080".to_string(),
+            cors_allowed_origins: Vec::new(),
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,    // DATABASE_URL(필수, sqlite:... 또는 postgres://...)
+}
+
+// 토큰 발급 및 검증 설정(유효 기간은 모두 초 단위)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenConfig {
+    pub issuer: String,     // JWT_ISSUER, access 토큰의 iss(OIDC 사용 시 공개 URL)
+    pub audience: String,   // JWT_AUDIENCE, access 토큰의 aud
+    pub leeway_secs: u64,   // JWT_LEEWAY_SECS, exp/nbf 검증 시 서버 간 시계 차이 허용 범위
+    pub key_dir: PathBuf,   // JWT_KEY_DIR, 서명 키(*.pem) 디렉터리
+    pub signing_kid: Option<String>,    // JWT_SIGNING_KID, 없으면 kid 정렬 기준 마지막 키로 서명
+    pub access_ttl_secs: u64,           // ACCESS_TOKEN_TTL_SECS
+    pub refresh_ttl_secs: u64,          // REFRESH_TOKEN_TTL_SECS
+    pub mfa_challenge_ttl_secs: u64,    // MFA_CHALLENGE_TTL_SECS
+    pub password_reset_ttl_secs: u64,   // PASSWORD_RESET_TTL_SECS
+    pub email_verification_ttl_secs: u64,   // EMAIL_VERIFICATION_TTL_SECS
+    pub authorization_code_ttl_secs: u64,   // AUTHORIZATION_CODE_TTL_SECS
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        TokenConfig {
+            issuer: "login_web_server".to_string(),
+            audience: "toy_project".to_string(),
+            leeway_secs: 30,
+            key_dir: PathBuf::from("./keys"),
+            signing_kid: None,
+            access_ttl_secs: 3600,  // 1시간, 만료 후에는 refresh 토큰으로 재발급
+            refresh_ttl_secs: 14 * 24 * 3600,   // 14일
+            mfa_challenge_ttl_secs: 300,    // 5분 안에 /api/login/mfa로 코드 제출
+            password_reset_ttl_secs: 30 * 60,
+            email_verification_ttl_secs: 24 * 3600,
+            authorization_code_ttl_secs: 120,   // 클라이언트는 redirect 직후 바로 토큰으로 교환
+        }
+    }
+}
+
+// 새 비밀번호 해시에 사용할 알고리즘
+// 다른 알고리즘이나 이전 매개변수로 만든 해시는 로그인 성공 시 자동으로 다시 해싱
+// 기본값은 bcrypt(기존 설정 그대로 시작 가능), argon2id로 바꾸려면 argon2.self_test도 설정
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordHashConfig {
+    pub algorithm: Algorithm,   // PASSWORD_HASH_ALGORITHM(bcrypt 또는 argon2id)
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        PasswordHashConfig { algorithm: Algorithm::Bcrypt }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct BcryptConfig {
+    pub cost: u32,  // BCRYPT_COST, 해시 반복 횟수(2^cost)
+    // 서버 시작 시 고정 솔트로 해시를 계산하여 기대값과 비교
+    // 기존 bcrypt 해시 검증에 항상 사용하므로 필수
+    pub self_test: Option<HashSelfTest>,
+}
+
+impl Default for BcryptConfig {
+    fn default() -> Self {
+        BcryptConfig { cost: 10, self_test: None }
+    }
+}
+
+// Argon2id 매개변수(기본값은 OWASP 권장값: 19MiB, 2회, 병렬 1)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Argon2Config {
+    pub memory_kib: u32,    // ARGON2_MEMORY_KIB
+    pub iterations: u32,    // ARGON2_ITERATIONS
+    pub parallelism: u32,   // ARGON2_PARALLELISM
+    // password_hash.algorithm이 argon2id이면 필수
+    pub self_test: Option<HashSelfTest>,
+}
+
+impl Default for Argon2Config {
+    fn default() -> Self {
+        Argon2Config { memory_kib: 19 * 1024, iterations: 2, parallelism: 1, self_test: None }
+    }
+}
+
+// 자가시험 입력(비밀번호, 고정 솔트)과 기대 해시
+// 환경 변수: <PREFIX>_TEST_PASSWORD, <PREFIX>_TEST_FIXED_SALT, <PREFIX>_EXPECTED_HASH(PREFIX: BCRYPT, ARGON2)
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HashSelfTest {
+    pub password: String,
+    pub fixed_salt: String,     // bcrypt는 16바이트, Argon2는 8~48바이트
+    pub expected_hash: String,
+}
+
+// 비밀번호 정책 중 설정으로 바꿀 수 있는 항목(나머지는 PasswordPolicy 기본값)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordPolicyConfig {
+    pub min_length: usize,      // PASSWORD_MIN_LENGTH
+    pub max_length: usize,      // PASSWORD_MAX_LENGTH(바이트 단위)
+    pub min_entropy_bits: f64,  // PASSWORD_MIN_ENTROPY_BITS
+    pub breached_passwords_file: Option<PathBuf>,   // BREACHED_PASSWORDS_FILE
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        PasswordPolicyConfig {
+            min_length: 8,
+            max_length: 72, // bcrypt는 72바이트 이후를 무시
+            min_entropy_bits: 35.0,
+            breached_passwords_file: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+    pub dir: Option<PathBuf>,   // MAILER_DIR, 설정 시 .eml 파일로 저장, 없으면 표준 출력
+}
+
+// todo 알림 이벤트 전달 방식
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationsConfig {
+    pub dir: Option<PathBuf>,   // NOTIFICATIONS_DIR, 설정 시 이벤트마다 .json 파일로 저장, 없으면 로그로 출력
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    pub usernames: Vec<String>, // ADMIN_USERNAMES(쉼표 구분), 시작 시 admin 역할 부여
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    // METRICS_BEARER_TOKEN, 설정 시 GET /metrics에 Authorization: Bearer <token> 필요(미설정 시 공개)
+    pub bearer_token: Option<String>,
+}
+
+// 서버 안에서 실행하는 주기 작업
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsConfig {
+    // CLEANUP_INTERVAL_SECS, 만료된 토큰 폐기 기록, 로그인 실패 기록, 재설정/인증 토큰, 인가 코드 정리 주기
+    pub cleanup_interval_secs: u64,
+    // REMINDER_INTERVAL_SECS, 알림 시각이 지난 todo의 알림 이벤트 기록 및 전달 주기
+    pub reminder_interval_secs: u64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        JobsConfig { cleanup_interval_secs: 3600, reminder_interval_secs: 60 }
+    }
+}
+
+// 환경 변수 값 조회 함수(테스트에서는 HashMap 등으로 대체)
+type EnvLookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+// 환경 변수가 있으면 파싱하여 덮어쓰기(파싱 실패는 errors에 추가)
+fn override_parsed<T: std::str::FromStr>(env: EnvLookup, errors: &mut Vec<String>, name: &str, target: &mut T) {
+    if let Some(value) = env(name) {
+        match value.parse() {
+            Ok(parsed) => *target = parsed,
+            Err(_) => errors.push(format!("{} has an invalid value: {:?}", name, value)),
+        }
+    }
+}
+
+fn override_optional<T: From<String>>(env: EnvLookup, name: &str, target: &mut Option<T>) {
+    if let Some(value) = env(name) {
+        *target = Some(T::from(value));
+    }
+}
+
+// 쉼표로 구분된 목록(빈 항목 무시)
+fn override_list(env: EnvLookup, name: &str, target: &mut Vec<String>) {
+    if let Some(value) = env(name) {
+        *target = value.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect();
+    }
+}
+
+impl Config {
+    // 설정 파일 로드 -> 환경 변수 재정의 -> 검증
+    // path가 없으면 DEFAULT_CONFIG_PATH가 있을 때만 읽고, 없으면 기본값에서 시작
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut config = match path {
+            Some(path) => Config::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => Config::from_file(Path::new(DEFAULT_CONFIG_PATH))?,
+            None => Config::default(),
+        };
+        config.apply_env_overrides(&|name| std::env::var(name).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        Config::parse(&content).with_context(|| format!("Invalid config file: {}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn apply_env_overrides(&mut self, env: EnvLookup) -> Result<()> {
+        let mut errors = Vec::new();
+        if let Some(bind) = env("BIND_ADDRESS") {
+            self.server.bind = bind;
+        }
+        override_list(env, "CORS_ALLOWED_ORIGINS", &mut self.server.cors_allowed_origins);
+        override_parsed(env, &mut errors, "SHUTDOWN_TIMEOUT_SECS", &mut self.server.shutdown_timeout_secs);
+        if let Some(url) = env("DATABASE_URL") {
+            self.database.url = url;
+        }
+
+        let tokens = &mut self.tokens;
+        if let Some(issuer) = env("JWT_ISSUER") {
+            tokens.issuer = issuer;
+        }
+        if let Some(audience) = env("JWT_AUDIENCE") {
+            tokens.audience = audience;
+        }
+        override_parsed(env, &mut errors, "JWT_LEEWAY_SECS", &mut tokens.leeway_secs);
+        override_parsed(env, &mut errors, "JWT_KEY_DIR", &mut tokens.key_dir);
+        override_optional(env, "JWT_SIGNING_KID", &mut tokens.signing_kid);
+        override_parsed(env, &mut errors, "ACCESS_TOKEN_TTL_SECS", &mut tokens.access_ttl_secs);
+        override_parsed(env, &mut errors, "REFRESH_TOKEN_TTL_SECS", &mut tokens.refresh_ttl_secs);
+        override_parsed(env, &mut errors, "MFA_CHALLENGE_TTL_SECS", &mut tokens.mfa_challenge_ttl_secs);
+        override_parsed(env, &mut errors, "PASSWORD_RESET_TTL_SECS", &mut tokens.password_reset_ttl_secs);
+        override_parsed(env, &mut errors, "EMAIL_VERIFICATION_TTL_SECS", &mut tokens.email_verification_ttl_secs);
+        override_parsed(env, &mut errors, "AUTHORIZATION_CODE_TTL_SECS", &mut tokens.authorization_code_ttl_secs);
+
+        override_parsed(env, &mut errors, "PASSWORD_HASH_ALGORITHM", &mut self.password_hash.algorithm);
+        override_parsed(env, &mut errors, "BCRYPT_COST", &mut self.bcrypt.cost);
+        override_self_test(env, "BCRYPT", &mut self.bcrypt.self_test);
+        override_parsed(env, &mut errors, "ARGON2_MEMORY_KIB", &mut self.argon2.memory_kib);
+        override_parsed(env, &mut errors, "ARGON2_ITERATIONS", &mut self.argon2.iterations);
+        override_parsed(env, &mut errors, "ARGON2_PARALLELISM", &mut self.argon2.parallelism);
+        override_self_test(env, "ARGON2", &mut self.argon2.self_test);
+
+        let policy = &mut self.password_policy;
+        override_parsed(env, &mut errors, "PASSWORD_MIN_LENGTH", &mut policy.min_length);
+        override_parsed(env, &mut errors, "PASSWORD_MAX_LENGTH", &mut policy.max_length);
+        override_parsed(env, &mut errors, "PASSWORD_MIN_ENTROPY_BITS", &mut policy.min_entropy_bits);
+        override_optional(env, "BREACHED_PASSWORDS_FILE", &mut policy.breached_passwords_file);
+        override_optional(env, "MAILER_DIR", &mut self.mail.dir);
+        override_optional(env, "NOTIFICATIONS_DIR", &mut self.notifications.dir);
+        override_list(env, "ADMIN_USERNAMES", &mut self.admin.usernames);
+        if let Some(level) = env("LOG_LEVEL").or_else(|| env("RUST_LOG")) {
+            self.logging.level = level;
+        }
+        override_parsed(env, &mut errors, "LOG_FORMAT", &mut self.logging.format);
+        override_optional(env, "METRICS_BEARER_TOKEN", &mut self.metrics.bearer_token);
+        override_parsed(env, &mut errors, "CLEANUP_INTERVAL_SECS", &mut self.jobs.cleanup_interval_secs);
+        override_parsed(env, &mut errors, "REMINDER_INTERVAL_SECS", &mut self.jobs.reminder_interval_secs);
+
+        if errors.is_empty() { Ok(()) } else { Err(config_error(errors)) }
+    }
+
+    // 모든 항목을 검사하여 잘못된 항목을 한 번에 보고
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+        if self.server.bind.to_socket_addrs().map(|mut addrs| addrs.next().is_none()).unwrap_or(true) {
+            errors.push(format!("server.bind (BIND_ADDRESS) is not a valid socket address: {:?}", self.server.bind));
+        }
+        for origin in &self.server.cors_allowed_origins {
+            if origin!="*" && !is_valid_origin(origin) {
+                errors.push(format!("server.cors_allowed_origins (CORS_ALLOWED_ORIGINS) contains an invalid origin: {:?} (expected scheme://host[:port] or \"*\")", origin));
+            }
+        }
+        if self.database.url.trim().is_empty() {
+            errors.push("database.url (DATABASE_URL) is required".to_string());
+        } else if Backend::from_url(&self.database.url).is_none() {
+            errors.push("database.url (DATABASE_URL) must start with sqlite:, postgres:// or postgresql://".to_string());
+        }
+
+        let tokens = &self.tokens;
+        if tokens.issuer.trim().is_empty() {
+            errors.push("tokens.issuer (JWT_ISSUER) must not be empty".to_string());
+        }
+        if tokens.audience.trim().is_empty() {
+            errors.push("tokens.audience (JWT_AUDIENCE) must not be empty".to_string());
+        }
+        for (name, value) in [
+            ("tokens.access_ttl_secs (ACCESS_TOKEN_TTL_SECS)", tokens.access_ttl_secs),
+            ("tokens.refresh_ttl_secs (REFRESH_TOKEN_TTL_SECS)", tokens.refresh_ttl_secs),
+            ("tokens.mfa_challenge_ttl_secs (MFA_CHALLENGE_TTL_SECS)", tokens.mfa_challenge_ttl_secs),
+            ("tokens.password_reset_ttl_secs (PASSWORD_RESET_TTL_SECS)", tokens.password_reset_ttl_secs),
+            ("tokens.email_verification_ttl_secs (EMAIL_VERIFICATION_TTL_SECS)", tokens.email_verification_ttl_secs),
+            ("tokens.authorization_code_ttl_secs (AUTHORIZATION_CODE_TTL_SECS)", tokens.authorization_code_ttl_secs),
+        ] {
+            if value==0 {
+                errors.push(format!("{} must be greater than 0", name));
+            }
+        }
+        if tokens.refresh_ttl_secs<tokens.access_ttl_secs {
+            errors.push("tokens.refresh_ttl_secs must not be shorter than tokens.access_ttl_secs".to_string());
+        }
+        if tokens.leeway_secs>=tokens.access_ttl_secs {
+            errors.push("tokens.leeway_secs must be shorter than tokens.access_ttl_secs".to_string());
+        }
+
+        // bcrypt crate가 허용하는 cost 범위
+        if !(4..=31).contains(&self.bcrypt.cost) {
+            errors.push(format!("bcrypt.cost (BCRYPT_COST) must be between 4 and 31, got {}", self.bcrypt.cost));
+        }
+        match &self.bcrypt.self_test {
+            None => errors.push("bcrypt.self_test (BCRYPT_TEST_PASSWORD, BCRYPT_TEST_FIXED_SALT, BCRYPT_EXPECTED_HASH) is required".to_string()),
+            Some(self_test) => {
+                if self_test.fixed_salt.len()!=16 {
+                    errors.push("bcrypt.self_test.fixed_salt (BCRYPT_TEST_FIXED_SALT) must be exactly 16 bytes".to_string());
+                }
+                if self_test.expected_hash.is_empty() {
+                    errors.push("bcrypt.self_test.expected_hash (BCRYPT_EXPECTED_HASH) is required".to_string());
+                }
+            }
+        }
+        let argon2 = &self.argon2;
+        if let Err(e) = Argon2Hasher::new(argon2.memory_kib, argon2.iterations, argon2.parallelism) {
+            errors.push(format!("argon2 (ARGON2_MEMORY_KIB, ARGON2_ITERATIONS, ARGON2_PARALLELISM): {}", e));
+        }
+        match &argon2.self_test {
+            None if self.password_hash.algorithm==Algorithm::Argon2id => {
+                errors.push("argon2.self_test (ARGON2_TEST_PASSWORD, ARGON2_TEST_FIXED_SALT, ARGON2_EXPECTED_HASH) is required when password_hash.algorithm is argon2id".to_string());
+            }
+            None => {}
+            Some(self_test) => {
+                if !(8..=48).contains(&self_test.fixed_salt.len()) {
+                    errors.push("argon2.self_test.fixed_salt (ARGON2_TEST_FIXED_SALT) must be 8 to 48 bytes".to_string());
+                }
+                if self_test.expected_hash.is_empty() {
+                    errors.push("argon2.self_test.expected_hash (ARGON2_EXPECTED_HASH) is required".to_string());
+                }
+            }
+        }
+
+        let policy = &self.password_policy;
+        if policy.min_length>policy.max_length {
+            errors.push("password_policy.min_length (PASSWORD_MIN_LENGTH) must not exceed password_policy.max_length (PASSWORD_MAX_LENGTH)".to_string());
+        }
+        if policy.min_entropy_bits<0.0 {
+            errors.push("password_policy.min_entropy_bits (PASSWORD_MIN_ENTROPY_BITS) must not be negative".to_string());
+        }
+        if let Err(e) = self.logging.filter() {
+            errors.push(format!("logging.level (LOG_LEVEL): {}", e));
+        }
+        if self.metrics.bearer_token.as_deref().is_some_and(|token| token.trim().is_empty()) {
+            errors.push("metrics.bearer_token (METRICS_BEARER_TOKEN) must not be empty when set".to_string());
+        }
+        if self.jobs.cleanup_interval_secs==0 {
+            errors.push("jobs.cleanup_interval_secs (CLEANUP_INTERVAL_SECS) must be greater than 0".to_string());
+        }
+        if self.jobs.reminder_interval_secs==0 {
+            errors.push("jobs.reminder_interval_secs (REMINDER_INTERVAL_SECS) must be greater than 0".to_string());
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(config_error(errors)) }
+    }
+}
+
+// CORS Origin 형식(scheme://host[:port], 경로 없음)
+fn is_valid_origin(origin: &str) -> bool {
+    match url::Url::parse(origin) {
+        Ok(url) => matches!(url.scheme(), "http" | "https") && url.has_host()
+            && url.path()=="/" && !origin.ends_with('/') && url.query().is_none() && url.fragment().is_none(),
+        Err(_) => false,
+    }
+}
+
+// 자가시험 항목 재정의(일부만 지정하면 나머지는 파일 값 유지)
+fn override_self_test(env: EnvLookup, prefix: &str, target: &mut Option<HashSelfTest>) {
+    let password = env(&format!("{}_TEST_PASSWORD", prefix));
+    let fixed_salt = env(&format!("{}_TEST_FIXED_SALT", prefix));
+    let expected_hash = env(&format!("{}_EXPECTED_HASH", prefix));
+    if password.is_none() && fixed_salt.is_none() && expected_hash.is_none() {
+        return;
+    }
+    let self_test = target.get_or_insert_with(HashSelfTest::default);
+    if let Some(password) = password {
+        self_test.password = password;
+    }
+    if let Some(fixed_salt) = fixed_salt {
+        self_test.fixed_salt = fixed_salt;
+    }
+    if let Some(expected_hash) = expected_hash {
+        self_test.expected_hash = expected_hash;
+    }
+}
+
+fn config_error(errors: Vec<String>) -> anyhow::Error {
+    anyhow!("Invalid configuration:\n  - {}", errors.join("\n  - "))
+}
+
+// 명령행 인자에서 --config <path> 또는 --config=<path> 추출
+pub fn path_from_args(args: impl IntoIterator<Item = String>) -> Result<Option<PathBuf>> {
+    let mut args = args.into_iter();
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        if arg=="--config" {
+            path = Some(args.next().ok_or_else(|| anyhow!("--config requires a file path"))?);
+        } else if let Some(value) = arg.strip_prefix("--config=") {
+            path = Some(value.to_string());
+        } else {
+            return Err(anyhow!("Unknown argument: {} (usage: login_web_server [--config <path>])", arg));
+        }
+    }
+    Ok(path.map(PathBuf::from))
+}
+
+// 서버 전체에서 공유하는 설정(토큰 유효 기간, bcrypt cost 등)
+// 서버 시작 시(main) 등록하며, 통합 테스트처럼 등록하지 않으면 기본값 사용
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+// 이미 초기화되어 있으면 기존 값을 유지
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
@@ -0,0 +1,64 @@
+use crate::{config, opaque_token};
+use crate::rate_limit::Limit;
+use crate::repository::{EmailVerificationRepository, UserRepository};
+
+// 인증 메일 발송 제한(가입 및 재발송, 계정 존재 여부와 관계없이 주소 기준으로 적용)
+// 같은 주소는 1분에 1통, 1시간에 5통까지, IP 기준 재발송 요청은 1시간에 20회
+pub const ADDRESS_COOLDOWN: Limit = Limit { max: 1, window_secs: 60 };
+pub const ADDRESS_LIMIT: Limit = Limit { max: 5, window_secs: 3600 };
+pub const IP_LIMIT: Limit = Limit { max: 20, window_secs: 3600 };
+
+// 이메일 주소 최대 길이(RFC 5321)
+const MAX_EMAIL_LEN: usize = 254;
+
+// 토큰 원문 길이(바이트, hex 인코딩 시 2배)
+const TOKEN_BYTES: usize = 32;
+
+// 이메일 주소 형식을 간단히 검사한 뒤 소문자로 정규화
+// (실제 주소 존재 여부는 인증 메일로 확인하므로 엄격한 RFC 5322 파싱은 하지 않음)
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let valid = email.len()<=MAX_EMAIL_LEN
+        && !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        && !email.chars().any(|c| c.is_whitespace() || c.is_control());
+    valid.then_some(email)
+}
+
+// 새 인증 토큰 발급 후 원문 반환
+// 아직 사용하지 않은 이전 토큰은 모두 무효화(가장 최근 메일의 토큰만 유효)
+pub async fn create(repo: &dyn EmailVerificationRepository, user_id: i64) -> Result<String, sqlx::Error> {
+    let token = opaque_token::generate(TOKEN_BYTES);
+    repo.create_email_verification(user_id, &opaque_token::hash(&token), config::get().tokens.email_verification_ttl_secs).await?;    // 기본 24시간
+    Ok(token)
+}
+
+// 주소 기준 요청 횟수 제한 키(rate_limits.key)와 제한(정규화된 주소 사용)
+pub fn address_limits(email: &str) -> Vec<(String, Limit)> {
+    vec![
+        (format!("email_verification:address:{}:cooldown", email), ADDRESS_COOLDOWN),
+        (format!("email_verification:address:{}", email), ADDRESS_LIMIT),
+    ]
+}
+
+pub fn ip_key(ip: &str) -> String {
+    format!("email_verification:ip:{}", ip)
+}
+
+// 유효한 토큰이면 사용 처리하고 계정을 인증 상태로 변경한 뒤 user id 반환
+// 존재하지 않거나, 만료되었거나, 이미 사용된 토큰이면 None
+pub async fn consume(repo: &dyn EmailVerificationRepository, token: &str) -> Result<Option<i64>, sqlx::Error> {
+    repo.consume_email_verification(&opaque_token::hash(token)).await
+}
+
+// 이메일 인증을 마친 계정인지 확인
+pub async fn is_verified(repo: &dyn UserRepository, user_id: i64) -> Result<bool, sqlx::Error> {
+    repo.is_email_verified(user_id).await
+}
+
+// 만료된 토큰과 사용된 토큰 정리(발송 횟수 제한은 rate_limits에서 관리)
+pub async fn prune_expired(repo: &dyn EmailVerificationRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_email_verifications(0).await
+}
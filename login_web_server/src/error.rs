@@ -0,0 +1,158 @@
+use actix_web::{http::{header, StatusCode}, HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt;
+use crate::password_policy::Violation;
+
+// RFC 7807 problem details 응답의 Content-Type
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+// 모든 핸들러와 AuthMiddleware가 공통으로 사용하는 API 에러
+// code는 프론트엔드가 분기 처리에 사용하는 고정 식별자이므로 변경 시 하위 호환에 주의
+#[derive(Debug)]
+pub enum ApiError {
+    BadRequest { code: &'static str, detail: String },    // 400 잘못된 입력
+    PasswordPolicy(Vec<Violation>), // 400 비밀번호 정책 위반(위반 규칙 목록 포함)
+    Unauthorized { code: &'static str, detail: String },  // 401 인증 실패
+    Forbidden { code: &'static str, detail: String },     // 403 권한 없음
+    NotFound { code: &'static str, detail: String },      // 404 리소스 없음
+    Conflict { code: &'static str, detail: String },      // 409 현재 상태와 충돌
+    TooManyAttempts { retry_after: u64 },   // 429 로그인 잠금(Retry-After 헤더 포함)
+    RateLimited { retry_after: u64 },       // 429 요청 횟수 제한(메일 재발송 등, Retry-After 헤더 포함)
+    Internal { code: &'static str, detail: String },      // 500 서버 내부 오류
+    Database(sqlx::Error),  // 500 DB 오류(상세 내용은 로그에만 남김)
+}
+
+impl ApiError {
+    pub fn bad_request(code: &'static str, detail: impl Into<String>) -> Self {
+        ApiError::BadRequest { code, detail: detail.into() }
+    }
+
+    pub fn unauthorized(code: &'static str, detail: impl Into<String>) -> Self {
+        ApiError::Unauthorized { code, detail: detail.into() }
+    }
+
+    pub fn forbidden(code: &'static str, detail: impl Into<String>) -> Self {
+        ApiError::Forbidden { code, detail: detail.into() }
+    }
+
+    pub fn not_found(code: &'static str, detail: impl Into<String>) -> Self {
+        ApiError::NotFound { code, detail: detail.into() }
+    }
+
+    pub fn conflict(code: &'static str, detail: impl Into<String>) -> Self {
+        ApiError::Conflict { code, detail: detail.into() }
+    }
+
+    pub fn internal(code: &'static str, detail: impl Into<String>) -> Self {
+        ApiError::Internal { code, detail: detail.into() }
+    }
+
+    // 응답 본문의 code 필드(고정 식별자)
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest { code, .. }
+            | ApiError::Unauthorized { code, .. }
+            | ApiError::Forbidden { code, .. }
+            | ApiError::NotFound { code, .. }
+            | ApiError::Conflict { code, .. }
+            | ApiError::Internal { code, .. } => code,
+            ApiError::PasswordPolicy(_) => "password_policy_violation",
+            ApiError::TooManyAttempts { .. } => "too_many_attempts",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::Database(_) => "database_error",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::BadRequest { detail, .. }
+            | ApiError::Unauthorized { detail, .. }
+            | ApiError::Forbidden { detail, .. }
+            | ApiError::NotFound { detail, .. }
+            | ApiError::Conflict { detail, .. }
+            | ApiError::Internal { detail, .. } => f.write_str(detail),
+            ApiError::PasswordPolicy(_) => f.write_str("Password does not meet the password policy."),
+            ApiError::TooManyAttempts { .. } => f.write_str("Too many failed login attempts. Please try again later."),
+            ApiError::RateLimited { .. } => f.write_str("Too many requests. Please try again later."),
+            // DB 에러 내용(쿼리, 제약 조건 이름 등)은 클라이언트에 노출하지 않음
+            ApiError::Database(_) => f.write_str("A database error occurred."),
+        }
+    }
+}
+
+// `?`로 DB 에러를 바로 반환할 수 있도록 변환
+impl From<sqlx::Error> for ApiError {
+    fn from(e: sqlx::Error) -> Self {
+        ApiError::Database(e)
+    }
+}
+
+// RFC 7807 problem details 본문
+#[derive(Serialize)]
+struct Problem<'a> {
+    #[serde(rename = "type")]
+    problem_type: &'static str, // 문제 유형 URI(별도 문서가 없으므로 about:blank)
+    title: &'a str,     // HTTP 상태 문구
+    status: u16,
+    detail: String,     // 사람이 읽을 수 있는 설명
+    code: &'static str, // 기계가 읽을 수 있는 고정 식별자(확장 필드)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    violations: Option<&'a [Violation]>,    // 비밀번호 정책 위반 목록(확장 필드)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,   // 잠금 해제 또는 다시 요청할 수 있을 때까지 남은 시간(초, 확장 필드)
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest { .. } | ApiError::PasswordPolicy(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden { .. } => StatusCode::FORBIDDEN,
+            ApiError::NotFound { .. } => StatusCode::NOT_FOUND,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::TooManyAttempts { .. } | ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Internal { .. } | ApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let ApiError::Database(e) = self {
+            tracing::error!("Database error: {:?}", e);
+        }
+        let status = self.status_code();
+        let (violations, retry_after) = match self {
+            ApiError::PasswordPolicy(violations) => (Some(violations.as_slice()), None),
+            ApiError::TooManyAttempts { retry_after } | ApiError::RateLimited { retry_after } => (None, Some(*retry_after)),
+            _ => (None, None),
+        };
+        let mut builder = HttpResponse::build(status);
+        builder.insert_header((header::CONTENT_TYPE, PROBLEM_JSON));
+        if let Some(retry_after) = retry_after {
+            builder.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        }
+        builder.json(Problem {
+            problem_type: "about:blank",
+            title: status.canonical_reason().unwrap_or("Error"),
+            status: status.as_u16(),
+            detail: self.to_string(),
+            code: self.code(),
+            violations,
+            retry_after,
+        })
+    }
+}
+
+// Json/Path/Query 익스트랙터 에러(형식이 잘못된 요청 본문, 숫자가 아닌 id, 잘못된 쿼리 문자열 등)도 problem+json으로 응답
+pub fn json_error_handler(err: actix_web::error::JsonPayloadError, _req: &actix_web::HttpRequest) -> actix_web::Error {
+    ApiError::bad_request("invalid_request_body", err.to_string()).into()
+}
+
+pub fn path_error_handler(err: actix_web::error::PathError, _req: &actix_web::HttpRequest) -> actix_web::Error {
+    ApiError::not_found("not_found", err.to_string()).into()
+}
+
+pub fn query_error_handler(err: actix_web::error::QueryPayloadError, _req: &actix_web::HttpRequest) -> actix_web::Error {
+    ApiError::bad_request("invalid_query", err.to_string()).into()
+}
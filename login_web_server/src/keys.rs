@@ -0,0 +1,283 @@
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+// JWT 서명 키 관리
+// 키 디렉터리의 <kid>.pem 파일(PKCS#8 Ed25519 또는 PKCS#1/PKCS#8 RSA 개인 키)을 모두 읽어
+// 가장 최근 kid(이름 정렬 기준 마지막, 또는 지정한 kid)로 서명하고, 나머지 키는 검증에만 사용
+//
+// 키 교체(rotation) 절차
+// 1. 더 뒤에 정렬되는 kid로 새 키 파일 추가(예: 자동 생성 키는 생성 시각 kid) 후 서버 재시작
+//    -> 새 토큰은 새 키로 서명되고, 기존 토큰은 이전 키로 계속 검증됨
+// 2. 이전 키로 서명된 access 토큰이 모두 만료된 뒤(tokens.access_ttl_secs 이후) 이전 키 파일 삭제
+
+// JWKS(/.well-known/jwks.json)로 공개하는 공개 키
+#[derive(Serialize, Clone, Debug)]
+pub struct Jwk {
+    kty: &'static str,  // OKP(Ed25519) 또는 RSA
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crv: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    x: Option<String>,  // Ed25519 공개 키
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<String>,  // RSA modulus
+    #[serde(skip_serializing_if = "Option::is_none")]
+    e: Option<String>,  // RSA exponent
+    kid: String,
+    alg: &'static str,
+    #[serde(rename = "use")]
+    key_use: &'static str,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    pub encoding: EncodingKey,
+    pub decoding: DecodingKey,
+    jwk: Jwk,
+}
+
+impl SigningKey {
+    // PEM 개인 키에서 서명 키와 공개 키(검증 키, JWK) 생성
+    pub fn from_pem(kid: &str, pem_text: &str) -> Result<Self> {
+        let parsed = pem::parse(pem_text).with_context(|| format!("Invalid PEM for key {}", kid))?;
+        match parsed.tag.as_str() {
+            "PRIVATE KEY" => {
+                // PKCS#8은 Ed25519와 RSA 모두 가능하므로 Ed25519부터 시도
+                if let Ok(pair) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&parsed.contents) {
+                    return Self::ed25519(kid, pem_text, pair.public_key().as_ref());
+                }
+                let pair = RsaKeyPair::from_pkcs8(&parsed.contents)
+                    .map_err(|e| anyhow!("Unsupported private key {}: {}", kid, e))?;
+                Self::rsa(kid, pem_text, &pair)
+            }
+            "RSA PRIVATE KEY" => {
+                let pair = RsaKeyPair::from_der(&parsed.contents)
+                    .map_err(|e| anyhow!("Invalid RSA private key {}: {}", kid, e))?;
+                Self::rsa(kid, pem_text, &pair)
+            }
+            tag => Err(anyhow!("Unsupported PEM type for key {}: {}", kid, tag)),
+        }
+    }
+
+    fn ed25519(kid: &str, pem_text: &str, public_key: &[u8]) -> Result<Self> {
+        Ok(SigningKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::EdDSA,
+            encoding: EncodingKey::from_ed_pem(pem_text.as_bytes())?,
+            decoding: DecodingKey::from_ed_der(public_key),
+            jwk: Jwk {
+                kty: "OKP",
+                crv: Some("Ed25519"),
+                x: Some(URL_SAFE_NO_PAD.encode(public_key)),
+                n: None,
+                e: None,
+                kid: kid.to_string(),
+                alg: "EdDSA",
+                key_use: "sig",
+            },
+        })
+    }
+
+    fn rsa(kid: &str, pem_text: &str, pair: &RsaKeyPair) -> Result<Self> {
+        let public_key = pair.public_key();
+        let n = public_key.modulus().big_endian_without_leading_zero();
+        let e = public_key.exponent().big_endian_without_leading_zero();
+        Ok(SigningKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::RS256,
+            encoding: EncodingKey::from_rsa_pem(pem_text.as_bytes())?,
+            decoding: DecodingKey::from_rsa_raw_components(n, e),
+            jwk: Jwk {
+                kty: "RSA",
+                crv: None,
+                x: None,
+                n: Some(URL_SAFE_NO_PAD.encode(n)),
+                e: Some(URL_SAFE_NO_PAD.encode(e)),
+                kid: kid.to_string(),
+                alg: "RS256",
+                key_use: "sig",
+            },
+        })
+    }
+}
+
+// 서명 키 1개와 검증 키 여러 개
+pub struct KeySet {
+    keys: Vec<SigningKey>,
+    signing: usize, // keys 중 서명에 사용하는 키의 인덱스
+}
+
+impl KeySet {
+    // 키 목록으로 생성(signing_kid가 없으면 kid 정렬 기준 마지막 키로 서명)
+    pub fn new(mut keys: Vec<SigningKey>, signing_kid: Option<&str>) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(anyhow!("No JWT signing keys available"));
+        }
+        keys.sort_by(|a, b| a.kid.cmp(&b.kid));
+        let signing = match signing_kid {
+            Some(kid) => keys.iter().position(|k| k.kid==kid)
+                .ok_or_else(|| anyhow!("JWT signing key {} not found", kid))?,
+            None => keys.len()-1,
+        };
+        Ok(KeySet { keys, signing })
+    }
+
+    // 디렉터리의 *.pem 파일을 모두 로드(파일 이름에서 확장자를 뺀 부분이 kid)
+    // 키가 하나도 없으면 Ed25519 키를 생성하여 저장
+    pub fn load_or_generate(dir: &Path, signing_kid: Option<&str>) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create key directory: {}", dir.display()))?;
+        let mut keys = load_dir(dir)?;
+        if keys.is_empty() {
+            let kid = new_kid();
+            let pem_text = generate_ed25519_pem()?;
+            let path = dir.join(format!("{}.pem", kid));
+            write_private_key(&path, &pem_text)?;
+            tracing::info!("Generated new JWT signing key {}", path.display());
+            keys.push(SigningKey::from_pem(&kid, &pem_text)?);
+        }
+        Self::new(keys, signing_kid)
+    }
+
+    // 메모리에만 존재하는 Ed25519 키 하나(통합 테스트 등에서 사용)
+    pub fn ephemeral() -> Result<Self> {
+        let key = SigningKey::from_pem(&new_kid(), &generate_ed25519_pem()?)?;
+        Self::new(vec![key], None)
+    }
+
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.keys[self.signing]
+    }
+
+    // 토큰 헤더의 kid로 검증 키 조회
+    pub fn verification_key(&self, kid: &str) -> Option<&SigningKey> {
+        self.keys.iter().find(|k| k.kid==kid)
+    }
+
+    pub fn jwks(&self) -> Jwks {
+        Jwks { keys: self.keys.iter().map(|k| k.jwk.clone()).collect() }
+    }
+}
+
+fn load_dir(dir: &Path) -> Result<Vec<SigningKey>> {
+    let mut keys = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read key directory: {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str())!=Some("pem") {
+            continue;
+        }
+        let kid = path.file_stem().and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("Invalid key file name: {}", path.display()))?;
+        let pem_text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read key file: {}", path.display()))?;
+        keys.push(SigningKey::from_pem(kid, &pem_text)?);
+    }
+    Ok(keys)
+}
+
+// 생성 시각 기반 kid(이름순 정렬이 생성 순서와 일치)
+fn new_kid() -> String {
+    format!("{}-{}", chrono::Utc::now().format("%Y%m%d%H%M%S"), crate::opaque_token::generate(4))
+}
+
+// PKCS#8 PEM 형식의 새 Ed25519 개인 키 생성
+pub fn generate_ed25519_pem() -> Result<String> {
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+        .map_err(|_| anyhow!("Failed to generate Ed25519 key"))?;
+    Ok(pem::encode(&pem::Pem { tag: "PRIVATE KEY".to_string(), contents: pkcs8.as_ref().to_vec() }))
+}
+
+// 개인 키 파일(서명 키, data.key)은 소유자만 읽을 수 있도록 저장
+// 쓰기 전에 잠시라도 다른 사용자가 읽을 수 없도록 처음부터 0600으로 생성하고, 이미 있는 파일(심볼릭 링크 포함)은 덮어쓰지 않음
+fn write_private_key(path: &Path, contents: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).with_context(|| format!("Failed to create key file: {}", path.display()))?;
+    file.write_all(contents.as_bytes()).with_context(|| format!("Failed to write key file: {}", path.display()))?;
+    Ok(())
+}
+
+// DB에 저장하는 비밀 값(TOTP 비밀 키 등)의 암호화 키(AES-256-GCM)
+// 키 디렉터리의 data.key 파일(32바이트 hex)을 사용하며, 없으면 생성
+// 이 키를 잃으면 암호화된 값을 복구할 수 없으므로 서명 키와 함께 백업(교체는 지원하지 않음)
+const DATA_KEY_FILE: &str = "data.key";
+// 암호화된 값의 접두사(접두사가 없는 값은 암호화 도입 이전의 평문)
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+pub struct DataKey(LessSafeKey);
+
+impl DataKey {
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes).map_err(|_| anyhow!("Data key must be 32 bytes"))?;
+        Ok(DataKey(LessSafeKey::new(key)))
+    }
+
+    fn generate_bytes() -> Result<[u8; 32]> {
+        let mut bytes = [0u8; 32];
+        SystemRandom::new().fill(&mut bytes).map_err(|_| anyhow!("Failed to generate data key"))?;
+        Ok(bytes)
+    }
+
+    pub fn load_or_generate(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create key directory: {}", dir.display()))?;
+        let path = dir.join(DATA_KEY_FILE);
+        if path.exists() {
+            let text = fs::read_to_string(&path).with_context(|| format!("Failed to read key file: {}", path.display()))?;
+            let bytes = hex::decode(text.trim()).with_context(|| format!("Invalid data key file: {}", path.display()))?;
+            return Self::from_bytes(&bytes);
+        }
+        let bytes = Self::generate_bytes()?;
+        write_private_key(&path, &hex::encode(bytes))?;
+        tracing::info!("Generated new data encryption key {}", path.display());
+        Self::from_bytes(&bytes)
+    }
+
+    // 메모리에만 존재하는 키(통합 테스트 등에서 사용)
+    pub fn ephemeral() -> Result<Self> {
+        Self::from_bytes(&Self::generate_bytes()?)
+    }
+
+    // context는 값이 저장되는 위치(예: 사용자 id)로, 다른 행에 복사한 값은 복호화되지 않음
+    pub fn encrypt(&self, plaintext: &str, context: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| anyhow!("Failed to generate nonce"))?;
+        let mut data = plaintext.as_bytes().to_vec();
+        self.0.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(context), &mut data)
+            .map_err(|_| anyhow!("Failed to encrypt value"))?;
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode([nonce.as_slice(), &data].concat())))
+    }
+
+    // 접두사가 없는 값(암호화 도입 이전)은 그대로 반환
+    pub fn decrypt(&self, value: &str, context: &str) -> Result<String> {
+        let encoded = match value.strip_prefix(ENCRYPTED_PREFIX) {
+            Some(encoded) => encoded,
+            None => return Ok(value.to_string()),
+        };
+        let mut data = STANDARD.decode(encoded).context("Invalid encrypted value")?;
+        if data.len()<NONCE_LEN {
+            return Err(anyhow!("Invalid encrypted value"));
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[..NONCE_LEN]);
+        let plaintext = self.0.open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(context), &mut data[NONCE_LEN..])
+            .map_err(|_| anyhow!("Failed to decrypt value (wrong data key?)"))?;
+        Ok(String::from_utf8(plaintext.to_vec())?)
+    }
+}
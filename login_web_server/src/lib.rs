@@ -0,0 +1,31 @@
+pub mod config; // src/config.rs 사용
+pub mod auth;   // src/auth.rs 사용
+pub mod error;  // src/error.rs 사용
+pub mod routes; // src/routes 모듈 import
+pub mod middleware; // src/middleware 모듈 import
+pub mod generator;  // src/generator 모듈 import
+pub mod refresh_token;  // src/refresh_token.rs 사용
+pub mod revocation; // src/revocation.rs 사용
+pub mod session;    // src/session.rs 사용
+pub mod lockout;    // src/lockout.rs 사용
+pub mod rate_limit; // src/rate_limit.rs 사용
+pub mod password_policy;    // src/password_policy.rs 사용
+pub mod password_hasher;    // src/password_hasher.rs 사용
+pub mod opaque_token;   // src/opaque_token.rs 사용
+pub mod mailer; // src/mailer.rs 사용
+pub mod password_reset; // src/password_reset.rs 사용
+pub mod totp;   // src/totp.rs 사용
+pub mod mfa;    // src/mfa.rs 사용
+pub mod rbac;   // src/rbac.rs 사용
+pub mod keys;   // src/keys.rs 사용
+pub mod oauth;  // src/oauth.rs 사용
+pub mod email_verification; // src/email_verification.rs 사용
+pub mod audit;  // src/audit.rs 사용
+pub mod metrics;    // src/metrics.rs 사용
+pub mod scheduler;  // src/scheduler.rs 사용
+pub mod maintenance;    // src/maintenance.rs 사용
+pub mod repository; // src/repository 모듈 import
+pub mod todo;   // src/todo.rs 사용
+pub mod recurrence; // src/recurrence.rs 사용
+pub mod reminder;   // src/reminder.rs 사용
+
@@ -0,0 +1,70 @@
+use crate::repository::LockoutRepository;
+
+// 잠금이 시작되는 연속 실패 횟수(사용자 이름 기준 / 클라이언트 IP 기준)
+// IP는 여러 사용자가 공유할 수 있으므로(NAT 등) 더 높게 설정
+pub const USERNAME_FAILURE_THRESHOLD: i64 = 5;
+pub const IP_FAILURE_THRESHOLD: i64 = 20;
+
+// 첫 잠금 시간(초), 이후 실패할 때마다 2배씩 증가(지수 백오프)하되 최대 1시간
+const BASE_LOCKOUT_SECS: i64 = 30;
+const MAX_LOCKOUT_SECS: i64 = 3600;
+
+// 마지막 실패 이후 이 시간(초)이 지나면 실패 횟수를 초기화
+const FAILURE_WINDOW_SECS: i64 = 15 * 60;
+
+// 실패 기록 키(login_attempts.key)
+pub fn username_key(username: &str) -> String {
+    format!("user:{}", username)
+}
+
+pub fn ip_key(ip: &str) -> String {
+    format!("ip:{}", ip)
+}
+
+// 실패 횟수에 따른 잠금 시간 계산(threshold 도달 시 BASE, 이후 2배씩)
+fn lockout_secs(failures: i64, threshold: i64) -> Option<i64> {
+    if failures<threshold {
+        return None;
+    }
+    let exponent = (failures-threshold).min(16) as u32;   // 오버플로 방지
+    Some((BASE_LOCKOUT_SECS << exponent).min(MAX_LOCKOUT_SECS))
+}
+
+// 주어진 키 중 잠겨 있는 것이 있으면 남은 잠금 시간(초) 중 최댓값 반환
+pub async fn check(repo: &dyn LockoutRepository, keys: &[String]) -> Result<Option<u64>, sqlx::Error> {
+    let mut retry_after: Option<u64> = None;
+    for key in keys {
+        if let Some(remaining) = repo.lock_remaining_secs(key).await? {
+            let remaining = remaining.max(1) as u64;
+            retry_after = Some(retry_after.map_or(remaining, |r| r.max(remaining)));
+        }
+    }
+    Ok(retry_after)
+}
+
+// 로그인 실패 기록 후 잠금이 걸렸다면 잠금 시간(초) 반환
+pub async fn record_failure(repo: &dyn LockoutRepository, key: &str, threshold: i64) -> Result<Option<u64>, sqlx::Error> {
+    // 실패 기록이 없거나 마지막 실패가 오래되었으면 1부터 다시 계산
+    let failures = repo.increment_login_failures(key, FAILURE_WINDOW_SECS).await?;
+    let locked = lockout_secs(failures, threshold);
+    if let Some(secs) = locked {
+        repo.lock_login(key, secs).await?;
+    }
+    Ok(locked.map(|secs| secs as u64))
+}
+
+// 로그인 성공 시 실패 기록 초기화
+pub async fn record_success(repo: &dyn LockoutRepository, key: &str) -> Result<(), sqlx::Error> {
+    repo.clear_login_failures(key).await?;
+    Ok(())
+}
+
+// 관리자에 의한 계정 잠금 해제, 실패 기록이 있었으면 true
+pub async fn unlock(repo: &dyn LockoutRepository, username: &str) -> Result<bool, sqlx::Error> {
+    repo.clear_login_failures(&username_key(username)).await
+}
+
+// 잠금이 끝났고 실패 집계 기간도 지난 기록 삭제
+pub async fn prune_expired(repo: &dyn LockoutRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_login_attempts(FAILURE_WINDOW_SECS).await
+}
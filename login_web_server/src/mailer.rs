@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::config::MailConfig;
+
+// 발송할 메일
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+// 메일 발송 방식(SMTP, 외부 API 등으로 교체 가능)
+// 핸들러에서는 web::Data<dyn Mailer>로 주입받아 사용
+pub trait Mailer: Send + Sync {
+    fn send(&self, email: &Email) -> Result<()>;
+}
+
+// 표준 출력으로 메일 내용을 출력(개발용 기본값)
+pub struct StdoutMailer;
+
+impl Mailer for StdoutMailer {
+    fn send(&self, email: &Email) -> Result<()> {
+        println!("---- mail to: {} ----\nSubject: {}\n\n{}\n----", email.to, email.subject, email.body);
+        Ok(())
+    }
+}
+
+// 메일 한 통을 디렉터리에 .eml 파일 하나로 저장(오프라인 테스트용)
+pub struct FileMailer {
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create mail directory: {}", dir.display()))?;
+        Ok(FileMailer { dir })
+    }
+}
+
+impl Mailer for FileMailer {
+    fn send(&self, email: &Email) -> Result<()> {
+        // 파일 이름이 겹치지 않도록 나노초 타임스탬프와 난수 사용
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+        let path = self.dir.join(format!("{}-{:08x}.eml", nanos, rand::random::<u32>()));
+        let content = format!("To: {}\r\nSubject: {}\r\n\r\n{}\r\n", email.to, email.subject, email.body);
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write mail file: {}", path.display()))
+    }
+}
+
+// 메일 디렉터리(mail.dir, MAILER_DIR)가 설정되어 있으면 FileMailer, 없으면 StdoutMailer
+pub fn from_config(config: &MailConfig) -> Result<Arc<dyn Mailer>> {
+    match &config.dir {
+        Some(dir) => Ok(Arc::new(FileMailer::new(dir)?)),
+        None => Ok(Arc::new(StdoutMailer)),
+    }
+}
@@ -1,91 +1,113 @@
-mod auth;   // src/auth.rs 사용
-mod routes; // src/routes 모듈 import
-mod middleware; // src/middleware 모듈 import
-mod generator;  // src/generator 모듈 import
-
 use actix_web::{web, App, HttpServer};
 use actix_cors::Cors;
 use anyhow::{self, Result};
-use sqlx::{migrate::Migrator, SqlitePool};
 use dotenv::dotenv;
-use std::env;
-
-// Denylist 상태 관리를 위한 모듈
-use std::sync::{Arc, Mutex};
-use std::collections::HashSet;  // 무효화 토큰 및 사용자 이름 저장
-
-// bcrypt 자가시험을 위한 모듈
-use bcrypt::{hash_with_salt, Version::TwoB};
+use std::time::Duration;
+use web_tracing::RequestTracing;
 
-// sqlx 마이그레이터 정의
-// 컴파일 타임에 ./migrations 폴더를 읽음
-static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
-
-// Denylist 타입 정의
-// Mutex로 인해 여러 스레드에서 안전하게 접근 가능, Arc로 인해 객체를 여러 곳에서 공유 가능
-#[derive(Debug)]
-pub struct Denylist(Mutex<HashSet<String>>);
+// 라이브러리 크레이트(src/lib.rs)에 정의된 라우트, 저장소, 토큰 폐기 모듈 사용
+use login_web_server::{auth, config::{self, Config}, keys::{DataKey, KeySet}, mfa, password_hasher::{self, Algorithm}, routes, mailer, maintenance, reminder, scheduler::Scheduler, password_policy::PasswordPolicy, rbac, metrics::MetricsAccess, middleware::request_metrics::RequestMetrics, repository::{self, Repository}};
 
 #[actix_web::main]
 async fn main() -> Result<()> {
     dotenv().ok();  // .env 파일 읽고 환경 변수로 로드
-    bcrypt_self_test().map_err(|e| e)?; // bcrypt 자가시험 실행 후 에러 시 서버 즉시 중단
-    println!("Starting server...");
-    // DB url 정의
-    let db_url = env::var("DATABASE_URL").expect("DATABASE_URL not set in .env or environment...");
-    // DB 연결 풀 생성: db_url 경로의 sqlite 파일을 찾거나 새로 만들고 연결 풀 생성
-        // await로 비동기 완료 대기
-    let pool = SqlitePool::connect(&db_url).await?;
-    println!("DB connection successful!");
+    // 설정 로드: --config로 지정한 TOML 파일(없으면 ./login_web_server.toml) -> 환경 변수 재정의 -> 검증
+    // 잘못된 항목이 있으면 모든 항목을 보고한 뒤 서버 시작 중단
+    let config_path = config::path_from_args(std::env::args().skip(1))?;
+    let config = Config::load(config_path.as_deref())?;
+    config::init(config.clone());
+    // 로그 설정(logging.level, logging.format) 적용, 이후 로그는 tracing으로 출력
+    web_tracing::init(&config.logging)?;
+    password_hash_self_test(&config)?; // 비밀번호 해싱 자가시험 실행 후 에러 시 서버 즉시 중단
+    // JWT 서명 키 로드(tokens.key_dir의 *.pem, 키가 없으면 Ed25519 키 생성)
+    // tokens.signing_kid로 서명 키를 지정하지 않으면 kid 정렬 기준 마지막 키로 서명
+    let tokens = &config.tokens;
+    let keys = KeySet::load_or_generate(&tokens.key_dir, tokens.signing_kid.as_deref())?;
+    tracing::info!("JWT signing key: {} ({:?})", keys.signing_key().kid, keys.signing_key().algorithm);
+    auth::init_keys(keys);
+    // DB에 저장하는 TOTP 비밀 키의 암호화 키(tokens.key_dir의 data.key, 없으면 생성)
+    mfa::init_data_key(DataKey::load_or_generate(&tokens.key_dir)?);
+    tracing::info!("JWT issuer: {}, audience: {}, leeway: {}s, access token TTL: {}s", tokens.issuer, tokens.audience, tokens.leeway_secs, tokens.access_ttl_secs);
+    tracing::info!("Starting server...");
+    // 저장소(DB 연결 풀) 생성: database.url의 scheme(sqlite:, postgres://)으로 DB 종류 선택
+        // sqlite는 database.url 경로의 파일을 찾거나 새로 만들고 연결 풀 생성
+    let repo = repository::connect(&config.database.url).await?;
+    tracing::info!("DB connection successful! ({})", repo.backend().as_str());
     
     // Execute Migration
-        // sqlx_migrations 테이블 확인 후 적용되지 않은 마이그레이션 스크립트 실행
-    MIGRATOR.run(&pool).await?;
+        // sqlx_migrations 테이블 확인 후 DB 종류별(migrations/sqlite, migrations/postgres) 미적용 마이그레이션 스크립트 실행
+    repo.migrate().await?;
     
-    // 객체 생성
-    let denylist = Arc::new(Denylist(Mutex::new(HashSet::new())));
-    let moved_denylist = denylist.clone();  // 복제하여 핸들러나 미들웨어에서 web::Data<Arc<Denylist>> 형태로 접근 가능
+    // admin.usernames(ADMIN_USERNAMES, 쉼표 구분)로 지정한 기존 사용자에게 admin 역할 부여
+    // 이후 관리자는 관리 API(/api/admin/users/{username}/roles/{role})로 역할 관리
+    let granted = rbac::bootstrap_admins(repo.as_ref(), &config.admin.usernames).await?;
+    tracing::info!("Granted admin role to {} users from admin.usernames", granted);
+    // 비밀번호 정책(password_policy 섹션, 유출 목록 파일 로드 실패 시 서버 시작 중단)
+    let password_policy = web::Data::new(PasswordPolicy::from_config(&config.password_policy)?);
+    // 메일 발송 방식(mail.dir 설정 시 해당 디렉터리에 .eml 파일 저장, 미설정 시 표준 출력)
+    let mailer: web::Data<dyn mailer::Mailer> = web::Data::from(mailer::from_config(&config.mail)?);
+    // GET /metrics 접근 제어(metrics.bearer_token)
+    let metrics_access = web::Data::new(MetricsAccess::from_config(&config.metrics));
+    // todo 알림 이벤트 전달 방식(notifications.dir 설정 시 해당 디렉터리에 .json 파일 저장, 미설정 시 로그 출력)
+    let notifier = reminder::from_config(&config.notifications)?;
+    // 주기 작업 시작(시작 시 한 번 실행 후 반복)
+    // 만료된 데이터 정리는 jobs.cleanup_interval_secs, todo 알림 기록 및 전달은 jobs.reminder_interval_secs마다
+    let cleanup_interval = Duration::from_secs(config.jobs.cleanup_interval_secs);
+    let scheduler = maintenance::register(Scheduler::new(), &repo, cleanup_interval);
+    let reminder_interval = Duration::from_secs(config.jobs.reminder_interval_secs);
+    let jobs = reminder::register(scheduler, &repo, &notifier, reminder_interval).start();
     
     // HTTP 서버 생성 및 구동
-    println!("Starting HTTP server at 127.0.0.1:8080");
-    HttpServer::new(move || {
+    let cors_allowed_origins = config.server.cors_allowed_origins.clone();
+    tracing::info!("Starting HTTP server at {} (CORS allowed origins: {})", config.server.bind,
+        if cors_allowed_origins.is_empty() { "none".to_string() } else { cors_allowed_origins.join(", ") });
+    let app_repo: web::Data<dyn Repository> = web::Data::from(repo.clone());
+    let server = HttpServer::new(move || {
         // Cors 미들웨어 설정
-        let cors = Cors::default()
-            .allow_any_origin() // 어떤 출처로부터 오는 요청이든 허용
+        let mut cors = Cors::default()
             .allow_any_method() // 어떤 메서드든 허용
             .allow_any_header() // 어떤 헤더든 허용
+            .expose_headers([web_tracing::REQUEST_ID_HEADER]) // 브라우저에서 응답의 X-Request-Id를 읽을 수 있도록 노출
             .max_age(3600); // Cors 사전 요청(Preflight Request) 결과 캐싱 시간 설정
+        // server.cors_allowed_origins에 지정한 출처만 허용("*"는 모든 출처 허용)
+        for origin in &cors_allowed_origins {
+            cors = if origin=="*" { cors.allow_any_origin() } else { cors.allowed_origin(origin) };
+        }
         App::new()
             .wrap(cors) // 보통 cors 미들웨어를 타 미들웨어보다 먼저 적용
-            // app_data를 통해 핸들러 함수에서 web::Data<SqlitePool>로 접근 가능
-            .app_data(web::Data::new(pool.clone())) // 풀을 복제하여 App 인스턴스마다 풀 공유
-            .app_data(web::Data::new(moved_denylist.clone()))   // Denylist 공유
+            // app_data를 통해 핸들러 함수에서 web::Data<dyn Repository>로 접근 가능
+            .app_data(app_repo.clone()) // 저장소(연결 풀)를 App 인스턴스마다 공유
+            .app_data(password_policy.clone())  // 비밀번호 정책 공유
+            .app_data(mailer.clone())   // 메일 발송기 공유
+            .app_data(metrics_access.clone())   // 메트릭 엔드포인트 접근 제어 공유
             .configure(routes::init)    // routes 모듈의 init 함수를 호출하여 라우트 및 서비스 설정
-    }).bind("127.0.0.1:8080")?.run().await?;
-    
+            .wrap(RequestMetrics)   // 경로별 요청 수 및 처리 시간 메트릭
+            // 요청 추적(X-Request-Id, 요청별 span 및 완료 로그), CORS 거부 등 다른 미들웨어의 응답도 기록하도록 가장 바깥쪽에 등록
+            .wrap(RequestTracing)
+    })
+    // 종료 신호(SIGINT/SIGTERM) 수신 시 새 연결을 받지 않고 처리 중인 요청 완료를 기다린 뒤(최대 shutdown_timeout_secs) 종료
+    .shutdown_timeout(config.server.shutdown_timeout_secs)
+    .bind(&config.server.bind)?.run();
+
+    let result = server.await;
+    // HTTP 서버가 멈춘 뒤(에러 포함) 실행 중인 작업이 끝나기를 기다렸다가 작업 중지 및 DB 연결 종료
+    tracing::info!("HTTP server stopped, stopping background jobs...");
+    jobs.shutdown().await;
+    repo.close().await;
+    result?;
     Ok(())
 }
 
-fn bcrypt_self_test() -> Result<()> {
-    println!("Running bcrypt self-test...");
-    let test_pw = env::var("BCRYPT_TEST_PASSWORD").expect("BCRYPT_TEST_PASSWORD not set in .env");
-    let test_fixed_salt = env::var("BCRYPT_TEST_FIXED_SALT")
-        .expect("BCRYPT_TEST_FIXED_SALT not set in .env");
-    let expected_hash = env::var("BCRYPT_EXPECTED_HASH").expect("BCRYPT_EXPECTED_HASH not set in .env");
-    let test_fixed_salt = test_fixed_salt.as_bytes();
-    match hash_with_salt(&test_pw, 10, test_fixed_salt) {
-        Ok(result) => {
-            if result.format_for_version(TwoB)==expected_hash {
-                println!("bcrypt self-test done!");
-                Ok(())
-            } else {
-                let error_msg = "bcrypt self-test failed... Aborting server start...";
-                return Err(anyhow::anyhow!("{}", error_msg))
-            }
-        }
-        Err(_) => {
-            let error_msg = "bcrypt self-test failed during hashing... Aborting server start...";
-            return Err(anyhow::anyhow!("{}", error_msg))
-        }
-    }
-}
\ No newline at end of file
+// 지원하는 모든 해싱 알고리즘의 왕복 검증과, 기대값이 설정된 알고리즘(bcrypt 필수, argon2id 사용 시 필수)의 고정 솔트 해시 비교
+fn password_hash_self_test(config: &Config) -> Result<()> {
+    let hashers = password_hasher::hashers();
+    tracing::info!("Running password hashing self-test (new hashes use {})...", hashers.algorithm().as_str());
+    let vectors: Vec<_> = [(Algorithm::Bcrypt, &config.bcrypt.self_test), (Algorithm::Argon2id, &config.argon2.self_test)]
+        .into_iter()
+        .filter_map(|(algorithm, self_test)| self_test.as_ref().map(|t| (algorithm, t)))
+        .collect();
+    hashers.self_test(&vectors)
+        .map_err(|e| anyhow::anyhow!("{}... Aborting server start...", e))?;
+    tracing::info!("Password hashing self-test done!");
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::repository::Repository;
+use crate::scheduler::Scheduler;
+use crate::{email_verification, lockout, oauth, password_reset, rate_limit, reminder, revocation};
+
+// 만료된 데이터 정리 작업
+// 서버 시작 시(서버가 내려가 있는 동안 만료된 데이터) 한 번 실행한 뒤 jobs.cleanup_interval_secs마다 반복
+
+// 정리 함수를 작업으로 등록(결과 요약: 삭제한 행 수)
+fn prune<F, Fut>(scheduler: Scheduler, name: &'static str, what: &'static str, interval: Duration, repo: &Arc<dyn Repository>, prune: F) -> Scheduler
+where
+    F: Fn(Arc<dyn Repository>) -> Fut+'static,
+    Fut: Future<Output=Result<u64, sqlx::Error>>+'static,
+{
+    let repo = repo.clone();
+    scheduler.every(name, interval, move || {
+        let pruned = prune(repo.clone());
+        async move { Ok(format!("pruned {} {}", pruned.await?, what)) }
+    })
+}
+
+pub fn register(scheduler: Scheduler, repo: &Arc<dyn Repository>, interval: Duration) -> Scheduler {
+    // 토큰 폐기 기록(토큰 만료 이후 불필요)
+    let scheduler = prune(scheduler, "prune_revoked_tokens", "expired token revocations", interval, repo,
+        |repo| async move { revocation::prune_expired(repo.as_ref()).await });
+    // 잠금 및 실패 집계 기간이 끝난 로그인 실패 기록
+    let scheduler = prune(scheduler, "prune_login_attempts", "expired login attempt records", interval, repo,
+        |repo| async move { lockout::prune_expired(repo.as_ref()).await });
+    // 기간이 끝난 요청 횟수 제한 기록
+    let scheduler = prune(scheduler, "prune_rate_limits", "expired rate limit records", interval, repo,
+        |repo| async move { rate_limit::prune_expired(repo.as_ref()).await });
+    // 사용했거나 만료된 비밀번호 재설정 토큰
+    let scheduler = prune(scheduler, "prune_password_resets", "used or expired password reset tokens", interval, repo,
+        |repo| async move { password_reset::prune_expired(repo.as_ref()).await });
+    // 만료된 이메일 인증 토큰
+    let scheduler = prune(scheduler, "prune_email_verifications", "expired email verification tokens", interval, repo,
+        |repo| async move { email_verification::prune_expired(repo.as_ref()).await });
+    // 사용했거나 만료된 OAuth 인가 코드
+    let scheduler = prune(scheduler, "prune_authorization_codes", "used or expired OAuth authorization codes", interval, repo,
+        |repo| async move { oauth::prune_expired(repo.as_ref()).await });
+    // 전달한 지 보관 기간이 지난 todo 이벤트
+    prune(scheduler, "prune_todo_events", "delivered todo events", interval, repo,
+        |repo| async move { reminder::prune_delivered(repo.as_ref()).await })
+}
@@ -0,0 +1,107 @@
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::sync::OnceLock;
+use std::time::Instant;
+use crate::config::MetricsConfig;
+use crate::repository::Repository;
+use crate::{opaque_token, revocation};
+
+// Prometheus 메트릭(GET /metrics에서 텍스트 형식으로 노출)
+// 요청 수와 처리 시간은 RequestMetrics 미들웨어가, 로그인 결과는 감사 로그 기록 시 집계하고
+// 폐기된 토큰 수와 DB 연결 풀 상태는 수집 요청마다 조회
+
+pub struct Metrics {
+    registry: Registry,
+    // http_requests_total{method, route, status}(route는 경로 패턴, 예: /api/todos/{id})
+    pub http_requests: IntCounterVec,
+    // http_request_duration_seconds{method, route}
+    pub http_request_duration: HistogramVec,
+    // auth_login_attempts_total{event, outcome, reason}(event: login, login_mfa / reason: 실패 시 에러 code)
+    pub login_attempts: IntCounterVec,
+    // password_hash_duration_seconds{algorithm, operation}(operation: hash, verify)
+    pub password_hash_duration: HistogramVec,
+    // auth_revoked_tokens(만료되지 않은 폐기 토큰 수)
+    revoked_tokens: IntGauge,
+    // db_pool_connections{state}(state: idle, in_use)
+    db_pool_connections: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Number of HTTP requests by route and status"),
+            &["method", "route", "status"])?;
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route"),
+            &["method", "route"])?;
+        let login_attempts = IntCounterVec::new(
+            Opts::new("auth_login_attempts_total", "Number of login attempts by outcome"),
+            &["event", "outcome", "reason"])?;
+        let password_hash_duration = HistogramVec::new(
+            HistogramOpts::new("password_hash_duration_seconds", "Time spent hashing and verifying passwords"),
+            &["algorithm", "operation"])?;
+        let revoked_tokens = IntGauge::new("auth_revoked_tokens", "Number of revoked access tokens that have not expired yet")?;
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database connection pool connections by state"),
+            &["state"])?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+        registry.register(Box::new(login_attempts.clone()))?;
+        registry.register(Box::new(password_hash_duration.clone()))?;
+        registry.register(Box::new(revoked_tokens.clone()))?;
+        registry.register(Box::new(db_pool_connections.clone()))?;
+        Ok(Metrics { registry, http_requests, http_request_duration, login_attempts, password_hash_duration, revoked_tokens, db_pool_connections })
+    }
+
+    // 비밀번호 해싱 및 검증 시간 기록
+    pub fn observe_password_hash(&self, algorithm: &str, operation: &str, started: Instant) {
+        self.password_hash_duration.with_label_values(&[algorithm, operation]).observe(started.elapsed().as_secs_f64());
+    }
+
+    // 조회가 필요한 값을 갱신한 뒤 Prometheus 텍스트 형식으로 출력
+    pub async fn render(&self, repo: &dyn Repository) -> anyhow::Result<String> {
+        self.revoked_tokens.set(revocation::count_active(repo).await?);
+        let (size, idle) = repo.connection_counts();
+        let (size, idle) = (size as i64, idle as i64);
+        self.db_pool_connections.with_label_values(&["idle"]).set(idle);
+        self.db_pool_connections.with_label_values(&["in_use"]).set(size-idle);
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+// 서버 전체에서 공유하는 메트릭(메트릭 이름이 고정이므로 생성 실패 시 panic)
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("invalid metric definitions"))
+}
+
+// GET /metrics 접근 제어
+// metrics.bearer_token을 설정하면 사용자 토큰(JWT)과 별개인 이 토큰이 있어야 수집 가능(미설정 시 공개)
+#[derive(Clone, Default)]
+pub struct MetricsAccess {
+    token_hash: Option<String>,
+}
+
+impl MetricsAccess {
+    pub fn new(bearer_token: Option<&str>) -> Self {
+        MetricsAccess { token_hash: bearer_token.map(opaque_token::hash) }
+    }
+
+    pub fn from_config(config: &MetricsConfig) -> Self {
+        MetricsAccess::new(config.bearer_token.as_deref())
+    }
+
+    // Authorization 헤더 값 확인(해시끼리 비교하여 비교 시간으로 토큰이 드러나지 않도록 함)
+    pub fn is_allowed(&self, authorization: Option<&str>) -> bool {
+        match &self.token_hash {
+            None => true,
+            Some(expected) => authorization.and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|token| opaque_token::hash(token)==*expected),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{opaque_token, totp};
+use crate::keys::DataKey;
+use crate::repository::MfaRepository;
+
+// 확인 완료 시 발급하는 복구 코드 개수와 길이(바이트, hex 인코딩 시 2배)
+// 솔트 없는 SHA-256으로 저장하므로 전수 조사가 불가능하도록 80비트 이상 사용
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_BYTES: usize = 10;
+
+// TOTP 비밀 키는 DataKey로 암호화하여 저장(DB만 유출되어서는 코드를 만들 수 없도록)
+static DATA_KEY: OnceLock<DataKey> = OnceLock::new();
+
+pub fn init_data_key(key: DataKey) {
+    let _ = DATA_KEY.set(key);
+}
+
+fn data_key() -> Result<&'static DataKey, sqlx::Error> {
+    DATA_KEY.get().ok_or_else(|| sqlx::Error::Configuration("Data encryption key is not initialized".into()))
+}
+
+// 같은 값을 다른 사용자 행에 복사해도 복호화되지 않도록 사용자 id를 함께 인증
+fn secret_context(user_id: i64) -> String {
+    format!("user_totp:{}", user_id)
+}
+
+fn encrypt_secret(user_id: i64, secret: &str) -> Result<String, sqlx::Error> {
+    data_key()?.encrypt(secret, &secret_context(user_id)).map_err(|e| sqlx::Error::Protocol(e.to_string()))
+}
+
+fn decrypt_secret(user_id: i64, stored: Option<String>) -> Result<Option<String>, sqlx::Error> {
+    stored.map(|stored| data_key()?.decrypt(&stored, &secret_context(user_id)).map_err(|e| sqlx::Error::Decode(e.into())))
+        .transpose()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+// 복구 코드는 "xxxxxxxxxx-xxxxxxxxxx" 형식으로 전달하되, 입력 시 하이픈/공백/대소문자는 무시
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase()
+}
+
+fn new_recovery_code() -> String {
+    let code = opaque_token::generate(RECOVERY_CODE_BYTES);
+    let (head, tail) = code.split_at(code.len()/2);
+    format!("{}-{}", head, tail)
+}
+
+// 2단계 인증이 활성화된 사용자인지 확인
+pub async fn is_enabled(repo: &dyn MfaRepository, user_id: i64) -> Result<bool, sqlx::Error> {
+    repo.totp_enabled(user_id).await
+}
+
+// TOTP 등록 시작: 새 비밀 키를 저장(확인 전 상태)하고 반환
+// 이미 활성화되어 있으면 None(기존 설정을 덮어쓰지 않도록 먼저 해제해야 함)
+pub async fn begin_enrollment(repo: &dyn MfaRepository, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+    let secret = totp::generate_secret();
+    // 확인 전 상태의 이전 등록 시도는 새 비밀 키로 교체
+    if !repo.begin_totp_enrollment(user_id, &encrypt_secret(user_id, &secret)?).await? {
+        return Ok(None);
+    }
+    Ok(Some(secret))
+}
+
+// 인증 앱에 표시된 코드로 등록 확인 후 활성화하고 복구 코드 원문 반환
+// 등록 중인 비밀 키가 없거나 코드가 틀리면 None
+pub async fn confirm_enrollment(repo: &dyn MfaRepository, user_id: i64, code: &str) -> Result<Option<Vec<String>>, sqlx::Error> {
+    let secret = match decrypt_secret(user_id, repo.pending_totp_secret(user_id).await?)? {
+        Some(secret) => secret,
+        None => return Ok(None),
+    };
+    let step = match totp::verify(&secret, code, unix_now()) {
+        Some(step) => step,
+        None => return Ok(None),
+    };
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| new_recovery_code()).collect();
+    let hashes: Vec<String> = codes.iter().map(|code| opaque_token::hash(&normalize_recovery_code(code))).collect();
+    repo.enable_totp(user_id, step as i64, &hashes).await?;
+    Ok(Some(codes))
+}
+
+// 로그인 2단계 검증: TOTP 코드 또는 사용하지 않은 복구 코드이면 true
+// 한 번 사용된 TOTP 코드(같은 시간 단계 이하)와 복구 코드는 다시 사용할 수 없음
+pub async fn verify(repo: &dyn MfaRepository, user_id: i64, code: &str) -> Result<bool, sqlx::Error> {
+    let secret = match decrypt_secret(user_id, repo.active_totp_secret(user_id).await?)? {
+        Some(secret) => secret,
+        None => return Ok(false),
+    };
+
+    if let Some(step) = totp::verify(&secret, code, unix_now()) {
+        // 이전에 사용된 단계보다 새로운 코드일 때만 갱신(동시 요청에서도 한 번만 성공)
+        return repo.advance_totp_step(user_id, step as i64).await;
+    }
+
+    repo.use_recovery_code(user_id, &opaque_token::hash(&normalize_recovery_code(code))).await
+}
+
+// 남은(사용하지 않은) 복구 코드 수
+pub async fn remaining_recovery_codes(repo: &dyn MfaRepository, user_id: i64) -> Result<i64, sqlx::Error> {
+    repo.remaining_recovery_codes(user_id).await
+}
+
+// 2단계 인증 해제(비밀 키와 복구 코드 삭제)
+pub async fn disable(repo: &dyn MfaRepository, user_id: i64) -> Result<(), sqlx::Error> {
+    repo.disable_totp(user_id).await
+}
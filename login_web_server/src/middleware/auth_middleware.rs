@@ -1,15 +1,16 @@
 use actix_web::{
-    HttpMessage, HttpResponse,  // HttpMessage 트레이트(extensions_mut 사용)
+    HttpMessage, ResponseError,  // HttpMessage 트레이트(extensions_mut 사용), ResponseError 트레이트(ApiError 응답 변환)
     body::BoxBody,  // 응답 본문 타입(미들웨어 응답 본문 통일)
     // dev::* => actix-web 개발 관련 모듈
     dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error
 };
 use std::rc::Rc;    // Rc(Reference Counting): 다음 서비스를 여러 Service 구현체에서 공유
 use futures_util::future::{ready, Ready};   // 비동기 Future 타입 - Service 구현에 사용
-use crate::auth::decode_jwt;
+use crate::auth::{decode_jwt, MfaPendingToken};
+use crate::{revocation, session};
+use crate::error::ApiError;
 
-use std::sync::Arc;
-use crate::Denylist;
+use crate::repository::Repository;
 use actix_web::web::Data;
 
 // Middleware Factory 구조체(Transform 트레이트 구현)
@@ -33,7 +34,27 @@ where
     // Transform 구현체(AuthMiddlewareService)를 비동기적으로 생성
     fn new_transform(&self, service: S) -> Self::Future {
         // 다음 서비스 객체를 Rc로 감싸서 AuthMiddlewareService의 service 필드에 저장
-        ready(Ok(AuthMiddlewareService { service: Rc::new(service) }))
+        ready(Ok(AuthMiddlewareService { service: Rc::new(service), accept_scoped: false }))
+    }
+}
+
+// OAuth 클라이언트에 발급한 scope 토큰도 허용하는 AuthMiddleware(/oauth/userinfo 전용)
+// 핸들러는 AuthContext::has_scope로 승인된 scope 확인
+pub struct OAuthMiddleware;
+
+impl<S> Transform<S, ServiceRequest> for OAuthMiddleware
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<BoxBody>, Error=Error>+'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Transform = AuthMiddlewareService<S>;
+    type Error = Error;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AuthMiddlewareService { service: Rc::new(service), accept_scoped: true }))
     }
 }
 
@@ -41,6 +62,8 @@ where
 pub struct AuthMiddlewareService<S> {
     // 다음 서비스(핸들러 함수 또는 그 이후의 미들웨어 체인)
     service: Rc<S>,
+    // OAuth 클라이언트 토큰(scope 클레임) 허용 여부
+    accept_scoped: bool,
 }
 
 // AuthMiddlewareService 구조체에 대해 Service 트레이트 구현
@@ -64,51 +87,79 @@ where
     // 요청 처리 로직(비동기 함수)
     fn call(&self, req: ServiceRequest) -> Self::Future {   // 요청 객체를 비동기 작업(Future)으로 반환
         let svc = self.service.clone(); // 다음 서비스 참조 복제(async move 블록 내에서 사용하기 위함)
+        let accept_scoped = self.accept_scoped;
         Box::pin(async move {   // 비동기 블록(impl Future)을 힙에 할당 후 Pin으로 고정하여 LocalBoxFuture 타입으로 변환
             let (request, payload) = req.into_parts();  // req 객체 분리 후 소유권 이동(request: 요청 정보, payload: 요청 본문 스트림)
             let temp = request.clone();
-            // App 데이터에서 Denylist 객체 가져오기
-            let denylist = match temp.app_data::<Data<Arc<Denylist>>>() {
-                Some(d) => d,
+            // App 데이터에서 저장소 가져오기(토큰 폐기 여부 확인용)
+            let repo = match temp.app_data::<Data<dyn Repository>>() {
+                Some(r) => r,
                 None => {
-                    // Denylist가 App data에 등록되지 않았다면 설정 오류
-                    let response = HttpResponse::InternalServerError().body("Server configuration Error...");
+                    // 저장소가 App data에 등록되지 않았다면 설정 오류
+                    let response = ApiError::internal("server_misconfigured", "Server configuration error.").error_response();
                     return Ok(ServiceResponse::new(request, response));
                 }
             };
             // Header에서 Authorization: Bearer <token> 추출
-            if let Some(auth_header) = temp.headers().get("Authorization") {
-                if let Ok(auth_str) = auth_header.to_str() {
-                    if let Some(token) = auth_str.strip_prefix("Bearer ") { // 접두사 제거
-                        // JWT 토큰 디코딩 및 검증
-                        match decode_jwt(token) {
-                            Ok(username) => {   // 토큰 유효 시
-                                // Mutex 락 획득 및 HashSet에 username 존재 확인
-                                // Mutex lock은 if 블록 이탈 시 해제
-                                if denylist.0.lock().unwrap().contains(&username) { // Denylist에 사용자 이름이 있을 경우
-                                    let response = HttpResponse::Unauthorized().body("Token is invalidated...");
-                                    return Ok(ServiceResponse::new(request, response));
-                                }
-                                // RequestExtensions에 username 저장
-                                // 핸들러 함수에서 req.extensions().get::<String>() 등으로 추출해 사용 가능
-                                request.extensions_mut().insert(username);
-                                // 다음 서비스로 요청 전달
-                                let original_req = ServiceRequest::from_parts(request, payload);    // 분리했던 요소들을 재결합해서 객체 생성
-                                return svc.call(original_req).await;    // 다음 서비스 호출 및 결과 대기
+            if let Some(auth_header) = temp.headers().get("Authorization")
+                && let Ok(auth_str) = auth_header.to_str()
+                && let Some(token) = auth_str.strip_prefix("Bearer ") { // 접두사 제거
+                // JWT 토큰 디코딩 및 검증
+                match decode_jwt(token) {
+                    Ok(auth) => {   // 토큰 유효 시
+                        // OAuth 클라이언트 토큰은 역할 및 권한이 없고 승인된 scope의 엔드포인트에서만 사용 가능
+                        if auth.is_client_token() && !accept_scoped {
+                            let response = ApiError::forbidden("insufficient_scope", "This token cannot be used for this API.").error_response();
+                            return Ok(ServiceResponse::new(request, response));
+                        }
+                        // 로그아웃 등으로 폐기된 토큰(jti)인지 확인
+                        match revocation::is_revoked(repo.get_ref(), &auth.token_id).await {
+                            Ok(false) => {}
+                            Ok(true) => {
+                                let response = ApiError::unauthorized("token_revoked", "Token has been revoked.").error_response();
+                                return Ok(ServiceResponse::new(request, response));
+                            }
+                            Err(e) => {
+                                let response = ApiError::from(e).error_response();
+                                return Ok(ServiceResponse::new(request, response));
                             }
-                            Err(_) => { // 토큰 검증 실패 시
-                                let response = HttpResponse::Unauthorized().body("Invalid token");
-                                // 해당 미들웨어의 Service 구현체는 Response = ServiceResponse<BoxBody>, HttpResponse<BoxBody>는 Into<actix_web::dev::Response<BoxBody>> 트레이트를 구현
-                                // 때문에 ServiceResponse 객체 생성 시 타입 추론 가능
+                        }
+                        // 토큰이 속한 세션이 유효한지 확인하고 마지막 사용 시각 갱신
+                        // 세션이 폐기되었거나 사용자 삭제로 사라졌다면 거부
+                        match session::touch(repo.get_ref(), &auth.session_id).await {
+                            Ok(true) => {}
+                            Ok(false) => {
+                                let response = ApiError::unauthorized("session_revoked", "Session has been revoked.").error_response();
+                                return Ok(ServiceResponse::new(request, response));
+                            }
+                            Err(e) => {
+                                let response = ApiError::from(e).error_response();
                                 return Ok(ServiceResponse::new(request, response));
-                                // return Ok(req.into_response(HttpResponse::Unauthorized().body("Invalid token").into()))
                             }
                         }
+                        // RequestExtensions에 인증 정보(사용자 id, 이름, 세션, 역할 등) 저장
+                        // 핸들러 함수에서 AuthContext 타입 인자로 추출해 사용 가능
+                        request.extensions_mut().insert(auth);
+                        // 다음 서비스로 요청 전달
+                        let original_req = ServiceRequest::from_parts(request, payload);    // 분리했던 요소들을 재결합해서 객체 생성
+                        return svc.call(original_req).await;    // 다음 서비스 호출 및 결과 대기
+                    }
+                    // 2단계 인증이 끝나지 않은 challenge 토큰은 API 접근에 사용할 수 없음
+                    Err(e) if e.is::<MfaPendingToken>() => {
+                        let response = ApiError::unauthorized("mfa_required", "Two-factor authentication required.").error_response();
+                        return Ok(ServiceResponse::new(request, response));
+                    }
+                    Err(_) => { // 토큰 검증 실패 시(서명, 발급자, 대상, 유효 기간 등)
+                        let response = ApiError::unauthorized("invalid_token", "Invalid or expired token.").error_response();
+                        // 해당 미들웨어의 Service 구현체는 Response = ServiceResponse<BoxBody>, HttpResponse<BoxBody>는 Into<actix_web::dev::Response<BoxBody>> 트레이트를 구현
+                        // 때문에 ServiceResponse 객체 생성 시 타입 추론 가능
+                        return Ok(ServiceResponse::new(request, response));
+                        // return Ok(req.into_response(HttpResponse::Unauthorized().body("Invalid token").into()))
                     }
                 }
             }
             // 인증 실패 시
-            let response = HttpResponse::Unauthorized().body("Missing or Invalid Authorization header");
+            let response = ApiError::unauthorized("missing_token", "Missing or invalid Authorization header.").error_response();
             Ok(ServiceResponse::new(request, response))
             // Ok(req.into_response(HttpResponse::Unauthorized().body("Missing or Invalid Authorization header").into()))
         })  // async move 블록의 끝(결과: Result<ServiceResponse<BoxBody>, Error>) 
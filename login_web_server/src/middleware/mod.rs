@@ -1 +1,3 @@
-pub mod auth_middleware;
\ No newline at end of file
+pub mod auth_middleware;
+pub mod rbac_guard;
+pub mod request_metrics;
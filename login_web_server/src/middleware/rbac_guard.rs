@@ -0,0 +1,72 @@
+use actix_web::{
+    HttpMessage, ResponseError,
+    body::BoxBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error
+};
+use std::rc::Rc;
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use crate::auth::{auth_context_missing, AuthContext};
+use crate::error::ApiError;
+
+// 권한 가드
+// AuthMiddleware가 RequestExtensions에 저장한 AuthContext의 권한을 확인하므로
+// 반드시 AuthMiddleware 안쪽에 등록해야 함(actix-web은 나중에 wrap한 미들웨어가 먼저 실행됨)
+//     web::resource(...).wrap(RequirePermission("users.read")).wrap(AuthMiddleware)
+// 역할 이름이 아닌 권한으로 검사하므로 admin 외의 역할에도 관리 기능 일부를 위임할 수 있음
+
+// 지정한 권한이 있어야 접근 가능
+pub struct RequirePermission(pub &'static str);
+
+impl<S> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<BoxBody>, Error=Error>+'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Transform = RequirePermissionService<S>;
+    type Error = Error;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequirePermissionService { service: Rc::new(service), permission: self.0 }))
+    }
+}
+
+pub struct RequirePermissionService<S> {
+    service: Rc<S>,
+    permission: &'static str,
+}
+
+impl<S> Service<ServiceRequest> for RequirePermissionService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<BoxBody>, Error=Error>+'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let svc = self.service.clone();
+        let permission = self.permission;
+        Box::pin(async move {
+            // extensions 참조는 다음 서비스 호출 전에 해제
+            let allowed = match req.extensions().get::<AuthContext>() {
+                Some(auth) => Ok(auth.has_permission(permission)),
+                None => Err(()),    // AuthMiddleware가 먼저 실행되지 않은 설정 오류
+            };
+            match allowed {
+                Ok(true) => svc.call(req).await,
+                Ok(false) => {
+                    let error = ApiError::forbidden("permission_required", format!("Permission '{}' is required.", permission));
+                    Ok(req.into_response(error.error_response()))
+                }
+                Err(()) => Ok(req.into_response(auth_context_missing().error_response())),
+            }
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceRequest, ServiceResponse, Transform}, Error
+};
+use std::rc::Rc;
+use std::time::Instant;
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use crate::metrics::metrics;
+
+// 요청 수 및 처리 시간 메트릭 미들웨어(App 단위로 등록)
+// route 라벨은 실제 경로 대신 경로 패턴(/api/todos/{id})을 사용하여 라벨 값 수를 제한하고,
+// 등록되지 않은 경로는 모두 "unmatched"로 집계
+pub struct RequestMetrics;
+
+// 경로 패턴을 알 수 없는 요청
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>+'static,
+    S::Future: 'static,
+    B: MessageBody+'static,
+{
+    type Response = ServiceResponse<B>;
+    type Transform = RequestMetricsService<S>;
+    type Error = Error;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsService { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestMetricsService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>+'static,
+    S::Future: 'static,
+    B: MessageBody+'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let method = req.method().to_string();
+        let started = Instant::now();
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let result = fut.await;
+            // 경로 패턴은 라우팅 이후에 정해지므로 응답에서 조회
+            let (route, status) = match &result {
+                Ok(res) => (res.request().match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string()), res.status()),
+                Err(e) => (UNMATCHED_ROUTE.to_string(), e.as_response_error().status_code()),
+            };
+            let metrics = metrics();
+            metrics.http_requests.with_label_values(&[&method, &route, status.as_str()]).inc();
+            metrics.http_request_duration.with_label_values(&[&method, &route]).observe(started.elapsed().as_secs_f64());
+            result
+        })
+    }
+}
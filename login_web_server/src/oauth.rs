@@ -0,0 +1,163 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use crate::{config, opaque_token};
+use crate::repository::OAuthRepository;
+
+// OAuth2 / OpenID Connect 제공자 모드의 클라이언트 등록 및 인가 코드 관리
+
+// 지원하는 scope(openid: id_token 발급, profile: preferred_username 제공)
+pub const SUPPORTED_SCOPES: [&str; 2] = ["openid", "profile"];
+
+// 클라이언트 id, 비밀 키, 인가 코드 길이(바이트, hex 인코딩 시 2배)
+const CLIENT_ID_BYTES: usize = 16;
+const CLIENT_SECRET_BYTES: usize = 32;
+const CODE_BYTES: usize = 32;
+
+// PKCE code_verifier 길이 제한(RFC 7636)
+const MIN_VERIFIER_LEN: usize = 43;
+const MAX_VERIFIER_LEN: usize = 128;
+
+// 등록된 클라이언트
+#[derive(Serialize, Clone, Debug)]
+pub struct Client {
+    #[serde(skip)]
+    pub id: i64,    // oauth_clients.id(내부 키)
+    pub client_id: String,
+    pub name: String,
+    pub confidential: bool, // 비밀 키가 있는 클라이언트(서버 측 앱)
+    pub redirect_uris: Vec<String>,
+    #[serde(skip)]
+    secret_hash: Option<String>,
+}
+
+impl Client {
+    // 저장소에서 읽은 클라이언트(비밀 키 해시가 있으면 기밀 클라이언트)
+    pub(crate) fn new(id: i64, client_id: String, name: String, secret_hash: Option<String>, redirect_uris: Vec<String>) -> Self {
+        Client { id, client_id, name, confidential: secret_hash.is_some(), redirect_uris, secret_hash }
+    }
+
+    // 등록된 redirect_uri와 정확히 일치하는지 확인(접두사 일치 등은 허용하지 않음)
+    pub fn allows_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.iter().any(|uri| uri==redirect_uri)
+    }
+
+    // 클라이언트 인증: 공개 클라이언트는 비밀 키 없이 통과, 기밀 클라이언트는 비밀 키 일치 필요
+    pub fn authenticate(&self, secret: Option<&str>) -> bool {
+        match (&self.secret_hash, secret) {
+            (None, _) => true,
+            (Some(hash), Some(secret)) => opaque_token::hash(secret)==*hash,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+// 새로 등록된 클라이언트 정보(비밀 키 원문은 등록 응답에서 한 번만 전달)
+pub struct Registered {
+    pub client: Client,
+    pub client_secret: Option<String>,
+}
+
+// 클라이언트 등록
+pub async fn register_client(repo: &dyn OAuthRepository, name: &str, redirect_uris: &[String], confidential: bool) -> Result<Registered, sqlx::Error> {
+    let client_id = opaque_token::generate(CLIENT_ID_BYTES);
+    let client_secret = confidential.then(|| opaque_token::generate(CLIENT_SECRET_BYTES));
+    let secret_hash = client_secret.as_deref().map(opaque_token::hash);
+    let id = repo.insert_client(&client_id, secret_hash.as_deref(), name, redirect_uris).await?;
+    Ok(Registered {
+        client: Client::new(id, client_id, name.to_string(), secret_hash, redirect_uris.to_vec()),
+        client_secret,
+    })
+}
+
+// client_id로 클라이언트 조회
+pub async fn find_client(repo: &dyn OAuthRepository, client_id: &str) -> Result<Option<Client>, sqlx::Error> {
+    repo.find_client(client_id).await
+}
+
+// 등록된 클라이언트 목록(등록 순)
+pub async fn list_clients(repo: &dyn OAuthRepository) -> Result<Vec<Client>, sqlx::Error> {
+    repo.list_clients().await
+}
+
+// 클라이언트 삭제(발급 대기 중인 인가 코드도 함께 삭제), 존재했으면 true
+pub async fn delete_client(repo: &dyn OAuthRepository, client_id: &str) -> Result<bool, sqlx::Error> {
+    repo.delete_client(client_id).await
+}
+
+// 요청된 scope(공백 구분) 검증 후 정규화된 문자열 반환
+// 지원하지 않는 scope가 있으면 None, 비어 있으면 openid
+pub fn normalize_scope(scope: Option<&str>) -> Option<String> {
+    let mut scopes: Vec<&str> = Vec::new();
+    for s in scope.unwrap_or("openid").split_whitespace() {
+        if !SUPPORTED_SCOPES.contains(&s) {
+            return None;
+        }
+        if !scopes.contains(&s) {
+            scopes.push(s);
+        }
+    }
+    if scopes.is_empty() {
+        scopes.push("openid");
+    }
+    Some(scopes.join(" "))
+}
+
+// PKCE S256: BASE64URL(SHA256(code_verifier))
+pub fn s256_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+// code_verifier 형식(43~128자의 unreserved 문자) 확인 후 code_challenge와 비교
+pub fn verify_pkce(code_verifier: &str, code_challenge: &str) -> bool {
+    let valid_format = (MIN_VERIFIER_LEN..=MAX_VERIFIER_LEN).contains(&code_verifier.len())
+        && code_verifier.chars().all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c));
+    valid_format && s256_challenge(code_verifier)==code_challenge
+}
+
+// 인가 코드 발급 시 저장할 요청 정보
+pub struct AuthorizationRequest<'a> {
+    pub client: &'a Client,
+    pub user_id: i64,
+    pub redirect_uri: &'a str,
+    pub scope: &'a str,
+    pub code_challenge: &'a str,
+    pub nonce: Option<&'a str>,
+}
+
+// 인가 코드 발급 후 원문 반환
+pub async fn create_code(repo: &dyn OAuthRepository, request: &AuthorizationRequest<'_>) -> Result<String, sqlx::Error> {
+    let code = opaque_token::generate(CODE_BYTES);
+    repo.insert_authorization_code(&opaque_token::hash(&code), request, config::get().tokens.authorization_code_ttl_secs).await?;    // 기본 2분
+    Ok(code)
+}
+
+// 토큰 교환에 사용된 인가 코드 정보
+pub struct AuthorizationCode {
+    pub client_id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub code_challenge: String,
+    pub nonce: Option<String>,
+}
+
+// 클라이언트 앱 로그인 세션에 기록하는 클라이언트(oauth_clients.id)와 승인된 scope
+// refresh 토큰은 이 클라이언트에서만 교체할 수 있고, access 토큰에는 이 scope만 담김
+#[derive(Clone, Debug)]
+pub struct Grant {
+    pub client_id: i64,
+    pub scope: String,
+}
+
+// 유효한 인가 코드면 사용 처리 후 정보 반환
+// 존재하지 않거나, 만료되었거나, 이미 사용된 코드면 None
+pub async fn consume_code(repo: &dyn OAuthRepository, code: &str) -> Result<Option<AuthorizationCode>, sqlx::Error> {
+    repo.consume_authorization_code(&opaque_token::hash(code)).await
+}
+
+// 사용했거나 만료된 인가 코드 정리
+pub async fn prune_expired(repo: &dyn OAuthRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_authorization_codes().await
+}
@@ -0,0 +1,17 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+// refresh 토큰, 비밀번호 재설정 토큰 등 DB에 해시로만 저장하는 불투명(opaque) 토큰 유틸리티
+
+// len 바이트 난수를 hex 문자열로 생성(hex 인코딩 시 길이 2배)
+pub fn generate(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// DB에는 토큰 원문 대신 SHA-256 해시만 저장
+// (원문은 충분한 엔트로피를 가진 난수이므로 솔트 없는 빠른 해시로 충분)
+pub fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,254 @@
+use anyhow::{anyhow, Result};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier as _, SaltString};
+use argon2::{Argon2, Params, Version};
+use bcrypt::Version::TwoB;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use std::time::Instant;
+use crate::config::{self, Config, HashSelfTest};
+use crate::metrics::metrics;
+
+// 비밀번호 해싱 알고리즘
+// 새 비밀번호는 설정된 알고리즘(password_hash.algorithm)으로 해싱하고,
+// 기존 해시는 해시 문자열의 접두사로 알고리즘을 판별하여 검증(알고리즘을 바꿔도 기존 사용자 로그인 가능)
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    Bcrypt,
+    Argon2id,
+}
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::Bcrypt => "bcrypt",
+            Algorithm::Argon2id => "argon2id",
+        }
+    }
+
+    // 해시 문자열의 알고리즘 판별($2b$..., $argon2id$...)
+    pub fn detect(hash: &str) -> Option<Algorithm> {
+        if ["$2a$", "$2b$", "$2x$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix)) {
+            Some(Algorithm::Bcrypt)
+        } else if hash.starts_with("$argon2id$") {
+            Some(Algorithm::Argon2id)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::str::FromStr for Algorithm {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bcrypt" => Ok(Algorithm::Bcrypt),
+            "argon2id" => Ok(Algorithm::Argon2id),
+            _ => Err(()),
+        }
+    }
+}
+
+// 비밀번호 해싱 방식(알고리즘별 구현)
+pub trait PasswordHasher: Send + Sync {
+    fn algorithm(&self) -> Algorithm;
+    // 무작위 솔트로 해싱
+    fn hash(&self, password: &str) -> Result<String>;
+    // 고정 솔트로 해싱(서버 시작 시 자가시험용)
+    fn hash_with_salt(&self, password: &str, salt: &[u8]) -> Result<String>;
+    // 해시에 담긴 매개변수로 검증
+    fn verify(&self, password: &str, hash: &str) -> Result<bool>;
+    // 현재 설정과 다른 매개변수로 만든 해시인지 확인
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
+
+// bcrypt(cost: 해시 반복 횟수 2^cost)
+pub struct BcryptHasher {
+    cost: u32,
+}
+
+impl BcryptHasher {
+    pub fn new(cost: u32) -> Self {
+        BcryptHasher { cost }
+    }
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Bcrypt
+    }
+
+    fn hash(&self, password: &str) -> Result<String> {
+        Ok(bcrypt::hash(password, self.cost)?)
+    }
+
+    fn hash_with_salt(&self, password: &str, salt: &[u8]) -> Result<String> {
+        Ok(bcrypt::hash_with_salt(password, self.cost, salt)?.format_for_version(TwoB))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool> {
+        Ok(bcrypt::verify(password, hash)?)
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        hash.parse::<bcrypt::HashParts>().map(|parts| parts.get_cost()!=self.cost).unwrap_or(true)
+    }
+}
+
+// Argon2id(memory_kib: 메모리 사용량, iterations: 반복 횟수, parallelism: 병렬 처리 수)
+pub struct Argon2Hasher {
+    params: Params,
+}
+
+impl Argon2Hasher {
+    pub fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Result<Self> {
+        let params = Params::new(memory_kib, iterations, parallelism, None)
+            .map_err(|e| anyhow!("Invalid Argon2 parameters: {}", e))?;
+        Ok(Argon2Hasher { params })
+    }
+
+    fn argon2(&self) -> Argon2<'static> {
+        Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, self.params.clone())
+    }
+}
+
+impl PasswordHasher for Argon2Hasher {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Argon2id
+    }
+
+    fn hash(&self, password: &str) -> Result<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = self.argon2().hash_password(password.as_bytes(), &salt)
+            .map_err(|e| anyhow!("Argon2 hashing failed: {}", e))?;
+        Ok(hash.to_string())
+    }
+
+    fn hash_with_salt(&self, password: &str, salt: &[u8]) -> Result<String> {
+        let salt = SaltString::encode_b64(salt).map_err(|e| anyhow!("Invalid Argon2 salt: {}", e))?;
+        let hash = self.argon2().hash_password(password.as_bytes(), &salt)
+            .map_err(|e| anyhow!("Argon2 hashing failed: {}", e))?;
+        Ok(hash.to_string())
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool> {
+        let parsed = PasswordHash::new(hash).map_err(|e| anyhow!("Invalid Argon2 hash: {}", e))?;
+        match self.argon2().verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(anyhow!("Argon2 verification failed: {}", e)),
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let parsed = match PasswordHash::new(hash) {
+            Ok(parsed) => parsed,
+            Err(_) => return true,
+        };
+        let same_params = Params::try_from(&parsed).is_ok_and(|params| {
+            params.m_cost()==self.params.m_cost() && params.t_cost()==self.params.t_cost() && params.p_cost()==self.params.p_cost()
+        });
+        parsed.algorithm!=argon2::Algorithm::Argon2id.ident() || parsed.version!=Some(Version::V0x13.into()) || !same_params
+    }
+}
+
+// 지원하는 모든 알고리즘의 해셔와 새 해시에 사용할 알고리즘
+pub struct PasswordHashers {
+    algorithm: Algorithm,
+    hashers: Vec<Box<dyn PasswordHasher>>,
+    dummy_hash: OnceLock<String>,   // 없는 사용자의 로그인 시 검증에 사용할 해시(처음 사용할 때 생성)
+}
+
+impl PasswordHashers {
+    pub fn new(algorithm: Algorithm, hashers: Vec<Box<dyn PasswordHasher>>) -> Result<Self> {
+        if !hashers.iter().any(|h| h.algorithm()==algorithm) {
+            return Err(anyhow!("No hasher for password hash algorithm {}", algorithm.as_str()));
+        }
+        Ok(PasswordHashers { algorithm, hashers, dummy_hash: OnceLock::new() })
+    }
+
+    // 설정(password_hash, bcrypt, argon2 섹션)으로 해셔 생성
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let argon2 = &config.argon2;
+        PasswordHashers::new(config.password_hash.algorithm, vec![
+            Box::new(BcryptHasher::new(config.bcrypt.cost)),
+            Box::new(Argon2Hasher::new(argon2.memory_kib, argon2.iterations, argon2.parallelism)?),
+        ])
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    fn hasher(&self, algorithm: Algorithm) -> Option<&dyn PasswordHasher> {
+        self.hashers.iter().find(|h| h.algorithm()==algorithm).map(|h| h.as_ref())
+    }
+
+    fn current(&self) -> &dyn PasswordHasher {
+        self.hasher(self.algorithm).expect("hasher for the configured algorithm is checked in new")
+    }
+
+    // 설정된 알고리즘으로 해싱
+    pub fn hash(&self, password: &str) -> Result<String> {
+        let started = Instant::now();
+        let result = self.current().hash(password);
+        metrics().observe_password_hash(self.algorithm.as_str(), "hash", started);
+        result
+    }
+
+    // 해시의 알고리즘으로 검증(알 수 없는 형식이면 에러)
+    pub fn verify(&self, password: &str, hash: &str) -> Result<bool> {
+        let hasher = Algorithm::detect(hash).and_then(|algorithm| self.hasher(algorithm))
+            .ok_or_else(|| anyhow!("Unknown password hash format"))?;
+        let started = Instant::now();
+        let result = hasher.verify(password, hash);
+        metrics().observe_password_hash(hasher.algorithm().as_str(), "verify", started);
+        result
+    }
+
+    // 사용자가 없을 때도 현재 알고리즘과 매개변수로 한 번 검증해 응답 시간으로 존재 여부가 드러나지 않게 함
+    // 결과는 항상 false
+    pub fn verify_dummy(&self, password: &str) -> bool {
+        let hash = self.dummy_hash.get_or_init(|| self.hash("dummy password").unwrap_or_default());
+        if let Err(e) = self.verify(password, hash) {
+            tracing::error!("Error verifying dummy password hash: {:?}", e);
+        }
+        false
+    }
+
+    // 알고리즘이 다르거나 매개변수가 현재 설정과 다르면 다시 해싱 필요
+    // (로그인 성공 시 입력한 비밀번호로 새 해시를 만들어 교체)
+    pub fn needs_rehash(&self, hash: &str) -> bool {
+        Algorithm::detect(hash)!=Some(self.algorithm) || self.current().needs_rehash(hash)
+    }
+
+    // 서버 시작 시 자가시험
+    // 1. 모든 알고리즘: 해싱 후 같은 비밀번호는 통과, 다른 비밀번호는 거부하는지 확인
+    // 2. 기대값이 설정된 알고리즘: 고정 솔트로 계산한 해시가 기대값과 같은지 확인
+    pub fn self_test(&self, vectors: &[(Algorithm, &HashSelfTest)]) -> Result<()> {
+        for hasher in &self.hashers {
+            let name = hasher.algorithm().as_str();
+            let hash = hasher.hash("self-test password")?;
+            if !hasher.verify("self-test password", &hash)? || hasher.verify("wrong password", &hash)? {
+                return Err(anyhow!("{} self-test failed: round trip mismatch", name));
+            }
+        }
+        for (algorithm, vector) in vectors {
+            let hasher = self.hasher(*algorithm).ok_or_else(|| anyhow!("No hasher for {}", algorithm.as_str()))?;
+            let hash = hasher.hash_with_salt(&vector.password, vector.fixed_salt.as_bytes())
+                .map_err(|e| anyhow!("{} self-test failed during hashing: {}", algorithm.as_str(), e))?;
+            if hash!=vector.expected_hash {
+                return Err(anyhow!("{} self-test failed: hash does not match expected_hash", algorithm.as_str()));
+            }
+        }
+        Ok(())
+    }
+}
+
+// 서버 전체에서 공유하는 해셔(설정에서 생성, 설정은 시작 시 검증되므로 생성 실패 시 panic)
+static HASHERS: OnceLock<PasswordHashers> = OnceLock::new();
+
+pub fn hashers() -> &'static PasswordHashers {
+    HASHERS.get_or_init(|| PasswordHashers::from_config(config::get()).expect("invalid password hashing configuration"))
+}
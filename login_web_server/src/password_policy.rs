@@ -0,0 +1,183 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use crate::config::PasswordPolicyConfig;
+
+// k-익명성(k-anonymity) 조회에 사용하는 SHA-1 해시 접두사 길이(HIBP range API와 동일)
+const HASH_PREFIX_LEN: usize = 5;
+
+// 유출 비밀번호 목록 조회 방식(로컬 파일, 원격 API 등으로 교체 가능)
+// 비밀번호 원문이나 전체 해시 대신 해시 접두사만 전달하고, 해당 범위의 해시 접미사 목록을 받아 로컬에서 비교
+pub trait BreachedPasswordSource: Send + Sync {
+    // 대문자 hex SHA-1 해시 앞 5자리(prefix)에 해당하는 나머지 35자리(suffix) 목록 반환
+    fn range(&self, prefix: &str) -> Vec<String>;
+}
+
+// 로컬 파일 기반 유출 비밀번호 목록
+// 파일 형식: 한 줄에 하나씩 SHA-1 해시(40자리 hex), HIBP 형식처럼 ":<출현 횟수>"가 붙어 있어도 무시
+pub struct LocalBreachList {
+    ranges: HashMap<String, HashSet<String>>,   // 접두사 -> 접미사 집합
+}
+
+impl LocalBreachList {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read breached password list: {}", path))?;
+        let mut ranges: HashMap<String, HashSet<String>> = HashMap::new();
+        for (line_no, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let hash = line.split(':').next().unwrap_or_default().to_ascii_uppercase();
+            if hash.len()!=40 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(anyhow!("Invalid SHA-1 hash at {}:{}", path, line_no+1));
+            }
+            let (prefix, suffix) = hash.split_at(HASH_PREFIX_LEN);
+            ranges.entry(prefix.to_string()).or_default().insert(suffix.to_string());
+        }
+        Ok(LocalBreachList { ranges })
+    }
+}
+
+impl BreachedPasswordSource for LocalBreachList {
+    fn range(&self, prefix: &str) -> Vec<String> {
+        self.ranges.get(prefix).map(|suffixes| suffixes.iter().cloned().collect()).unwrap_or_default()
+    }
+}
+
+// 정책 위반 항목(JSON 응답으로 규칙별 사유 전달)
+#[derive(Serialize, Debug)]
+pub struct Violation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+// 비밀번호 정책 설정
+#[derive(Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,  // 바이트 단위(UTF-8), bcrypt는 72바이트 이후를 무시하므로 지나치게 긴 비밀번호 제한
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,  // 특수 문자는 영숫자가 아닌 문자
+    pub min_entropy_bits: f64,  // 추정 엔트로피 하한(0이면 검사 안 함)
+    pub reject_username: bool,  // 사용자 이름이 포함된 비밀번호 거부
+    pub breach_source: Option<Arc<dyn BreachedPasswordSource>>,
+}
+
+impl Default for PasswordPolicy {
+    // 기존 register의 규칙(8자 이상, 소문자/대문자/특수 문자 포함)을 기본값으로 유지
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 8,
+            max_length: 72,
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: false,
+            require_special: true,
+            min_entropy_bits: 35.0,
+            reject_username: true,
+            breach_source: None,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    // 설정 파일(password_policy 섹션)로 기본 정책 일부 재정의
+    // 값 검증은 Config::validate에서 수행, 유출 목록 파일 로드 실패 시 에러
+    pub fn from_config(config: &PasswordPolicyConfig) -> Result<Self> {
+        let breach_source = match &config.breached_passwords_file {
+            Some(path) => Some(Arc::new(LocalBreachList::load(&path.to_string_lossy())?) as Arc<dyn BreachedPasswordSource>),
+            None => None,
+        };
+        Ok(PasswordPolicy {
+            min_length: config.min_length,
+            max_length: config.max_length,
+            min_entropy_bits: config.min_entropy_bits,
+            breach_source,
+            ..PasswordPolicy::default()
+        })
+    }
+
+    // 모든 규칙을 검사하여 위반 항목 목록 반환(비어 있으면 통과)
+    pub fn check(&self, username: &str, password: &str) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let length = password.chars().count();
+
+        if length<self.min_length {
+            violations.push(Violation { rule: "min_length", message: format!("Password must be at least {} characters long.", self.min_length) });
+        }
+        // 최대 길이는 문자 수가 아닌 UTF-8 바이트 수로 검사(한글 등은 한 글자가 3바이트)
+        if password.len()>self.max_length {
+            violations.push(Violation { rule: "max_length", message: format!("Password must be at most {} bytes long.", self.max_length) });
+        }
+
+        // 문자 종류 포함 여부 검사
+        let has_lower = password.chars().any(char::is_lowercase);
+        let has_upper = password.chars().any(char::is_uppercase);
+        let has_digit = password.chars().any(|c| c.is_ascii_digit());
+        let has_special = password.chars().any(|c| !c.is_alphanumeric());
+        if self.require_lowercase && !has_lower {
+            violations.push(Violation { rule: "lowercase", message: "Password must contain at least one lowercase letter.".to_string() });
+        }
+        if self.require_uppercase && !has_upper {
+            violations.push(Violation { rule: "uppercase", message: "Password must contain at least one uppercase letter.".to_string() });
+        }
+        if self.require_digit && !has_digit {
+            violations.push(Violation { rule: "digit", message: "Password must contain at least one digit.".to_string() });
+        }
+        if self.require_special && !has_special {
+            violations.push(Violation { rule: "special", message: "Password must contain at least one special character.".to_string() });
+        }
+
+        if self.min_entropy_bits>0.0 && estimate_entropy_bits(password)<self.min_entropy_bits {
+            violations.push(Violation { rule: "entropy", message: "Password is too predictable. Use a longer or more varied password.".to_string() });
+        }
+
+        // 대소문자 구분 없이 사용자 이름 포함 여부 검사(너무 짧은 이름은 우연히 포함될 수 있으므로 제외)
+        if self.reject_username && username.chars().count()>=3
+            && password.to_lowercase().contains(&username.to_lowercase()) {
+            violations.push(Violation { rule: "contains_username", message: "Password must not contain the username.".to_string() });
+        }
+
+        if let Some(source) = &self.breach_source && is_breached(source.as_ref(), password) {
+            violations.push(Violation { rule: "breached", message: "Password has appeared in a data breach. Choose a different password.".to_string() });
+        }
+
+        violations
+    }
+}
+
+// 비밀번호의 SHA-1 해시 접두사로 범위를 조회한 뒤 접미사를 로컬에서 비교
+fn is_breached(source: &dyn BreachedPasswordSource, password: &str) -> bool {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = hash.split_at(HASH_PREFIX_LEN);
+    source.range(prefix).iter().any(|s| s.eq_ignore_ascii_case(suffix))
+}
+
+// 문자 집합 크기 기반 엔트로피 추정(비트)
+// 같은 문자가 연속으로 반복되는 부분은 한 글자로 계산
+pub fn estimate_entropy_bits(password: &str) -> f64 {
+    let mut pool = 0u32;
+    if password.chars().any(|c| c.is_ascii_lowercase()) { pool += 26; }
+    if password.chars().any(|c| c.is_ascii_uppercase()) { pool += 26; }
+    if password.chars().any(|c| c.is_ascii_digit()) { pool += 10; }
+    if password.chars().any(|c| c.is_ascii_punctuation() || c==' ') { pool += 33; }
+    if !password.is_ascii() { pool += 100; }
+    if pool==0 {
+        return 0.0;
+    }
+    let mut effective_len = 0usize;
+    let mut prev: Option<char> = None;
+    for c in password.chars() {
+        if prev!=Some(c) {
+            effective_len += 1;
+        }
+        prev = Some(c);
+    }
+    effective_len as f64 * (pool as f64).log2()
+}
@@ -0,0 +1,45 @@
+use crate::{config, opaque_token};
+use crate::rate_limit::Limit;
+use crate::repository::PasswordResetRepository;
+
+// 토큰 원문 길이(바이트, hex 인코딩 시 2배)
+const TOKEN_BYTES: usize = 32;
+
+// 재설정 메일 요청 제한(계정 존재 여부와 관계없이 적용)
+// 사용자 이름 기준 15분에 3회, IP 기준 1시간에 20회(NAT 등으로 여러 사용자가 공유할 수 있으므로 더 높게 설정)
+pub const USERNAME_LIMIT: Limit = Limit { max: 3, window_secs: 15 * 60 };
+pub const IP_LIMIT: Limit = Limit { max: 20, window_secs: 3600 };
+
+// 요청 횟수 제한 키(rate_limits.key)
+pub fn username_key(username: &str) -> String {
+    format!("password_reset:user:{}", username)
+}
+
+pub fn ip_key(ip: &str) -> String {
+    format!("password_reset:ip:{}", ip)
+}
+
+// 새 재설정 토큰 발급 후 원문 반환
+// 아직 사용하지 않은 이전 토큰은 모두 무효화(가장 최근 메일의 토큰만 유효)
+pub async fn create(repo: &dyn PasswordResetRepository, user_id: i64) -> Result<String, sqlx::Error> {
+    let token = opaque_token::generate(TOKEN_BYTES);
+    repo.create_password_reset(user_id, &opaque_token::hash(&token), config::get().tokens.password_reset_ttl_secs).await?;    // 기본 30분
+    Ok(token)
+}
+
+// 유효한(만료되지 않았고 사용하지 않은) 토큰의 소유자 (user id, username) 조회
+// 비밀번호 정책 검사 전에 소유자를 확인하기 위해 사용하며 토큰은 소모하지 않음
+pub async fn lookup(repo: &dyn PasswordResetRepository, token: &str) -> Result<Option<(i64, String)>, sqlx::Error> {
+    repo.find_password_reset(&opaque_token::hash(token)).await
+}
+
+// 유효한 토큰이면 사용 처리와 비밀번호 해시 변경을 한 트랜잭션으로 수행한 뒤 user id 반환
+// 존재하지 않거나, 만료되었거나, 이미 사용된 토큰이면 None(비밀번호도 바뀌지 않음)
+pub async fn consume(repo: &dyn PasswordResetRepository, token: &str, password_hash: &str) -> Result<Option<i64>, sqlx::Error> {
+    repo.consume_password_reset(&opaque_token::hash(token), password_hash).await
+}
+
+// 사용했거나 만료된 토큰 정리
+pub async fn prune_expired(repo: &dyn PasswordResetRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_password_resets().await
+}
@@ -0,0 +1,33 @@
+use crate::repository::RateLimitRepository;
+
+// 요청 횟수 제한(고정 기간 카운터)
+// 로그인 잠금(lockout)과 달리 성공 여부와 관계없이 요청마다 세며, 대상 계정의 존재 여부와도 무관하게 적용
+
+// 기간(window_secs) 안에 허용하는 최대 요청 수
+#[derive(Clone, Copy)]
+pub struct Limit {
+    pub max: i64,
+    pub window_secs: i64,
+}
+
+// 요청 한 번을 기록하고, 제한을 넘었으면 다시 요청할 수 있을 때까지 남은 시간(초) 반환
+pub async fn hit(repo: &dyn RateLimitRepository, key: &str, limit: Limit) -> Result<Option<u64>, sqlx::Error> {
+    let (hits, remaining) = repo.hit_rate_limit(key, limit.window_secs).await?;
+    Ok((hits>limit.max).then(|| remaining.max(1) as u64))
+}
+
+// 주어진 키를 모두 기록하고 제한된 키가 있으면 남은 시간(초) 중 최댓값 반환
+pub async fn hit_all(repo: &dyn RateLimitRepository, keys: &[(String, Limit)]) -> Result<Option<u64>, sqlx::Error> {
+    let mut retry_after: Option<u64> = None;
+    for (key, limit) in keys {
+        if let Some(remaining) = hit(repo, key, *limit).await? {
+            retry_after = Some(retry_after.map_or(remaining, |r| r.max(remaining)));
+        }
+    }
+    Ok(retry_after)
+}
+
+// 기간이 끝난 기록 삭제
+pub async fn prune_expired(repo: &dyn RateLimitRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_rate_limits().await
+}
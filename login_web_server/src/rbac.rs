@@ -0,0 +1,59 @@
+use serde::Serialize;
+use crate::repository::RoleRepository;
+
+// 회원가입 시 부여하는 기본 역할
+pub const DEFAULT_ROLE: &str = "user";
+// ADMIN_USERNAMES로 지정한 사용자에게 부여하는 역할
+pub const ADMIN_ROLE: &str = "admin";
+
+// 관리 API 권한 이름(migrations의 permissions 테이블과 일치해야 함)
+pub const PERM_USERS_READ: &str = "users.read";
+pub const PERM_USERS_WRITE: &str = "users.write";
+pub const PERM_USERS_UNLOCK: &str = "users.unlock";
+pub const PERM_SESSIONS_REVOKE: &str = "sessions.revoke";
+pub const PERM_ROLES_WRITE: &str = "roles.write";
+pub const PERM_OAUTH_CLIENTS_WRITE: &str = "oauth_clients.write";
+pub const PERM_AUDIT_READ: &str = "audit.read";
+
+// 사용자의 역할과 (역할을 통해 얻은) 권한 목록
+// 로그인 및 토큰 갱신 시 조회하여 JWT 클레임에 담음
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Grants {
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+// 사용자 id로 역할 및 권한 조회(이름순 정렬)
+pub async fn grants_for(repo: &dyn RoleRepository, user_id: i64) -> Result<Grants, sqlx::Error> {
+    repo.grants_for(user_id).await
+}
+
+// 사용자에게 역할 부여(이미 있으면 무시)
+// 사용자나 역할이 존재하지 않으면 false
+pub async fn assign_role(repo: &dyn RoleRepository, username: &str, role: &str) -> Result<bool, sqlx::Error> {
+    repo.assign_role(username, role).await
+}
+
+// 사용자의 역할 회수, 회수된 역할이 있으면 true
+pub async fn remove_role(repo: &dyn RoleRepository, username: &str, role: &str) -> Result<bool, sqlx::Error> {
+    repo.remove_role(username, role).await
+}
+
+// 역할이 존재하는지 확인
+pub async fn role_exists(repo: &dyn RoleRepository, role: &str) -> Result<bool, sqlx::Error> {
+    repo.role_exists(role).await
+}
+
+// 서버 시작 시 지정된 사용자들(설정의 admin.usernames)에게 admin 역할 부여
+// 아직 가입하지 않은 사용자 이름은 건너뛰고, 부여된 사용자 수 반환
+pub async fn bootstrap_admins(repo: &dyn RoleRepository, usernames: &[String]) -> Result<u64, sqlx::Error> {
+    let mut granted = 0;
+    for username in usernames {
+        if assign_role(repo, username, ADMIN_ROLE).await? {
+            granted += 1;
+        } else {
+            tracing::warn!("admin.usernames: user {} does not exist, skipped", username);
+        }
+    }
+    Ok(granted)
+}
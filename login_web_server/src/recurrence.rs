@@ -0,0 +1,185 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::fmt;
+
+// todo 반복 규칙(RFC 5545 RRULE의 일부)
+//   FREQ=DAILY|WEEKLY|MONTHLY(필수), INTERVAL=n, BYDAY=MO,WE(WEEKLY만), BYMONTHDAY=1~31(MONTHLY만),
+//   COUNT=n 또는 UNTIL=YYYYMMDD(둘 중 하나만)
+// 간단한 이름 daily, weekly, monthly, weekdays(월~금)도 허용하며, 저장할 때는 항상 RRULE 형식으로 정규화
+// 다음 날짜는 현재 occurrence의 마감일을 기준으로 계산하고 주의 시작은 월요일(WKST=MO)
+
+// INTERVAL 최댓값
+const MAX_INTERVAL: u32 = 366;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub weekdays: Vec<Weekday>,     // 월요일부터 순서대로, 비어 있으면 마감일과 같은 요일
+    pub month_day: Option<u32>,     // 없으면 마감일과 같은 날(anchored에서 채움)
+    pub count: Option<u32>,         // 현재 occurrence를 포함한 남은 횟수
+    pub until: Option<NaiveDate>,   // 이 날짜 이후로는 만들지 않음
+}
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon), ("TU", Weekday::Tue), ("WE", Weekday::Wed), ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri), ("SA", Weekday::Sat), ("SU", Weekday::Sun),
+];
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    WEEKDAYS.iter().find(|(name, _)| name.eq_ignore_ascii_case(value)).map(|(_, day)| *day)
+}
+
+fn weekday_name(day: Weekday) -> &'static str {
+    WEEKDAYS[day.num_days_from_monday() as usize].0
+}
+
+fn parse_number(name: &str, value: &str, range: std::ops::RangeInclusive<u32>) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(n) if range.contains(&n) => Ok(n),
+        _ => Err(format!("{} must be between {} and {}.", name, range.start(), range.end())),
+    }
+}
+
+// UNTIL은 날짜(YYYYMMDD) 또는 UTC 시각(YYYYMMDDTHHMMSSZ), 시각은 날짜 부분만 사용
+fn parse_until(value: &str) -> Result<NaiveDate, String> {
+    let date = value.split_once('T').map_or(value, |(date, _)| date);
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| "UNTIL must be a date in YYYYMMDD format.".to_string())
+}
+
+impl Rule {
+    // 간단한 이름 또는 RRULE("RRULE:" 접두사 허용, 대소문자 구분 없음) 파싱, 실패 시 사용자에게 보여줄 메시지
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        let preset = |frequency, weekdays: &[Weekday]| Rule {
+            frequency, interval: 1, weekdays: weekdays.to_vec(), month_day: None, count: None, until: None,
+        };
+        match value.to_ascii_lowercase().as_str() {
+            "daily" => return Ok(preset(Frequency::Daily, &[])),
+            "weekly" => return Ok(preset(Frequency::Weekly, &[])),
+            "monthly" => return Ok(preset(Frequency::Monthly, &[])),
+            "weekdays" => return Ok(preset(Frequency::Weekly, &[Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri])),
+            _ => {}
+        }
+        let rrule = value.get(..6).filter(|p| p.eq_ignore_ascii_case("RRULE:")).map_or(value, |_| &value[6..]);
+        let mut frequency = None;
+        let mut rule = preset(Frequency::Daily, &[]);
+        for part in rrule.split(';').filter(|p| !p.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(|| format!("Invalid RRULE part: {}", part))?;
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(match value.to_ascii_uppercase().as_str() {
+                    "DAILY" => Frequency::Daily,
+                    "WEEKLY" => Frequency::Weekly,
+                    "MONTHLY" => Frequency::Monthly,
+                    _ => return Err("FREQ must be DAILY, WEEKLY or MONTHLY.".to_string()),
+                }),
+                "INTERVAL" => rule.interval = parse_number("INTERVAL", value, 1..=MAX_INTERVAL)?,
+                "BYDAY" => {
+                    rule.weekdays = value.split(',')
+                        .map(|day| parse_weekday(day).ok_or_else(|| format!("Invalid BYDAY weekday: {}", day)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "BYMONTHDAY" => rule.month_day = Some(parse_number("BYMONTHDAY", value, 1..=31)?),
+                "COUNT" => rule.count = Some(parse_number("COUNT", value, 1..=u32::MAX)?),
+                "UNTIL" => rule.until = Some(parse_until(value)?),
+                "WKST" if value.eq_ignore_ascii_case("MO") => {}
+                _ => return Err(format!("Unsupported RRULE part: {}", part)),
+            }
+        }
+        rule.frequency = frequency.ok_or("FREQ is required (or use daily, weekly, monthly, weekdays).")?;
+        if !rule.weekdays.is_empty() && rule.frequency!=Frequency::Weekly {
+            return Err("BYDAY is only supported with FREQ=WEEKLY.".to_string());
+        }
+        if rule.month_day.is_some() && rule.frequency!=Frequency::Monthly {
+            return Err("BYMONTHDAY is only supported with FREQ=MONTHLY.".to_string());
+        }
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("COUNT and UNTIL cannot be used together.".to_string());
+        }
+        rule.weekdays.sort_by_key(|day| day.num_days_from_monday());
+        rule.weekdays.dedup();
+        Ok(rule)
+    }
+
+    // 매월 반복의 날짜를 마감일에 고정(짧은 달에서 말일로 당겨진 뒤에도 원래 날짜로 돌아오도록)
+    pub fn anchored(mut self, due_date: NaiveDate) -> Self {
+        if self.frequency==Frequency::Monthly && self.month_day.is_none() {
+            self.month_day = Some(due_date.day());
+        }
+        self
+    }
+
+    // 마감일을 옮길 때 매월 반복의 날짜를 새 마감일에 다시 고정
+    pub fn reanchored(self, due_date: NaiveDate) -> Self {
+        Rule { month_day: None, ..self }.anchored(due_date)
+    }
+
+    // 다음 occurrence의 마감일과 그 occurrence에 저장할 규칙(COUNT 1 감소)
+    // 마지막 occurrence이거나 UNTIL을 넘으면 None
+    pub fn next(&self, due_date: NaiveDate) -> Option<(NaiveDate, Rule)> {
+        if self.count==Some(1) {
+            return None;
+        }
+        let next = match self.frequency {
+            Frequency::Daily => due_date.checked_add_days(Days::new(self.interval as u64))?,
+            Frequency::Weekly if self.weekdays.is_empty() => due_date.checked_add_days(Days::new(7 * self.interval as u64))?,
+            Frequency::Weekly => self.next_weekday(due_date)?,
+            Frequency::Monthly => {
+                let month = due_date.with_day(1)?.checked_add_months(Months::new(self.interval))?;
+                let last_day = month.checked_add_months(Months::new(1))?.pred_opt()?.day();
+                month.with_day(self.month_day.unwrap_or(due_date.day()).min(last_day))?
+            }
+        };
+        if self.until.is_some_and(|until| next>until) {
+            return None;
+        }
+        let rule = Rule { count: self.count.map(|c| c-1), ..self.clone() };
+        Some((next, rule))
+    }
+
+    // 같은 주의 남은 요일 중 첫 번째, 없으면 interval주 뒤 주의 첫 번째 요일
+    fn next_weekday(&self, due_date: NaiveDate) -> Option<NaiveDate> {
+        let today = due_date.weekday().num_days_from_monday();
+        if let Some(day) = self.weekdays.iter().find(|d| d.num_days_from_monday()>today) {
+            return due_date.checked_add_days(Days::new((day.num_days_from_monday()-today) as u64));
+        }
+        let monday = due_date.checked_sub_days(Days::new(today as u64))?;
+        let week = monday.checked_add_days(Days::new(7 * self.interval as u64))?;
+        week.checked_add_days(Days::new(self.weekdays[0].num_days_from_monday() as u64))
+    }
+}
+
+// 정규화한 RRULE 문자열(저장 및 응답 형식)
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval!=1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<_> = self.weekdays.iter().map(|d| weekday_name(*d)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(day) = self.month_day {
+            write!(f, ";BYMONTHDAY={}", day)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{config, opaque_token};
+use crate::oauth::Grant;
+use crate::repository::RefreshTokenRepository;
+
+// 토큰 원문 및 family id 길이(바이트, hex 인코딩 시 2배)
+const TOKEN_BYTES: usize = 32;
+const FAMILY_ID_BYTES: usize = 16;
+
+// Refresh 토큰 교체(rotation) 실패 사유
+#[derive(Debug)]
+pub enum RefreshError {
+    Invalid,    // 존재하지 않거나 이미 폐기된 토큰, 다른 클라이언트에 발급된 토큰
+    Expired,    // 유효 기간이 지난 토큰
+    Reused,     // 이미 사용된 토큰의 재사용(탈취 의심) -> family 전체 폐기됨
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for RefreshError {
+    fn from(e: sqlx::Error) -> Self {
+        RefreshError::Database(e)
+    }
+}
+
+// 교체 성공 시 새 토큰과 토큰 소유자 정보
+pub struct Rotated {
+    pub user_id: i64,
+    pub username: String,
+    pub family_id: String,
+    pub refresh_token: String,
+    pub grant: Option<Grant>,   // OAuth 클라이언트 앱 세션이면 클라이언트와 승인된 scope
+}
+
+// 로그인마다 새 토큰 family id 생성(access 토큰의 sid 클레임으로도 사용)
+pub fn new_family_id() -> String {
+    opaque_token::generate(FAMILY_ID_BYTES)
+}
+
+// 새 refresh 토큰 발급 후 원문 반환
+pub async fn issue(repo: &dyn RefreshTokenRepository, user_id: i64, family_id: &str) -> Result<String, sqlx::Error> {
+    let token = opaque_token::generate(TOKEN_BYTES);
+    repo.insert_refresh_token(user_id, family_id, &opaque_token::hash(&token), config::get().tokens.refresh_ttl_secs).await?;   // 기본 14일
+    Ok(token)
+}
+
+// 사용된 refresh 토큰을 폐기하고 같은 family의 새 토큰 발급
+// 이미 사용된 토큰이 다시 제출되면 탈취로 간주하여 family 전체 폐기
+// client_id: 교체를 요청한 OAuth 클라이언트(oauth_clients.id), /api/token/refresh면 None
+// 세션을 만든 클라이언트와 다르면 교체하지 않음(다른 앱 또는 이 서버의 API에서 사용 불가)
+pub async fn rotate(repo: &dyn RefreshTokenRepository, token: &str, client_id: Option<i64>) -> Result<Rotated, RefreshError> {
+    let current = match repo.find_refresh_token(&opaque_token::hash(token)).await? {
+        Some(current) => current,
+        None => return Err(RefreshError::Invalid),
+    };
+
+    if current.grant.as_ref().map(|grant| grant.client_id)!=client_id {
+        return Err(RefreshError::Invalid);
+    }
+    if current.revoked {
+        return Err(RefreshError::Invalid);
+    }
+    if current.used {
+        repo.revoke_token_family(&current.family_id).await?;
+        return Err(RefreshError::Reused);
+    }
+    if current.expired {
+        return Err(RefreshError::Expired);
+    }
+
+    // 동시 요청으로 같은 토큰이 두 번 사용된 경우(저장소에서 used_at이 비어 있을 때만 갱신)
+    let new_token = opaque_token::generate(TOKEN_BYTES);
+    let rotated = repo.rotate_refresh_token(&current, &opaque_token::hash(&new_token), config::get().tokens.refresh_ttl_secs).await?;
+    if !rotated {
+        repo.revoke_token_family(&current.family_id).await?;
+        return Err(RefreshError::Reused);
+    }
+
+    Ok(Rotated { user_id: current.user_id, username: current.username, family_id: current.family_id, refresh_token: new_token, grant: current.grant })
+}
+
+// 한 세션(family)의 refresh 토큰 폐기(로그아웃 시)
+pub async fn revoke_family(repo: &dyn RefreshTokenRepository, family_id: &str) -> Result<(), sqlx::Error> {
+    repo.revoke_token_family(family_id).await
+}
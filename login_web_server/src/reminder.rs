@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::NotificationsConfig;
+use crate::repository::{Repository, TodoEvent, TodoEventRepository};
+use crate::scheduler::Scheduler;
+
+// todo 마감 알림
+// 알림 작업이 알림 시각이 지난 todo마다 reminder 이벤트를 todo_events(outbox)에 기록하고,
+// 같은 작업에서 아직 전달하지 않은 이벤트를 notifier로 보낸 뒤 전달 완료로 표시
+// 전달에 실패한 이벤트는 다음 실행에서 다시 보내며, 사용자는 GET /api/events로 직접 가져갈 수도 있음
+
+// 한 번 실행에서 전달하는 최대 이벤트 수
+const DELIVERY_BATCH: i64 = 100;
+// 전달한 이벤트 보관 기간(GET /api/events로 가져갈 수 있는 기간)
+const KEEP_DELIVERED_SECS: i64 = 7 * 24 * 3600;
+
+// 이벤트 전달 방식(웹훅, 푸시 알림 등으로 교체 가능)
+pub trait Notifier: Send + Sync {
+    fn notify(&self, event: &TodoEvent) -> Result<()>;
+}
+
+// 로그로 출력(기본값)
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn notify(&self, event: &TodoEvent) -> Result<()> {
+        tracing::info!("Todo {} event for user {}: {:?} due {} (remind at {})",
+            event.kind, event.user_id, event.title, event.due_date, event.remind_at);
+        Ok(())
+    }
+}
+
+// 이벤트 하나를 디렉터리에 JSON 파일 하나로 저장(웹훅 중계 프로그램 등이 가져감)
+pub struct FileNotifier {
+    dir: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create notification directory: {}", dir.display()))?;
+        Ok(FileNotifier { dir })
+    }
+}
+
+impl Notifier for FileNotifier {
+    fn notify(&self, event: &TodoEvent) -> Result<()> {
+        // 이벤트 id가 유일하므로 다시 전달해도 같은 파일을 덮어씀
+        let path = self.dir.join(format!("{:012}-{}.json", event.id, event.kind));
+        fs::write(&path, serde_json::to_vec(event)?)
+            .with_context(|| format!("Failed to write notification file: {}", path.display()))
+    }
+}
+
+// 알림 디렉터리(notifications.dir, NOTIFICATIONS_DIR)가 설정되어 있으면 FileNotifier, 없으면 LogNotifier
+pub fn from_config(config: &NotificationsConfig) -> Result<Arc<dyn Notifier>> {
+    match &config.dir {
+        Some(dir) => Ok(Arc::new(FileNotifier::new(dir)?)),
+        None => Ok(Arc::new(LogNotifier)),
+    }
+}
+
+// 알림 시각이 지난 todo의 reminder 이벤트 기록, 기록한 수 반환
+pub async fn emit_due(repo: &dyn TodoEventRepository) -> Result<u64, sqlx::Error> {
+    repo.emit_due_reminders().await
+}
+
+// 전달하지 않은 이벤트를 기록순으로 전달, 전달한 수 반환
+// 전달에 실패하면 순서를 지키기 위해 멈추고 남은 이벤트는 다음 실행에서 다시 시도
+pub async fn deliver(repo: &dyn TodoEventRepository, notifier: &dyn Notifier) -> Result<u64> {
+    let mut delivered = 0;
+    for event in repo.undelivered_todo_events(DELIVERY_BATCH).await? {
+        notifier.notify(&event).with_context(|| format!("Failed to deliver todo event {}", event.id))?;
+        repo.mark_todo_event_delivered(event.id).await?;
+        delivered += 1;
+    }
+    Ok(delivered)
+}
+
+pub async fn prune_delivered(repo: &dyn TodoEventRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_todo_events(KEEP_DELIVERED_SECS).await
+}
+
+// 알림 작업 등록(서버 시작 시 한 번 실행한 뒤 jobs.reminder_interval_secs마다 반복)
+pub fn register(scheduler: Scheduler, repo: &Arc<dyn Repository>, notifier: &Arc<dyn Notifier>, interval: Duration) -> Scheduler {
+    let (repo, notifier) = (repo.clone(), notifier.clone());
+    scheduler.every("todo_reminders", interval, move || {
+        let (repo, notifier) = (repo.clone(), notifier.clone());
+        async move {
+            let emitted = emit_due(repo.as_ref()).await?;
+            let delivered = deliver(repo.as_ref(), notifier.as_ref()).await?;
+            Ok(format!("emitted {} reminders, delivered {} events", emitted, delivered))
+        }
+    })
+}
@@ -0,0 +1,443 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::migrate::{MigrateError, Migrator};
+use std::sync::Arc;
+use crate::audit::{AuditEvent, Entry, Filter};
+use crate::oauth::{AuthorizationCode, AuthorizationRequest, Client, Grant};
+use crate::rbac::Grants;
+use crate::session::ClientInfo;
+use crate::todo::{Cursor, Filter as TodoFilter, Sort};
+
+mod sqlite;     // src/repository/sqlite.rs 사용
+mod postgres;   // src/repository/postgres.rs 사용
+
+pub use sqlite::SqliteRepository;
+pub use postgres::PgRepository;
+
+// DB 접근 계층
+// 핸들러와 도메인 모듈은 SQL 대신 저장소 트레이트를 사용하고, database.url의 scheme으로 구현을 선택
+//   sqlite:...              -> SqliteRepository(migrations/sqlite)
+//   postgres://, postgresql:// -> PgRepository(migrations/postgres)
+// 시각은 두 구현 모두 "YYYY-MM-DD HH:MM:SS"(UTC) 문자열로 주고받음
+
+// DB 종류
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+}
+
+impl Backend {
+    // DB url의 scheme으로 종류 판단(지원하지 않는 scheme이면 None)
+    pub fn from_url(url: &str) -> Option<Self> {
+        let (scheme, _) = url.split_once(':')?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "sqlite" => Some(Backend::Sqlite),
+            "postgres" | "postgresql" => Some(Backend::Postgres),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Backend::Sqlite => "sqlite",
+            Backend::Postgres => "postgres",
+        }
+    }
+}
+
+// DB url에 맞는 저장소 생성(연결 풀 생성까지, 마이그레이션은 migrate로 별도 실행)
+pub async fn connect(url: &str) -> anyhow::Result<Arc<dyn Repository>> {
+    let repository: Arc<dyn Repository> = match Backend::from_url(url) {
+        Some(Backend::Sqlite) => Arc::new(SqliteRepository::connect(url).await?),
+        Some(Backend::Postgres) => Arc::new(PgRepository::connect(url).await?),
+        None => anyhow::bail!("Unsupported database url scheme (expected sqlite: or postgres://)"),
+    };
+    Ok(repository)
+}
+
+// 모든 저장소 트레이트를 구현한 DB(핸들러에서 web::Data<dyn Repository>로 사용)
+pub trait Repository:
+    Database + UserRepository + TodoRepository + TodoEventRepository + ProjectRepository + TagRepository + SessionRepository
+    + RefreshTokenRepository + RevocationRepository + LockoutRepository + RateLimitRepository + PasswordResetRepository
+    + EmailVerificationRepository + MfaRepository + RoleRepository + OAuthRepository + AuditRepository {}
+
+impl<T> Repository for T where
+    T: Database + UserRepository + TodoRepository + TodoEventRepository + ProjectRepository + TagRepository + SessionRepository
+        + RefreshTokenRepository + RevocationRepository + LockoutRepository + RateLimitRepository + PasswordResetRepository
+        + EmailVerificationRepository + MfaRepository + RoleRepository + OAuthRepository + AuditRepository {}
+
+// 연결 풀 및 마이그레이션(준비 상태 확인, 메트릭, 서버 시작/종료)
+#[async_trait]
+pub trait Database: Send+Sync {
+    fn backend(&self) -> Backend;
+    // 이 DB 종류의 마이그레이션 목록(빌드에 포함됨)
+    fn migrator(&self) -> &'static Migrator;
+    // 연결 풀의 (전체, 유휴) 연결 수
+    fn connection_counts(&self) -> (u32, usize);
+    async fn migrate(&self) -> Result<(), MigrateError>;
+    async fn ping(&self) -> Result<(), sqlx::Error>;
+    // 적용에 성공한 마이그레이션 버전 목록
+    async fn applied_migrations(&self) -> Result<Vec<i64>, sqlx::Error>;
+    async fn close(&self);
+}
+
+// 회원가입 실패 사유
+#[derive(Debug)]
+pub enum NewUserError {
+    UsernameTaken,
+    EmailTaken,
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for NewUserError {
+    fn from(e: sqlx::Error) -> Self {
+        NewUserError::Database(e)
+    }
+}
+
+// 로그인 시 비밀번호 확인용 정보
+pub struct Credentials {
+    pub id: i64,
+    pub password_hash: String,
+}
+
+// 관리 API 사용자 목록 항목
+#[derive(Serialize)]
+pub struct UserSummary {
+    pub id: i64,
+    pub username: String,
+    pub created_at: Option<String>,
+    pub disabled_at: Option<String>,
+    pub roles: Vec<String>,
+    pub locked_until: Option<String>,   // 로그인 실패로 잠겨 있으면 잠금 해제 시각
+}
+
+#[async_trait]
+pub trait UserRepository: Send+Sync {
+    // 사용자 생성과 역할 부여를 한 트랜잭션으로 처리한 뒤 새 사용자 id 반환(username 또는 email 중복이면 해당 에러)
+    // 역할이 존재하지 않으면 RowNotFound(사용자도 만들어지지 않음)
+    async fn create_user(&self, username: &str, password_hash: &str, email: &str, role: &str) -> Result<i64, NewUserError>;
+    async fn find_credentials(&self, username: &str) -> Result<Option<Credentials>, sqlx::Error>;
+    async fn find_user_id(&self, username: &str) -> Result<Option<i64>, sqlx::Error>;
+    // id와 사용자 이름이 같은 사용자를 가리키는지 확인
+    async fn user_matches(&self, user_id: i64, username: &str) -> Result<bool, sqlx::Error>;
+    // 관리자에 의해 비활성화되지 않은 계정인지 확인(사용자가 없으면 RowNotFound)
+    async fn is_active(&self, user_id: i64) -> Result<bool, sqlx::Error>;
+    async fn is_email_verified(&self, user_id: i64) -> Result<bool, sqlx::Error>;
+    // 메일 수신자 (user id, 주소), 이메일 인증 도입 이전에 가입하여 주소가 없는 계정은 사용자 이름
+    async fn mail_recipient(&self, username: &str) -> Result<Option<(i64, String)>, sqlx::Error>;
+    async fn find_unverified_user_id(&self, email: &str) -> Result<Option<i64>, sqlx::Error>;
+    // 비밀번호 해시를 바꾸고 같은 트랜잭션에서 current_session_id를 제외한 세션과 refresh 토큰 폐기, 폐기된 세션 수 반환
+    async fn change_password_hash(&self, user_id: i64, password_hash: &str, current_session_id: &str) -> Result<u64, sqlx::Error>;
+    // 저장된 해시가 old_hash 그대로일 때만 교체, 교체했으면 true
+    async fn replace_password_hash(&self, user_id: i64, old_hash: &str, new_hash: &str) -> Result<bool, sqlx::Error>;
+    async fn set_disabled(&self, user_id: i64, disabled: bool) -> Result<(), sqlx::Error>;
+    // 사용자 삭제(todo, 세션, refresh 토큰 등은 외래 키로 함께 삭제), 존재했으면 true
+    async fn delete_user(&self, username: &str) -> Result<bool, sqlx::Error>;
+    async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<UserSummary>, sqlx::Error>;
+    async fn count_users(&self) -> Result<i64, sqlx::Error>;
+}
+
+#[derive(Serialize)]
+pub struct Todo {
+    pub id: i64,
+    pub title: String,
+    pub notes: Option<String>,
+    pub completed: bool,
+    pub due_date: Option<String>,   // YYYY-MM-DD
+    pub tags: Vec<String>,          // 이름순
+    pub project_id: Option<i64>,
+    pub parent_id: Option<i64>,     // 하위 todo(subtask)면 상위 todo id
+    pub recurrence: Option<String>, // 정규화한 RRULE(recurrence::Rule)
+    pub reminder_offset_minutes: Option<i64>,   // 마감일 0시(UTC) 기준 알림 시각(분)
+    pub remind_at: Option<String>,  // 마감일과 reminder_offset_minutes로 계산한 알림 시각(읽기 전용)
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 새 todo
+pub struct NewTodo {
+    pub title: String,
+    pub notes: Option<String>,
+    pub due_date: Option<String>,
+    pub tags: Vec<String>,
+    pub project_id: Option<i64>,
+    pub parent_id: Option<i64>,
+    pub recurrence: Option<String>,
+    pub reminder_offset_minutes: Option<i64>,
+}
+
+// todo는 모두 소유자 범위 내에서만 조회 및 변경
+#[async_trait]
+pub trait TodoRepository: Send+Sync {
+    // 조건에 맞는 todo를 정렬 순서대로 최대 limit개 조회
+    // cursor가 있으면 그 todo 다음부터, 이전 페이지(backward)면 그 todo 앞쪽을 역순으로 조회
+    async fn list_todos(&self, owner_id: i64, filter: &TodoFilter, sort: Sort, cursor: Option<&Cursor>, limit: i64) -> Result<Vec<Todo>, sqlx::Error>;
+    async fn count_todos(&self, owner_id: i64, filter: &TodoFilter) -> Result<i64, sqlx::Error>;
+    async fn find_todo(&self, owner_id: i64, id: i64) -> Result<Option<Todo>, sqlx::Error>;
+    // 태그는 이름으로 지정하고 없으면 새로 만듦
+    async fn create_todo(&self, owner_id: i64, todo: &NewTodo) -> Result<Todo, sqlx::Error>;
+    // todo.id의 제목, 메모, 완료 상태, 마감일, 태그, 프로젝트, 상위 todo, 반복 규칙, 알림을 저장한 뒤 갱신된 todo 반환
+    // 하위 todo들도 모두 같은 프로젝트로 옮기고, 알림 시각이 바뀌면 알림을 다시 보낼 수 있도록 함
+    async fn update_todo(&self, owner_id: i64, todo: &Todo) -> Result<Option<Todo>, sqlx::Error>;
+    // 하위 todo는 외래 키로 함께 삭제
+    async fn delete_todo(&self, owner_id: i64, id: i64) -> Result<bool, sqlx::Error>;
+    // id부터 최상위 todo까지의 id 목록(상위 todo 변경 시 순환 확인용)
+    async fn todo_ancestors(&self, owner_id: i64, id: i64) -> Result<Vec<i64>, sqlx::Error>;
+    // 반복 todo 완료: 한 트랜잭션에서 반복 규칙을 지우고 todo를 저장한 뒤 다음 occurrence(next) 생성
+    // 반복 규칙이 이미 지워졌으면(같은 occurrence를 동시에 완료한 다른 요청) todo만 저장하고 다음 occurrence는 만들지 않음
+    // 반환값: (갱신된 todo, 만든 다음 occurrence), todo가 없으면 None
+    async fn complete_recurring_todo(&self, owner_id: i64, todo: &Todo, next: Option<&NewTodo>) -> Result<Option<(Todo, Option<Todo>)>, sqlx::Error>;
+    // 소유자 개념 도입 이전에 만들어져 소유자가 없는 todo를 모두 owner_id에게 이관, 이관한 수 반환
+    // (PostgreSQL 스키마는 처음부터 소유자가 필수이므로 항상 0)
+    async fn assign_ownerless_todos(&self, owner_id: i64) -> Result<u64, sqlx::Error>;
+}
+
+// todo 이벤트(알림 등, 이벤트 발생 시점의 todo 정보)
+#[derive(Serialize, Debug)]
+pub struct TodoEvent {
+    pub id: i64,
+    pub user_id: i64,
+    pub todo_id: Option<i64>,   // todo가 삭제되면 None
+    pub kind: String,
+    pub title: String,
+    pub due_date: String,
+    pub remind_at: String,
+    pub created_at: String,
+}
+
+// todo 이벤트 outbox(알림 작업이 기록하고 notifier로 전달)
+#[async_trait]
+pub trait TodoEventRepository: Send+Sync {
+    // 알림 시각이 지났고 완료되지 않은 todo마다 reminder 이벤트를 기록(todo당 한 번), 기록한 수 반환
+    async fn emit_due_reminders(&self) -> Result<u64, sqlx::Error>;
+    // 아직 전달하지 않은 이벤트(기록순, 최대 limit개)
+    async fn undelivered_todo_events(&self, limit: i64) -> Result<Vec<TodoEvent>, sqlx::Error>;
+    async fn mark_todo_event_delivered(&self, id: i64) -> Result<(), sqlx::Error>;
+    // 사용자의 이벤트 중 id가 after_id보다 큰 것(기록순, 최대 limit개)
+    async fn list_todo_events(&self, owner_id: i64, after_id: i64, limit: i64) -> Result<Vec<TodoEvent>, sqlx::Error>;
+    // 전달한 지 keep_secs보다 오래된 이벤트 삭제
+    async fn prune_todo_events(&self, keep_secs: i64) -> Result<u64, sqlx::Error>;
+}
+
+// 사용자별로 이름이 유일한 항목(프로젝트, 태그)의 생성 및 변경 실패 사유
+#[derive(Debug)]
+pub enum NameError {
+    Taken,
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for NameError {
+    fn from(e: sqlx::Error) -> Self {
+        NameError::Database(e)
+    }
+}
+
+#[derive(Serialize)]
+pub struct Project {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub todo_count: i64,    // 하위 todo 포함
+}
+
+// 프로젝트 삭제 시 소속 todo 처리 방법
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectTodos {
+    Delete,                 // 함께 삭제
+    Reassign(Option<i64>),  // 다른 프로젝트로 이동(None이면 프로젝트 없음)
+}
+
+#[async_trait]
+pub trait ProjectRepository: Send+Sync {
+    async fn list_projects(&self, owner_id: i64) -> Result<Vec<Project>, sqlx::Error>;
+    async fn find_project(&self, owner_id: i64, id: i64) -> Result<Option<Project>, sqlx::Error>;
+    async fn create_project(&self, owner_id: i64, name: &str) -> Result<Project, NameError>;
+    async fn rename_project(&self, owner_id: i64, id: i64, name: &str) -> Result<Option<Project>, NameError>;
+    // 소속 todo를 처리한 뒤 프로젝트 삭제, 존재했으면 true
+    async fn delete_project(&self, owner_id: i64, id: i64, todos: ProjectTodos) -> Result<bool, sqlx::Error>;
+}
+
+#[derive(Serialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub color: String,      // #rrggbb
+    pub todo_count: i64,
+}
+
+#[async_trait]
+pub trait TagRepository: Send+Sync {
+    async fn list_tags(&self, owner_id: i64) -> Result<Vec<Tag>, sqlx::Error>;
+    async fn find_tag(&self, owner_id: i64, id: i64) -> Result<Option<Tag>, sqlx::Error>;
+    async fn create_tag(&self, owner_id: i64, name: &str, color: &str) -> Result<Tag, NameError>;
+    // tag.id의 이름과 색상 저장
+    async fn update_tag(&self, owner_id: i64, tag: &Tag) -> Result<Option<Tag>, NameError>;
+    // todo에 붙은 태그도 함께 제거
+    async fn delete_tag(&self, owner_id: i64, id: i64) -> Result<bool, sqlx::Error>;
+}
+
+// 세션 목록 항목
+#[derive(Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub device_label: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: String,
+    pub last_seen_at: String,
+}
+
+#[async_trait]
+pub trait SessionRepository: Send+Sync {
+    // grant: OAuth 클라이언트 앱 로그인이면 클라이언트와 승인된 scope, 직접 로그인이면 None
+    async fn create_session(&self, id: &str, user_id: i64, device_label: Option<&str>, client: &ClientInfo, grant: Option<&Grant>) -> Result<(), sqlx::Error>;
+    // 폐기되지 않은 세션이면 true, 마지막 사용 시각이 interval_secs초보다 오래되었으면 갱신
+    async fn touch_session(&self, id: &str, interval_secs: i64) -> Result<bool, sqlx::Error>;
+    // 활성 세션 목록(최근 사용 순)
+    async fn list_sessions(&self, username: &str) -> Result<Vec<SessionInfo>, sqlx::Error>;
+    // 사용자의 세션 하나와 그 refresh 토큰 family 폐기, 해당 사용자의 활성 세션이 아니면 false
+    async fn revoke_session(&self, username: &str, id: &str) -> Result<bool, sqlx::Error>;
+    // 아래 두 함수는 refresh 토큰도 함께 폐기하고 폐기된 세션 수 반환
+    async fn revoke_other_sessions(&self, username: &str, current_id: &str) -> Result<u64, sqlx::Error>;
+    async fn revoke_all_sessions(&self, user_id: i64) -> Result<u64, sqlx::Error>;
+}
+
+// 제출된 refresh 토큰의 상태
+pub struct RefreshTokenState {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    pub family_id: String,
+    pub used: bool,
+    pub revoked: bool,
+    pub expired: bool,
+    pub grant: Option<Grant>,   // 세션을 만든 OAuth 클라이언트(직접 로그인이면 None)
+}
+
+#[async_trait]
+pub trait RefreshTokenRepository: Send+Sync {
+    async fn insert_refresh_token(&self, user_id: i64, family_id: &str, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error>;
+    // 세션이 삭제된(OAuth 클라이언트 삭제 등) family의 토큰이면 None
+    async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshTokenState>, sqlx::Error>;
+    // 아직 사용되지 않은 토큰이면 사용 처리와 같은 family의 새 토큰 저장을 한 트랜잭션으로 처리
+    // 동시 요청 등으로 이미 사용된 토큰이면 false
+    async fn rotate_refresh_token(&self, current: &RefreshTokenState, new_token_hash: &str, ttl_secs: u64) -> Result<bool, sqlx::Error>;
+    async fn revoke_token_family(&self, family_id: &str) -> Result<(), sqlx::Error>;
+}
+
+// 폐기된 access 토큰(jti) 목록
+#[async_trait]
+pub trait RevocationRepository: Send+Sync {
+    // 이미 등록된 jti면 무시, expires_at은 토큰 exp(unix 시각)
+    async fn revoke_token(&self, jti: &str, expires_at: i64) -> Result<(), sqlx::Error>;
+    async fn is_token_revoked(&self, jti: &str) -> Result<bool, sqlx::Error>;
+    // 아직 만료되지 않은 폐기 토큰 수
+    async fn count_revoked_tokens(&self) -> Result<i64, sqlx::Error>;
+    async fn prune_revoked_tokens(&self) -> Result<u64, sqlx::Error>;
+}
+
+// 로그인 실패 기록 및 잠금(login_attempts)
+#[async_trait]
+pub trait LockoutRepository: Send+Sync {
+    // 잠겨 있으면 남은 잠금 시간(초)
+    async fn lock_remaining_secs(&self, key: &str) -> Result<Option<i64>, sqlx::Error>;
+    // 실패 횟수를 1 늘린 뒤 반환(마지막 실패가 window_secs보다 오래되었으면 1부터 다시 계산)
+    async fn increment_login_failures(&self, key: &str, window_secs: i64) -> Result<i64, sqlx::Error>;
+    async fn lock_login(&self, key: &str, secs: i64) -> Result<(), sqlx::Error>;
+    // 실패 기록 삭제, 기록이 있었으면 true
+    async fn clear_login_failures(&self, key: &str) -> Result<bool, sqlx::Error>;
+    // 잠금이 끝났고 마지막 실패가 window_secs보다 오래된 기록 삭제
+    async fn prune_login_attempts(&self, window_secs: i64) -> Result<u64, sqlx::Error>;
+}
+
+// 요청 횟수 제한(rate_limits)
+#[async_trait]
+pub trait RateLimitRepository: Send+Sync {
+    // 요청 횟수를 1 늘린 뒤 (횟수, 기간이 끝날 때까지 남은 초) 반환
+    // 기간이 끝났거나 기록이 없으면 1부터 다시 계산하고 window_secs 뒤를 새 기간의 끝으로 설정
+    async fn hit_rate_limit(&self, key: &str, window_secs: i64) -> Result<(i64, i64), sqlx::Error>;
+    // 기간이 끝난 기록 삭제
+    async fn prune_rate_limits(&self) -> Result<u64, sqlx::Error>;
+}
+
+// 비밀번호 재설정 토큰(해시로 저장)
+#[async_trait]
+pub trait PasswordResetRepository: Send+Sync {
+    // 아직 사용하지 않은 이전 토큰을 무효화한 뒤 새 토큰 저장
+    async fn create_password_reset(&self, user_id: i64, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error>;
+    // 유효한 토큰의 소유자 (user id, username), 토큰은 소모하지 않음
+    async fn find_password_reset(&self, token_hash: &str) -> Result<Option<(i64, String)>, sqlx::Error>;
+    // 유효한 토큰이면 사용 처리하고 같은 트랜잭션에서 비밀번호 해시를 바꾼 뒤 user id 반환(동시 요청에서도 한 번만 성공)
+    async fn consume_password_reset(&self, token_hash: &str, password_hash: &str) -> Result<Option<i64>, sqlx::Error>;
+    async fn prune_password_resets(&self) -> Result<u64, sqlx::Error>;
+}
+
+// 이메일 인증 토큰(해시로 저장)
+#[async_trait]
+pub trait EmailVerificationRepository: Send+Sync {
+    // 아직 사용하지 않은 이전 토큰을 무효화한 뒤 새 토큰 저장
+    async fn create_email_verification(&self, user_id: i64, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error>;
+    // 유효한 토큰이면 사용 처리하고 계정을 인증 상태로 변경한 뒤 user id 반환
+    async fn consume_email_verification(&self, token_hash: &str) -> Result<Option<i64>, sqlx::Error>;
+    // 만료된 토큰과 keep_used_secs보다 오래된 사용된 토큰 삭제
+    async fn prune_email_verifications(&self, keep_used_secs: i64) -> Result<u64, sqlx::Error>;
+}
+
+// TOTP 설정 및 복구 코드(해시로 저장)
+#[async_trait]
+pub trait MfaRepository: Send+Sync {
+    async fn totp_enabled(&self, user_id: i64) -> Result<bool, sqlx::Error>;
+    // 확인 전 상태의 비밀 키 저장(이전 등록 시도는 교체), 이미 활성화되어 있으면 false
+    async fn begin_totp_enrollment(&self, user_id: i64, secret: &str) -> Result<bool, sqlx::Error>;
+    async fn pending_totp_secret(&self, user_id: i64) -> Result<Option<String>, sqlx::Error>;
+    async fn active_totp_secret(&self, user_id: i64) -> Result<Option<String>, sqlx::Error>;
+    // 활성화하고 복구 코드를 교체(한 트랜잭션)
+    async fn enable_totp(&self, user_id: i64, step: i64, recovery_code_hashes: &[String]) -> Result<(), sqlx::Error>;
+    // 마지막으로 사용된 단계보다 새로운 단계일 때만 갱신하고 true
+    async fn advance_totp_step(&self, user_id: i64, step: i64) -> Result<bool, sqlx::Error>;
+    // 사용하지 않은 복구 코드면 사용 처리하고 true
+    async fn use_recovery_code(&self, user_id: i64, code_hash: &str) -> Result<bool, sqlx::Error>;
+    async fn remaining_recovery_codes(&self, user_id: i64) -> Result<i64, sqlx::Error>;
+    async fn disable_totp(&self, user_id: i64) -> Result<(), sqlx::Error>;
+}
+
+// 역할 및 권한
+#[async_trait]
+pub trait RoleRepository: Send+Sync {
+    // 사용자의 역할과 권한(이름순)
+    async fn grants_for(&self, user_id: i64) -> Result<Grants, sqlx::Error>;
+    // 역할 부여(이미 있으면 무시), 사용자나 역할이 없으면 false
+    async fn assign_role(&self, username: &str, role: &str) -> Result<bool, sqlx::Error>;
+    // 역할 회수, 회수된 역할이 있으면 true
+    async fn remove_role(&self, username: &str, role: &str) -> Result<bool, sqlx::Error>;
+    async fn role_exists(&self, role: &str) -> Result<bool, sqlx::Error>;
+}
+
+// OAuth 클라이언트 및 인가 코드
+#[async_trait]
+pub trait OAuthRepository: Send+Sync {
+    // 클라이언트와 redirect_uri 저장 후 내부 id 반환
+    async fn insert_client(&self, client_id: &str, secret_hash: Option<&str>, name: &str, redirect_uris: &[String]) -> Result<i64, sqlx::Error>;
+    async fn find_client(&self, client_id: &str) -> Result<Option<Client>, sqlx::Error>;
+    // 등록 순
+    async fn list_clients(&self) -> Result<Vec<Client>, sqlx::Error>;
+    // 클라이언트 삭제(인가 코드도 함께 삭제), 존재했으면 true
+    async fn delete_client(&self, client_id: &str) -> Result<bool, sqlx::Error>;
+    async fn insert_authorization_code(&self, code_hash: &str, request: &AuthorizationRequest<'_>, ttl_secs: u64) -> Result<(), sqlx::Error>;
+    // 유효한 코드면 사용 처리 후 정보 반환(동시 요청에서도 한 번만 성공)
+    async fn consume_authorization_code(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, sqlx::Error>;
+    async fn prune_authorization_codes(&self) -> Result<u64, sqlx::Error>;
+}
+
+// 감사 로그(추가만 가능)
+#[async_trait]
+pub trait AuditRepository: Send+Sync {
+    async fn insert_audit_event(&self, client: &ClientInfo, entry: &Entry<'_>) -> Result<(), sqlx::Error>;
+    // 조건에 맞는 이벤트 한 페이지(최신순)
+    async fn list_audit_events(&self, filter: &Filter, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, sqlx::Error>;
+    async fn count_audit_events(&self, filter: &Filter) -> Result<i64, sqlx::Error>;
+    // 조건에 맞는 모든 이벤트(기록순)
+    async fn export_audit_events(&self, filter: &Filter) -> Result<Vec<AuditEvent>, sqlx::Error>;
+}
@@ -0,0 +1,1139 @@
+use async_trait::async_trait;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions, PgRow};
+use sqlx::{Postgres, Row, Transaction};
+use crate::todo::SortField;
+use std::str::FromStr;
+use crate::audit::{AuditEvent, Entry, Filter};
+use crate::oauth::{AuthorizationCode, AuthorizationRequest, Client, Grant};
+use crate::rbac::Grants;
+use crate::session::ClientInfo;
+use super::*;
+
+// PostgreSQL 마이그레이션(컴파일 타임에 ./migrations/postgres 폴더를 읽음)
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+// 유일성 제약 위반 SQLSTATE
+const UNIQUE_VIOLATION: &str = "23505";
+
+// PostgreSQL 저장소
+// 시각은 timestamptz로 저장하고, 연결마다 세션 시간대를 UTC로 설정하여
+// to_char 결과와 문자열 비교 조건("YYYY-MM-DD HH:MM:SS")이 SQLite 구현과 같은 UTC 기준이 되도록 함
+#[derive(Clone)]
+pub struct PgRepository {
+    pool: PgPool,
+}
+
+impl PgRepository {
+    pub fn new(pool: PgPool) -> Self {
+        PgRepository { pool }
+    }
+
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let options = PgConnectOptions::from_str(url)?.options([("timezone", "UTC")]);
+        Ok(PgRepository::new(PgPoolOptions::new().connect_with(options).await?))
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl Database for PgRepository {
+    fn backend(&self) -> Backend {
+        Backend::Postgres
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &MIGRATOR
+    }
+
+    fn connection_counts(&self) -> (u32, usize) {
+        (self.pool.size(), self.pool.num_idle())
+    }
+
+    async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("select 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query("select version from _sqlx_migrations where success").fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("version")).collect())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+#[async_trait]
+impl UserRepository for PgRepository {
+    async fn create_user(&self, username: &str, password_hash: &str, email: &str, role: &str) -> Result<i64, NewUserError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("insert into users(username, password_hash, email) values ($1, $2, $3) returning id")
+            .bind(username).bind(password_hash).bind(email)
+            .fetch_one(&mut tx).await;
+        let user_id: i64 = match result {
+            Ok(row) => row.get("id"),
+            Err(sqlx::Error::Database(e)) if e.code().as_deref()==Some(UNIQUE_VIOLATION) => {
+                return if e.constraint()==Some("users_email_key") {
+                    Err(NewUserError::EmailTaken)
+                } else {
+                    Err(NewUserError::UsernameTaken)
+                };
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let assigned = sqlx::query("insert into user_roles(user_id, role_id) select $1, id from roles where name=$2")
+            .bind(user_id).bind(role).execute(&mut tx).await?;
+        if assigned.rows_affected()==0 {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+        tx.commit().await?;
+        Ok(user_id)
+    }
+
+    async fn find_credentials(&self, username: &str) -> Result<Option<Credentials>, sqlx::Error> {
+        let row = sqlx::query("select id, password_hash from users where username=$1").bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| Credentials { id: r.get("id"), password_hash: r.get("password_hash") }))
+    }
+
+    async fn find_user_id(&self, username: &str) -> Result<Option<i64>, sqlx::Error> {
+        let row = sqlx::query("select id from users where username=$1").bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("id")))
+    }
+
+    async fn user_matches(&self, user_id: i64, username: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select 1 from users where id=$1 and username=$2").bind(user_id).bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+
+    async fn is_active(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select disabled_at is null as active from users where id=$1").bind(user_id)
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("active"))
+    }
+
+    async fn is_email_verified(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select email_verified_at is not null as verified from users where id=$1").bind(user_id)
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("verified"))
+    }
+
+    async fn mail_recipient(&self, username: &str) -> Result<Option<(i64, String)>, sqlx::Error> {
+        let row = sqlx::query("select id, coalesce(email, username) as recipient from users where username=$1").bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| (r.get("id"), r.get("recipient"))))
+    }
+
+    async fn find_unverified_user_id(&self, email: &str) -> Result<Option<i64>, sqlx::Error> {
+        let row = sqlx::query("select id from users where email=$1 and email_verified_at is null").bind(email)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("id")))
+    }
+
+    async fn change_password_hash(&self, user_id: i64, password_hash: &str, current_session_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update users set password_hash=$1 where id=$2").bind(password_hash).bind(user_id)
+            .execute(&mut tx).await?;
+        let result = sqlx::query("update sessions set revoked_at=now() where user_id=$1 and id<>$2 and revoked_at is null")
+            .bind(user_id).bind(current_session_id).execute(&mut tx).await?;
+        sqlx::query("update refresh_tokens set revoked_at=now() where user_id=$1 and family_id<>$2 and revoked_at is null")
+            .bind(user_id).bind(current_session_id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn replace_password_hash(&self, user_id: i64, old_hash: &str, new_hash: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("update users set password_hash=$1 where id=$2 and password_hash=$3")
+            .bind(new_hash).bind(user_id).bind(old_hash)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn set_disabled(&self, user_id: i64, disabled: bool) -> Result<(), sqlx::Error> {
+        let query = if disabled {
+            "update users set disabled_at=now() where id=$1 and disabled_at is null"
+        } else {
+            "update users set disabled_at=null where id=$1"
+        };
+        sqlx::query(query).bind(user_id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_user(&self, username: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from users where username=$1").bind(username)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<UserSummary>, sqlx::Error> {
+        let rows = sqlx::query(
+            "select u.id, u.username, to_char(u.created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at, \
+                 to_char(u.disabled_at, 'YYYY-MM-DD HH24:MI:SS') as disabled_at, \
+                 (select string_agg(r.name, ',' order by r.name) from user_roles ur join roles r on r.id = ur.role_id where ur.user_id = u.id) as roles, \
+                 (select to_char(a.locked_until, 'YYYY-MM-DD HH24:MI:SS') from login_attempts a \
+                  where a.key = 'user:' || u.username and a.locked_until > now()) as locked_until \
+             from users u order by u.id limit $1 offset $2")
+            .bind(limit).bind(offset)
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| UserSummary {
+            id: r.get("id"),
+            username: r.get("username"),
+            created_at: r.get("created_at"),
+            disabled_at: r.get("disabled_at"),
+            roles: r.get::<Option<String>, _>("roles")
+                .map(|roles| roles.split(',').map(String::from).collect())
+                .unwrap_or_default(),
+            locked_until: r.get("locked_until"),
+        }).collect())
+    }
+
+    async fn count_users(&self) -> Result<i64, sqlx::Error> {
+        Ok(sqlx::query("select count(*) as total from users").fetch_one(&self.pool).await?.get("total"))
+    }
+}
+
+// 모든 조회 쿼리에서 공통으로 사용하는 컬럼 목록(날짜와 시각은 SQLite 구현과 같은 문자열 형식)
+const TODO_COLUMNS: &str = "id, title, notes, completed, to_char(due_date, 'YYYY-MM-DD') as due_date, project_id, parent_id, \
+     to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at, to_char(updated_at, 'YYYY-MM-DD HH24:MI:SS') as updated_at, \
+     recurrence, reminder_offset_minutes::bigint as reminder_offset_minutes, \
+     to_char(due_date + make_interval(mins => reminder_offset_minutes), 'YYYY-MM-DD HH24:MI:SS') as remind_at, \
+     array(select t.name from todo_tags tt join tags t on t.id = tt.tag_id where tt.todo_id = todos.id) as tags";
+
+// 목록 조회 조건($1~$8, 조건이 없는 항목은 null로 바인딩하여 무시)
+const TODO_FILTER_WHERE: &str = "where owner_id=$1 and ($2::boolean is null or completed=$2) \
+     and ($3::text is null or due_date>=$3::date) and ($4::text is null or due_date<=$4::date) \
+     and ($5::text is null or exists (select 1 from todo_tags tt join tags t on t.id = tt.tag_id where tt.todo_id = todos.id and t.name=$5)) \
+     and ($6::text is null or search @@ plainto_tsquery('simple', $6)) \
+     and ($7::bigint is null or coalesce(project_id, 0)=$7) and ($8::bigint is null or coalesce(parent_id, 0)=$8)";
+
+fn bind_todo_filter<'q>(
+    query: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    owner_id: i64,
+    filter: &'q TodoFilter,
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    query.bind(owner_id).bind(filter.completed).bind(&filter.due_from).bind(&filter.due_to).bind(&filter.tag).bind(&filter.search)
+        .bind(filter.project_id).bind(filter.parent_id)
+}
+
+// 정렬 식(todo::Sort::key와 같은 값, 시각은 초 단위 문자열로 비교해야 커서의 정렬 키와 일치)
+fn todo_sort_key(sort: Sort) -> String {
+    match sort.field {
+        SortField::CreatedAt => "to_char(created_at, 'YYYY-MM-DD HH24:MI:SS')".to_string(),
+        SortField::UpdatedAt => "to_char(updated_at, 'YYYY-MM-DD HH24:MI:SS')".to_string(),
+        SortField::DueDate => format!("coalesce(to_char(due_date, 'YYYY-MM-DD'), '{}')", sort.no_due_date()),
+        SortField::Title => "title".to_string(),
+    }
+}
+
+fn todo_from_row(r: &PgRow) -> Todo {
+    let mut tags: Vec<String> = r.get("tags");
+    tags.sort();
+    Todo {
+        id: r.get("id"),
+        title: r.get("title"),
+        notes: r.get("notes"),
+        completed: r.get("completed"),
+        due_date: r.get("due_date"),
+        tags,
+        project_id: r.get("project_id"),
+        parent_id: r.get("parent_id"),
+        recurrence: r.get("recurrence"),
+        reminder_offset_minutes: r.get("reminder_offset_minutes"),
+        remind_at: r.get("remind_at"),
+        created_at: r.get("created_at"),
+        updated_at: r.get("updated_at"),
+    }
+}
+
+// todo의 태그를 주어진 이름 목록으로 교체(없는 태그는 새로 만듦)
+async fn set_todo_tags(tx: &mut Transaction<'_, Postgres>, owner_id: i64, todo_id: i64, tags: &[String]) -> Result<(), sqlx::Error> {
+    sqlx::query("delete from todo_tags where todo_id=$1").bind(todo_id).execute(&mut *tx).await?;
+    for tag in tags {
+        sqlx::query("insert into tags(owner_id, name) values ($1, $2) on conflict do nothing").bind(owner_id).bind(tag)
+            .execute(&mut *tx).await?;
+        sqlx::query("insert into todo_tags(todo_id, tag_id) select $1, id from tags where owner_id=$2 and name=$3 on conflict do nothing")
+            .bind(todo_id).bind(owner_id).bind(tag)
+            .execute(&mut *tx).await?;
+    }
+    Ok(())
+}
+
+// todo 추가 후 id 반환(태그 포함)
+async fn insert_todo(tx: &mut Transaction<'_, Postgres>, owner_id: i64, todo: &NewTodo) -> Result<i64, sqlx::Error> {
+    let id: i64 = sqlx::query(
+        "insert into todos(owner_id, title, notes, due_date, project_id, parent_id, recurrence, reminder_offset_minutes) \
+         values ($1, $2, $3, $4::date, $5, $6, $7, $8) returning id")
+        .bind(owner_id).bind(&todo.title).bind(&todo.notes).bind(&todo.due_date).bind(todo.project_id).bind(todo.parent_id)
+        .bind(&todo.recurrence).bind(todo.reminder_offset_minutes)
+        .fetch_one(&mut *tx).await?.get("id");
+    set_todo_tags(tx, owner_id, id, &todo.tags).await?;
+    Ok(id)
+}
+
+// todo 저장 및 하위 todo 프로젝트 이동, todo가 없으면 false
+async fn save_todo(tx: &mut Transaction<'_, Postgres>, owner_id: i64, todo: &Todo) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "update todos set title=$1, notes=$2, completed=$3, due_date=$4::date, project_id=$5, parent_id=$6, \
+             recurrence=$7, reminder_offset_minutes=$8, updated_at=now(), \
+             reminded_at = case when due_date is not distinct from $4::date \
+                 and reminder_offset_minutes is not distinct from $8::integer then reminded_at end \
+         where id=$9 and owner_id=$10")
+        .bind(&todo.title).bind(&todo.notes).bind(todo.completed).bind(&todo.due_date).bind(todo.project_id).bind(todo.parent_id)
+        .bind(&todo.recurrence).bind(todo.reminder_offset_minutes)
+        .bind(todo.id).bind(owner_id)
+        .execute(&mut *tx).await?;
+    if result.rows_affected()==0 {
+        return Ok(false);
+    }
+    set_todo_tags(tx, owner_id, todo.id, &todo.tags).await?;
+    // 하위 todo 전체를 같은 프로젝트로 이동
+    sqlx::query(
+        "with recursive subtree(id) as (select id from todos where parent_id=$1 \
+         union all select t.id from todos t join subtree s on t.parent_id = s.id) \
+         update todos set project_id=$2 where id in (select id from subtree)")
+        .bind(todo.id).bind(todo.project_id)
+        .execute(&mut *tx).await?;
+    Ok(true)
+}
+
+#[async_trait]
+impl TodoRepository for PgRepository {
+    async fn list_todos(&self, owner_id: i64, filter: &TodoFilter, sort: Sort, cursor: Option<&Cursor>, limit: i64) -> Result<Vec<Todo>, sqlx::Error> {
+        let key = todo_sort_key(sort);
+        // 이전 페이지는 기준 todo 앞쪽을 역순으로 조회
+        let descending = sort.descending != cursor.is_some_and(|c| c.backward);
+        let (order, op) = if descending { ("desc", "<") } else { ("asc", ">") };
+        let (after, limit_param) = if cursor.is_some() { (format!(" and ({}, id) {} ($9, $10)", key, op), "$11") } else { (String::new(), "$9") };
+        let sql = format!("select {} from todos {}{} order by {} {}, id {} limit {}",
+            TODO_COLUMNS, TODO_FILTER_WHERE, after, key, order, order, limit_param);
+        let mut query = bind_todo_filter(sqlx::query(&sql), owner_id, filter);
+        if let Some(cursor) = cursor {
+            query = query.bind(&cursor.key).bind(cursor.id);
+        }
+        let rows = query.bind(limit).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(todo_from_row).collect())
+    }
+
+    async fn count_todos(&self, owner_id: i64, filter: &TodoFilter) -> Result<i64, sqlx::Error> {
+        let sql = format!("select count(*) as total from todos {}", TODO_FILTER_WHERE);
+        Ok(bind_todo_filter(sqlx::query(&sql), owner_id, filter).fetch_one(&self.pool).await?.get("total"))
+    }
+
+    async fn find_todo(&self, owner_id: i64, id: i64) -> Result<Option<Todo>, sqlx::Error> {
+        let query = format!("select {} from todos where id=$1 and owner_id=$2", TODO_COLUMNS);
+        let row = sqlx::query(&query).bind(id).bind(owner_id).fetch_optional(&self.pool).await?;
+        Ok(row.as_ref().map(todo_from_row))
+    }
+
+    async fn create_todo(&self, owner_id: i64, todo: &NewTodo) -> Result<Todo, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let id = insert_todo(&mut tx, owner_id, todo).await?;
+        tx.commit().await?;
+        self.find_todo(owner_id, id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn update_todo(&self, owner_id: i64, todo: &Todo) -> Result<Option<Todo>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if !save_todo(&mut tx, owner_id, todo).await? {
+            return Ok(None);
+        }
+        tx.commit().await?;
+        self.find_todo(owner_id, todo.id).await
+    }
+
+    async fn delete_todo(&self, owner_id: i64, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from todos where id=$1 and owner_id=$2").bind(id).bind(owner_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn todo_ancestors(&self, owner_id: i64, id: i64) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query(
+            "with recursive ancestors(id, parent_id) as (select id, parent_id from todos where id=$1 and owner_id=$2 \
+             union all select t.id, t.parent_id from todos t join ancestors a on t.id = a.parent_id) \
+             select id from ancestors")
+            .bind(id).bind(owner_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("id")).collect())
+    }
+
+    async fn complete_recurring_todo(&self, owner_id: i64, todo: &Todo, next: Option<&NewTodo>) -> Result<Option<(Todo, Option<Todo>)>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // 반복 규칙을 먼저 지우는 데 성공한 요청만 다음 occurrence 생성(동시 완료 요청은 쓰기 잠금으로 직렬화)
+        let cleared = sqlx::query("update todos set recurrence=null where id=$1 and owner_id=$2 and recurrence is not null")
+            .bind(todo.id).bind(owner_id).execute(&mut tx).await?.rows_affected()>0;
+        if !save_todo(&mut tx, owner_id, todo).await? {
+            return Ok(None);
+        }
+        let next_id = match next {
+            Some(next) if cleared => Some(insert_todo(&mut tx, owner_id, next).await?),
+            _ => None,
+        };
+        tx.commit().await?;
+        let Some(todo) = self.find_todo(owner_id, todo.id).await? else {
+            return Ok(None);
+        };
+        let next = match next_id {
+            Some(id) => Some(self.find_todo(owner_id, id).await?.ok_or(sqlx::Error::RowNotFound)?),
+            None => None,
+        };
+        Ok(Some((todo, next)))
+    }
+
+    async fn assign_ownerless_todos(&self, owner_id: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("update todos set owner_id=$1 where owner_id is null").bind(owner_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+const TODO_EVENT_COLUMNS: &str = "id, owner_id, todo_id, kind, title, to_char(due_date, 'YYYY-MM-DD') as due_date, \
+     to_char(remind_at, 'YYYY-MM-DD HH24:MI:SS') as remind_at, to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at";
+
+fn todo_event_from_row(r: &PgRow) -> TodoEvent {
+    TodoEvent {
+        id: r.get("id"),
+        user_id: r.get("owner_id"),
+        todo_id: r.get("todo_id"),
+        kind: r.get("kind"),
+        title: r.get("title"),
+        due_date: r.get("due_date"),
+        remind_at: r.get("remind_at"),
+        created_at: r.get("created_at"),
+    }
+}
+
+#[async_trait]
+impl TodoEventRepository for PgRepository {
+    async fn emit_due_reminders(&self) -> Result<u64, sqlx::Error> {
+        // 알림 표시와 이벤트 기록을 한 문장으로 처리(todo당 한 번만 기록)
+        let result = sqlx::query(
+            "with due as (update todos set reminded_at=now() \
+                 where reminder_offset_minutes is not null and reminded_at is null and not completed \
+                     and due_date + make_interval(mins => reminder_offset_minutes) <= now() \
+                 returning id, owner_id, title, due_date, due_date + make_interval(mins => reminder_offset_minutes) as remind_at) \
+             insert into todo_events(owner_id, todo_id, kind, title, due_date, remind_at) \
+             select owner_id, id, 'reminder', title, due_date, remind_at from due")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn undelivered_todo_events(&self, limit: i64) -> Result<Vec<TodoEvent>, sqlx::Error> {
+        let query = format!("select {} from todo_events where delivered_at is null order by id limit $1", TODO_EVENT_COLUMNS);
+        let rows = sqlx::query(&query).bind(limit).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(todo_event_from_row).collect())
+    }
+
+    async fn mark_todo_event_delivered(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("update todo_events set delivered_at=now() where id=$1 and delivered_at is null")
+            .bind(id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn list_todo_events(&self, owner_id: i64, after_id: i64, limit: i64) -> Result<Vec<TodoEvent>, sqlx::Error> {
+        let query = format!("select {} from todo_events where owner_id=$1 and id>$2 order by id limit $3", TODO_EVENT_COLUMNS);
+        let rows = sqlx::query(&query).bind(owner_id).bind(after_id).bind(limit).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(todo_event_from_row).collect())
+    }
+
+    async fn prune_todo_events(&self, keep_secs: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from todo_events where delivered_at <= now() - make_interval(secs => $1)")
+            .bind(keep_secs)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+// 유일성 제약 위반(사용자별 이름 중복)
+fn name_error(e: sqlx::Error) -> NameError {
+    match e {
+        sqlx::Error::Database(db) if db.code().as_deref()==Some(UNIQUE_VIOLATION) => NameError::Taken,
+        e => NameError::Database(e),
+    }
+}
+
+const PROJECT_COLUMNS: &str = "id, name, to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at, \
+     (select count(*) from todos where project_id = projects.id) as todo_count";
+
+fn project_from_row(r: &PgRow) -> Project {
+    Project {
+        id: r.get("id"),
+        name: r.get("name"),
+        created_at: r.get("created_at"),
+        todo_count: r.get("todo_count"),
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for PgRepository {
+    async fn list_projects(&self, owner_id: i64) -> Result<Vec<Project>, sqlx::Error> {
+        let query = format!("select {} from projects where owner_id=$1 order by name, id", PROJECT_COLUMNS);
+        let rows = sqlx::query(&query).bind(owner_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(project_from_row).collect())
+    }
+
+    async fn find_project(&self, owner_id: i64, id: i64) -> Result<Option<Project>, sqlx::Error> {
+        let query = format!("select {} from projects where id=$1 and owner_id=$2", PROJECT_COLUMNS);
+        let row = sqlx::query(&query).bind(id).bind(owner_id).fetch_optional(&self.pool).await?;
+        Ok(row.as_ref().map(project_from_row))
+    }
+
+    async fn create_project(&self, owner_id: i64, name: &str) -> Result<Project, NameError> {
+        let query = format!("insert into projects(owner_id, name) values ($1, $2) returning {}", PROJECT_COLUMNS);
+        let row = sqlx::query(&query).bind(owner_id).bind(name).fetch_one(&self.pool).await.map_err(name_error)?;
+        Ok(project_from_row(&row))
+    }
+
+    async fn rename_project(&self, owner_id: i64, id: i64, name: &str) -> Result<Option<Project>, NameError> {
+        let query = format!("update projects set name=$1 where id=$2 and owner_id=$3 returning {}", PROJECT_COLUMNS);
+        let row = sqlx::query(&query).bind(name).bind(id).bind(owner_id)
+            .fetch_optional(&self.pool).await.map_err(name_error)?;
+        Ok(row.as_ref().map(project_from_row))
+    }
+
+    async fn delete_project(&self, owner_id: i64, id: i64, todos: ProjectTodos) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        match todos {
+            ProjectTodos::Delete => {
+                sqlx::query("delete from todos where project_id=$1 and owner_id=$2").bind(id).bind(owner_id)
+                    .execute(&mut tx).await?;
+            }
+            ProjectTodos::Reassign(target) => {
+                sqlx::query("update todos set project_id=$1, updated_at=now() where project_id=$2 and owner_id=$3")
+                    .bind(target).bind(id).bind(owner_id)
+                    .execute(&mut tx).await?;
+            }
+        }
+        let result = sqlx::query("delete from projects where id=$1 and owner_id=$2").bind(id).bind(owner_id)
+            .execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected()>0)
+    }
+}
+
+const TAG_COLUMNS: &str = "id, name, color, (select count(*) from todo_tags where tag_id = tags.id) as todo_count";
+
+fn tag_from_row(r: &PgRow) -> Tag {
+    Tag {
+        id: r.get("id"),
+        name: r.get("name"),
+        color: r.get("color"),
+        todo_count: r.get("todo_count"),
+    }
+}
+
+#[async_trait]
+impl TagRepository for PgRepository {
+    async fn list_tags(&self, owner_id: i64) -> Result<Vec<Tag>, sqlx::Error> {
+        let query = format!("select {} from tags where owner_id=$1 order by name", TAG_COLUMNS);
+        let rows = sqlx::query(&query).bind(owner_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(tag_from_row).collect())
+    }
+
+    async fn find_tag(&self, owner_id: i64, id: i64) -> Result<Option<Tag>, sqlx::Error> {
+        let query = format!("select {} from tags where id=$1 and owner_id=$2", TAG_COLUMNS);
+        let row = sqlx::query(&query).bind(id).bind(owner_id).fetch_optional(&self.pool).await?;
+        Ok(row.as_ref().map(tag_from_row))
+    }
+
+    async fn create_tag(&self, owner_id: i64, name: &str, color: &str) -> Result<Tag, NameError> {
+        let query = format!("insert into tags(owner_id, name, color) values ($1, $2, $3) returning {}", TAG_COLUMNS);
+        let row = sqlx::query(&query).bind(owner_id).bind(name).bind(color)
+            .fetch_one(&self.pool).await.map_err(name_error)?;
+        Ok(tag_from_row(&row))
+    }
+
+    async fn update_tag(&self, owner_id: i64, tag: &Tag) -> Result<Option<Tag>, NameError> {
+        let query = format!("update tags set name=$1, color=$2 where id=$3 and owner_id=$4 returning {}", TAG_COLUMNS);
+        let row = sqlx::query(&query).bind(&tag.name).bind(&tag.color).bind(tag.id).bind(owner_id)
+            .fetch_optional(&self.pool).await.map_err(name_error)?;
+        Ok(row.as_ref().map(tag_from_row))
+    }
+
+    async fn delete_tag(&self, owner_id: i64, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from tags where id=$1 and owner_id=$2").bind(id).bind(owner_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+}
+
+#[async_trait]
+impl SessionRepository for PgRepository {
+    async fn create_session(&self, id: &str, user_id: i64, device_label: Option<&str>, client: &ClientInfo, grant: Option<&Grant>) -> Result<(), sqlx::Error> {
+        sqlx::query("insert into sessions(id, user_id, device_label, ip, user_agent, oauth_client_id, scope) values ($1, $2, $3, $4, $5, $6, $7)")
+            .bind(id).bind(user_id).bind(device_label).bind(&client.ip).bind(&client.user_agent)
+            .bind(grant.map(|g| g.client_id)).bind(grant.map(|g| g.scope.as_str()))
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn touch_session(&self, id: &str, interval_secs: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select last_seen_at <= now() - make_interval(secs => $2) as stale from sessions where id=$1 and revoked_at is null")
+            .bind(id).bind(interval_secs as f64).fetch_optional(&self.pool).await?;
+        let Some(row) = row else { return Ok(false) };
+        if row.get::<bool, _>("stale") {
+            sqlx::query("update sessions set last_seen_at=now() where id=$1").bind(id).execute(&self.pool).await?;
+        }
+        Ok(true)
+    }
+
+    async fn list_sessions(&self, username: &str) -> Result<Vec<SessionInfo>, sqlx::Error> {
+        let rows = sqlx::query(
+            "select s.id, s.device_label, s.ip, s.user_agent, \
+             to_char(s.created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at, to_char(s.last_seen_at, 'YYYY-MM-DD HH24:MI:SS') as last_seen_at \
+             from sessions s join users u on u.id = s.user_id where u.username=$1 and s.revoked_at is null \
+             order by s.last_seen_at desc, s.created_at desc")
+            .bind(username).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| SessionInfo {
+            id: r.get("id"),
+            device_label: r.get("device_label"),
+            ip: r.get("ip"),
+            user_agent: r.get("user_agent"),
+            created_at: r.get("created_at"),
+            last_seen_at: r.get("last_seen_at"),
+        }).collect())
+    }
+
+    async fn revoke_session(&self, username: &str, id: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "update sessions set revoked_at=now() where id=$1 and revoked_at is null \
+             and user_id=(select id from users where username=$2)")
+            .bind(id).bind(username).execute(&mut tx).await?;
+        if result.rows_affected()==0 {
+            return Ok(false);
+        }
+        sqlx::query("update refresh_tokens set revoked_at=now() where family_id=$1 and revoked_at is null")
+            .bind(id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn revoke_other_sessions(&self, username: &str, current_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "update sessions set revoked_at=now() where id<>$1 and revoked_at is null \
+             and user_id=(select id from users where username=$2)")
+            .bind(current_id).bind(username).execute(&mut tx).await?;
+        sqlx::query(
+            "update refresh_tokens set revoked_at=now() where family_id<>$1 and revoked_at is null \
+             and user_id=(select id from users where username=$2)")
+            .bind(current_id).bind(username).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn revoke_all_sessions(&self, user_id: i64) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("update sessions set revoked_at=now() where user_id=$1 and revoked_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        sqlx::query("update refresh_tokens set revoked_at=now() where user_id=$1 and revoked_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for PgRepository {
+    async fn insert_refresh_token(&self, user_id: i64, family_id: &str, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        sqlx::query("insert into refresh_tokens(user_id, family_id, token_hash, expires_at) values ($1, $2, $3, now() + make_interval(secs => $4))")
+            .bind(user_id).bind(family_id).bind(token_hash).bind(ttl_secs as i64)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshTokenState>, sqlx::Error> {
+        let row = sqlx::query(
+            "select r.id, r.user_id, r.family_id, r.used_at is not null as used, r.revoked_at is not null as revoked, \
+             r.expires_at <= now() as expired, u.username, s.oauth_client_id, s.scope \
+             from refresh_tokens r join users u on u.id = r.user_id join sessions s on s.id = r.family_id where r.token_hash=$1")
+            .bind(token_hash).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| RefreshTokenState {
+            id: r.get("id"),
+            user_id: r.get("user_id"),
+            username: r.get("username"),
+            family_id: r.get("family_id"),
+            used: r.get("used"),
+            revoked: r.get("revoked"),
+            expired: r.get("expired"),
+            grant: r.get::<Option<i64>, _>("oauth_client_id").map(|client_id| Grant { client_id, scope: r.get::<Option<String>, _>("scope").unwrap_or_default() }),
+        }))
+    }
+
+    async fn rotate_refresh_token(&self, current: &RefreshTokenState, new_token_hash: &str, ttl_secs: u64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // used_at이 비어 있을 때만 갱신하므로 동시 요청에서도 한 번만 성공
+        let marked = sqlx::query("update refresh_tokens set used_at=now() where id=$1 and used_at is null")
+            .bind(current.id).execute(&mut tx).await?;
+        if marked.rows_affected()==0 {
+            return Ok(false);
+        }
+        sqlx::query("insert into refresh_tokens(user_id, family_id, token_hash, expires_at) values ($1, $2, $3, now() + make_interval(secs => $4))")
+            .bind(current.user_id).bind(&current.family_id).bind(new_token_hash).bind(ttl_secs as i64)
+            .execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn revoke_token_family(&self, family_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("update refresh_tokens set revoked_at=now() where family_id=$1 and revoked_at is null")
+            .bind(family_id).execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RevocationRepository for PgRepository {
+    async fn revoke_token(&self, jti: &str, expires_at: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("insert into revoked_tokens(jti, expires_at) values ($1, to_timestamp($2)) on conflict do nothing")
+            .bind(jti).bind(expires_at)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select exists(select 1 from revoked_tokens where jti=$1) as revoked")
+            .bind(jti).fetch_one(&self.pool).await?;
+        Ok(row.get("revoked"))
+    }
+
+    async fn count_revoked_tokens(&self) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query("select count(*) as count from revoked_tokens where expires_at > now()")
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("count"))
+    }
+
+    async fn prune_revoked_tokens(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from revoked_tokens where expires_at <= now()")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl LockoutRepository for PgRepository {
+    async fn lock_remaining_secs(&self, key: &str) -> Result<Option<i64>, sqlx::Error> {
+        let row = sqlx::query(
+            "select ceil(extract(epoch from locked_until - now()))::bigint as remaining \
+             from login_attempts where key=$1 and locked_until > now()")
+            .bind(key).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("remaining")))
+    }
+
+    async fn increment_login_failures(&self, key: &str, window_secs: i64) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query(
+            "insert into login_attempts(key, failures, last_failure_at) values ($1, 1, now()) \
+             on conflict(key) do update set \
+                 failures = case when login_attempts.last_failure_at <= now() - make_interval(secs => $2) then 1 \
+                                 else login_attempts.failures + 1 end, \
+                 last_failure_at = now() \
+             returning failures")
+            .bind(key).bind(window_secs)
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("failures"))
+    }
+
+    async fn lock_login(&self, key: &str, secs: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("update login_attempts set locked_until=now() + make_interval(secs => $1) where key=$2")
+            .bind(secs).bind(key)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn clear_login_failures(&self, key: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from login_attempts where key=$1").bind(key)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn prune_login_attempts(&self, window_secs: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "delete from login_attempts where (locked_until is null or locked_until <= now()) \
+             and last_failure_at <= now() - make_interval(secs => $1)")
+            .bind(window_secs)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for PgRepository {
+    async fn hit_rate_limit(&self, key: &str, window_secs: i64) -> Result<(i64, i64), sqlx::Error> {
+        let row = sqlx::query(
+            "insert into rate_limits(key, hits, resets_at) values ($1, 1, now() + make_interval(secs => $2)) \
+             on conflict(key) do update set \
+                 hits = case when rate_limits.resets_at <= now() then 1 else rate_limits.hits + 1 end, \
+                 resets_at = case when rate_limits.resets_at <= now() then excluded.resets_at else rate_limits.resets_at end \
+             returning hits, ceil(extract(epoch from resets_at - now()))::bigint as remaining")
+            .bind(key).bind(window_secs)
+            .fetch_one(&self.pool).await?;
+        Ok((row.get("hits"), row.get("remaining")))
+    }
+
+    async fn prune_rate_limits(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from rate_limits where resets_at <= now()")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for PgRepository {
+    async fn create_password_reset(&self, user_id: i64, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update password_reset_tokens set used_at=now() where user_id=$1 and used_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        sqlx::query("insert into password_reset_tokens(user_id, token_hash, expires_at) values ($1, $2, now() + make_interval(secs => $3))")
+            .bind(user_id).bind(token_hash).bind(ttl_secs as i64)
+            .execute(&mut tx).await?;
+        tx.commit().await
+    }
+
+    async fn find_password_reset(&self, token_hash: &str) -> Result<Option<(i64, String)>, sqlx::Error> {
+        let row = sqlx::query(
+            "select p.user_id, u.username from password_reset_tokens p join users u on u.id = p.user_id \
+             where p.token_hash=$1 and p.used_at is null and p.expires_at > now()")
+            .bind(token_hash).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| (r.get("user_id"), r.get("username"))))
+    }
+
+    async fn consume_password_reset(&self, token_hash: &str, password_hash: &str) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            "update password_reset_tokens set used_at=now() \
+             where token_hash=$1 and used_at is null and expires_at > now() \
+             returning user_id")
+            .bind(token_hash).fetch_optional(&mut tx).await?;
+        let Some(user_id) = row.map(|r| r.get::<i64, _>("user_id")) else { return Ok(None) };
+        sqlx::query("update users set password_hash=$1 where id=$2").bind(password_hash).bind(user_id)
+            .execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    async fn prune_password_resets(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from password_reset_tokens where used_at is not null or expires_at <= now()")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl EmailVerificationRepository for PgRepository {
+    async fn create_email_verification(&self, user_id: i64, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update email_verification_tokens set used_at=now() where user_id=$1 and used_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        sqlx::query("insert into email_verification_tokens(user_id, token_hash, expires_at) values ($1, $2, now() + make_interval(secs => $3))")
+            .bind(user_id).bind(token_hash).bind(ttl_secs as i64)
+            .execute(&mut tx).await?;
+        tx.commit().await
+    }
+
+    async fn consume_email_verification(&self, token_hash: &str) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            "update email_verification_tokens set used_at=now() \
+             where token_hash=$1 and used_at is null and expires_at > now() \
+             returning user_id")
+            .bind(token_hash).fetch_optional(&mut tx).await?;
+        let user_id: i64 = match row {
+            Some(row) => row.get("user_id"),
+            None => return Ok(None),
+        };
+        sqlx::query("update users set email_verified_at=now() where id=$1 and email_verified_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    async fn prune_email_verifications(&self, keep_used_secs: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "delete from email_verification_tokens where expires_at <= now() \
+             or (used_at is not null and created_at <= now() - make_interval(secs => $1))")
+            .bind(keep_used_secs)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl MfaRepository for PgRepository {
+    async fn totp_enabled(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select 1 from user_totp where user_id=$1 and enabled_at is not null")
+            .bind(user_id).fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+
+    async fn begin_totp_enrollment(&self, user_id: i64, secret: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "insert into user_totp(user_id, secret) values ($1, $2) \
+             on conflict(user_id) do update set secret=excluded.secret, created_at=now(), last_used_step=null \
+             where user_totp.enabled_at is null")
+            .bind(user_id).bind(secret).execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn pending_totp_secret(&self, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("select secret from user_totp where user_id=$1 and enabled_at is null")
+            .bind(user_id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("secret")))
+    }
+
+    async fn active_totp_secret(&self, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("select secret from user_totp where user_id=$1 and enabled_at is not null")
+            .bind(user_id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("secret")))
+    }
+
+    async fn enable_totp(&self, user_id: i64, step: i64, recovery_code_hashes: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update user_totp set enabled_at=now(), last_used_step=$1 where user_id=$2")
+            .bind(step).bind(user_id).execute(&mut tx).await?;
+        sqlx::query("delete from mfa_recovery_codes where user_id=$1")
+            .bind(user_id).execute(&mut tx).await?;
+        for code_hash in recovery_code_hashes {
+            sqlx::query("insert into mfa_recovery_codes(user_id, code_hash) values ($1, $2)")
+                .bind(user_id).bind(code_hash).execute(&mut tx).await?;
+        }
+        tx.commit().await
+    }
+
+    async fn advance_totp_step(&self, user_id: i64, step: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "update user_totp set last_used_step=$1 where user_id=$2 and (last_used_step is null or last_used_step < $1)")
+            .bind(step).bind(user_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn use_recovery_code(&self, user_id: i64, code_hash: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "update mfa_recovery_codes set used_at=now() where user_id=$1 and code_hash=$2 and used_at is null")
+            .bind(user_id).bind(code_hash)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn remaining_recovery_codes(&self, user_id: i64) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query("select count(*) as remaining from mfa_recovery_codes where user_id=$1 and used_at is null")
+            .bind(user_id).fetch_one(&self.pool).await?;
+        Ok(row.get("remaining"))
+    }
+
+    async fn disable_totp(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("delete from user_totp where user_id=$1").bind(user_id).execute(&mut tx).await?;
+        sqlx::query("delete from mfa_recovery_codes where user_id=$1").bind(user_id).execute(&mut tx).await?;
+        tx.commit().await
+    }
+}
+
+#[async_trait]
+impl RoleRepository for PgRepository {
+    async fn grants_for(&self, user_id: i64) -> Result<Grants, sqlx::Error> {
+        let roles = sqlx::query(
+            "select r.name from user_roles ur join roles r on r.id = ur.role_id where ur.user_id=$1 order by r.name")
+            .bind(user_id).fetch_all(&self.pool).await?
+            .iter().map(|r| r.get("name")).collect();
+        let permissions = sqlx::query(
+            "select distinct p.name from user_roles ur \
+             join role_permissions rp on rp.role_id = ur.role_id \
+             join permissions p on p.id = rp.permission_id \
+             where ur.user_id=$1 order by p.name")
+            .bind(user_id).fetch_all(&self.pool).await?
+            .iter().map(|r| r.get("name")).collect();
+        Ok(Grants { roles, permissions })
+    }
+
+    async fn assign_role(&self, username: &str, role: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "insert into user_roles(user_id, role_id) \
+             select u.id, r.id from users u, roles r where u.username=$1 and r.name=$2 \
+             on conflict do nothing")
+            .bind(username).bind(role).execute(&self.pool).await?;
+        if result.rows_affected()>0 {
+            return Ok(true);
+        }
+        // 이미 부여된 역할인 경우에도 true
+        let row = sqlx::query(
+            "select 1 from user_roles ur join users u on u.id = ur.user_id join roles r on r.id = ur.role_id \
+             where u.username=$1 and r.name=$2")
+            .bind(username).bind(role).fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+
+    async fn remove_role(&self, username: &str, role: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "delete from user_roles where user_id=(select id from users where username=$1) \
+             and role_id=(select id from roles where name=$2)")
+            .bind(username).bind(role).execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn role_exists(&self, role: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select 1 from roles where name=$1").bind(role).fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+}
+
+impl PgRepository {
+    async fn redirect_uris_of(&self, id: i64) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query("select redirect_uri from oauth_redirect_uris where client_id=$1 order by redirect_uri")
+            .bind(id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("redirect_uri")).collect())
+    }
+
+    async fn client_from_row(&self, row: &PgRow) -> Result<Client, sqlx::Error> {
+        let id: i64 = row.get("id");
+        let redirect_uris = self.redirect_uris_of(id).await?;
+        Ok(Client::new(id, row.get("client_id"), row.get("name"), row.get("client_secret_hash"), redirect_uris))
+    }
+}
+
+#[async_trait]
+impl OAuthRepository for PgRepository {
+    async fn insert_client(&self, client_id: &str, secret_hash: Option<&str>, name: &str, redirect_uris: &[String]) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let id: i64 = sqlx::query("insert into oauth_clients(client_id, client_secret_hash, name) values ($1, $2, $3) returning id")
+            .bind(client_id).bind(secret_hash).bind(name)
+            .fetch_one(&mut tx).await?.get("id");
+        for uri in redirect_uris {
+            sqlx::query("insert into oauth_redirect_uris(client_id, redirect_uri) values ($1, $2) on conflict do nothing")
+                .bind(id).bind(uri).execute(&mut tx).await?;
+        }
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    async fn find_client(&self, client_id: &str) -> Result<Option<Client>, sqlx::Error> {
+        let row = sqlx::query("select id, client_id, client_secret_hash, name from oauth_clients where client_id=$1")
+            .bind(client_id).fetch_optional(&self.pool).await?;
+        match row {
+            Some(row) => Ok(Some(self.client_from_row(&row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_clients(&self) -> Result<Vec<Client>, sqlx::Error> {
+        let rows = sqlx::query("select id, client_id, client_secret_hash, name from oauth_clients order by id")
+            .fetch_all(&self.pool).await?;
+        let mut clients = Vec::with_capacity(rows.len());
+        for row in &rows {
+            clients.push(self.client_from_row(row).await?);
+        }
+        Ok(clients)
+    }
+
+    async fn delete_client(&self, client_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from oauth_clients where client_id=$1").bind(client_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn insert_authorization_code(&self, code_hash: &str, request: &AuthorizationRequest<'_>, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into oauth_authorization_codes(code_hash, client_id, user_id, redirect_uri, scope, code_challenge, nonce, expires_at) \
+             values ($1, $2, $3, $4, $5, $6, $7, now() + make_interval(secs => $8))")
+            .bind(code_hash).bind(request.client.id).bind(request.user_id)
+            .bind(request.redirect_uri).bind(request.scope).bind(request.code_challenge).bind(request.nonce)
+            .bind(ttl_secs as i64)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn consume_authorization_code(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, sqlx::Error> {
+        // 사용 처리와 사용자 이름 조회를 한 문장으로(UPDATE ... FROM)
+        let row = sqlx::query(
+            "update oauth_authorization_codes c set used_at=now() from users u \
+             where c.code_hash=$1 and c.used_at is null and c.expires_at > now() and u.id = c.user_id \
+             returning c.client_id, c.user_id, u.username, c.redirect_uri, c.scope, c.code_challenge, c.nonce")
+            .bind(code_hash).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| AuthorizationCode {
+            client_id: r.get("client_id"),
+            user_id: r.get("user_id"),
+            username: r.get("username"),
+            redirect_uri: r.get("redirect_uri"),
+            scope: r.get("scope"),
+            code_challenge: r.get("code_challenge"),
+            nonce: r.get("nonce"),
+        }))
+    }
+
+    async fn prune_authorization_codes(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from oauth_authorization_codes where used_at is not null or expires_at <= now()")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+// 조건이 없는 항목은 null로 바인딩하여 무시(시각 조건은 UTC 문자열을 timestamptz로 변환하여 비교)
+const AUDIT_FILTER_WHERE: &str = "where ($1::text is null or event=$1) and ($2::text is null or outcome=$2) \
+     and ($3::text is null or actor=$3) and ($4::bigint is null or actor_id=$4) and ($5::text is null or ip=$5) \
+     and ($6::text is null or created_at>=$6::timestamptz) and ($7::text is null or created_at<$7::timestamptz)";
+
+const AUDIT_COLUMNS: &str = "select id, to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at, event, outcome, \
+     actor_id, actor, ip, user_agent, detail from audit_events";
+
+fn bind_audit_filter<'q>(
+    query: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    filter: &'q Filter,
+) -> sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments> {
+    query.bind(&filter.event).bind(&filter.outcome).bind(&filter.actor).bind(filter.actor_id)
+        .bind(&filter.ip).bind(&filter.since).bind(&filter.until)
+}
+
+fn audit_event_from_row(row: &PgRow) -> AuditEvent {
+    AuditEvent {
+        id: row.get("id"),
+        created_at: row.get("created_at"),
+        event: row.get("event"),
+        outcome: row.get("outcome"),
+        actor_id: row.get("actor_id"),
+        actor: row.get("actor"),
+        ip: row.get("ip"),
+        user_agent: row.get("user_agent"),
+        detail: row.get("detail"),
+    }
+}
+
+#[async_trait]
+impl AuditRepository for PgRepository {
+    async fn insert_audit_event(&self, client: &ClientInfo, entry: &Entry<'_>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into audit_events(event, outcome, actor_id, actor, ip, user_agent, detail) values ($1, $2, $3, $4, $5, $6, $7)")
+            .bind(entry.event).bind(entry.outcome).bind(entry.actor_id).bind(entry.actor)
+            .bind(&client.ip).bind(&client.user_agent).bind(entry.detail)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn list_audit_events(&self, filter: &Filter, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        let sql = format!("{} {} order by id desc limit $8 offset $9", AUDIT_COLUMNS, AUDIT_FILTER_WHERE);
+        let rows = bind_audit_filter(sqlx::query(&sql), filter).bind(limit).bind(offset)
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(audit_event_from_row).collect())
+    }
+
+    async fn count_audit_events(&self, filter: &Filter) -> Result<i64, sqlx::Error> {
+        let sql = format!("select count(*) as total from audit_events {}", AUDIT_FILTER_WHERE);
+        Ok(bind_audit_filter(sqlx::query(&sql), filter).fetch_one(&self.pool).await?.get("total"))
+    }
+
+    async fn export_audit_events(&self, filter: &Filter) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        let sql = format!("{} {} order by id", AUDIT_COLUMNS, AUDIT_FILTER_WHERE);
+        let rows = bind_audit_filter(sqlx::query(&sql), filter).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(audit_event_from_row).collect())
+    }
+}
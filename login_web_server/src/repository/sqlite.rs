@@ -0,0 +1,1169 @@
+use async_trait::async_trait;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteRow};
+use sqlx::{Row, SqlitePool, Transaction};
+use crate::todo::SortField;
+use crate::audit::{AuditEvent, Entry, Filter};
+use crate::oauth::{AuthorizationCode, AuthorizationRequest, Client, Grant};
+use crate::rbac::Grants;
+use crate::session::ClientInfo;
+use super::*;
+
+// SQLite 마이그레이션(컴파일 타임에 ./migrations/sqlite 폴더를 읽음)
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+// SQLite 저장소
+// 시각은 current_timestamp 형식("YYYY-MM-DD HH:MM:SS", UTC) 문자열로 저장하고 datetime('now', ...)과 비교
+#[derive(Clone)]
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteRepository { pool }
+    }
+
+    // database.url 경로의 sqlite 파일을 찾거나(mode=rwc면 새로 만들고) 연결 풀 생성
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        Ok(SqliteRepository::new(SqlitePool::connect(url).await?))
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+// datetime('now', ?)에 바인딩할 상대 시각("+30 seconds", "-900 seconds")
+fn after_secs(secs: u64) -> String {
+    format!("+{} seconds", secs)
+}
+
+fn before_secs(secs: i64) -> String {
+    format!("-{} seconds", secs)
+}
+
+#[async_trait]
+impl Database for SqliteRepository {
+    fn backend(&self) -> Backend {
+        Backend::Sqlite
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &MIGRATOR
+    }
+
+    fn connection_counts(&self) -> (u32, usize) {
+        (self.pool.size(), self.pool.num_idle())
+    }
+
+    async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("select 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query("select version from _sqlx_migrations where success=1").fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("version")).collect())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqliteRepository {
+    async fn create_user(&self, username: &str, password_hash: &str, email: &str, role: &str) -> Result<i64, NewUserError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("insert into users(username, password_hash, email) values (?, ?, ?)")
+            .bind(username).bind(password_hash).bind(email)
+            .execute(&mut tx).await;
+        let user_id = match result {
+            Ok(result) => result.last_insert_rowid(),
+            // 유일성 제약 위반 메시지: "UNIQUE constraint failed: users.email"
+            Err(sqlx::Error::Database(e)) if e.message().contains("UNIQUE") => {
+                return if e.message().contains("users.email") {
+                    Err(NewUserError::EmailTaken)
+                } else {
+                    Err(NewUserError::UsernameTaken)
+                };
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let assigned = sqlx::query("insert into user_roles(user_id, role_id) select ?, id from roles where name=?")
+            .bind(user_id).bind(role).execute(&mut tx).await?;
+        if assigned.rows_affected()==0 {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+        tx.commit().await?;
+        Ok(user_id)
+    }
+
+    async fn find_credentials(&self, username: &str) -> Result<Option<Credentials>, sqlx::Error> {
+        let row = sqlx::query("select id, password_hash from users where username=?").bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| Credentials { id: r.get("id"), password_hash: r.get("password_hash") }))
+    }
+
+    async fn find_user_id(&self, username: &str) -> Result<Option<i64>, sqlx::Error> {
+        let row = sqlx::query("select id from users where username=?").bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("id")))
+    }
+
+    async fn user_matches(&self, user_id: i64, username: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select 1 from users where id=? and username=?").bind(user_id).bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+
+    async fn is_active(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select disabled_at is null as active from users where id=?").bind(user_id)
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("active"))
+    }
+
+    async fn is_email_verified(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select email_verified_at is not null as verified from users where id=?").bind(user_id)
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("verified"))
+    }
+
+    async fn mail_recipient(&self, username: &str) -> Result<Option<(i64, String)>, sqlx::Error> {
+        let row = sqlx::query("select id, coalesce(email, username) as recipient from users where username=?").bind(username)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| (r.get("id"), r.get("recipient"))))
+    }
+
+    async fn find_unverified_user_id(&self, email: &str) -> Result<Option<i64>, sqlx::Error> {
+        let row = sqlx::query("select id from users where email=? and email_verified_at is null").bind(email)
+            .fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("id")))
+    }
+
+    async fn change_password_hash(&self, user_id: i64, password_hash: &str, current_session_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update users set password_hash=? where id=?").bind(password_hash).bind(user_id)
+            .execute(&mut tx).await?;
+        let result = sqlx::query("update sessions set revoked_at=current_timestamp where user_id=? and id<>? and revoked_at is null")
+            .bind(user_id).bind(current_session_id).execute(&mut tx).await?;
+        sqlx::query("update refresh_tokens set revoked_at=current_timestamp where user_id=? and family_id<>? and revoked_at is null")
+            .bind(user_id).bind(current_session_id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn replace_password_hash(&self, user_id: i64, old_hash: &str, new_hash: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("update users set password_hash=? where id=? and password_hash=?")
+            .bind(new_hash).bind(user_id).bind(old_hash)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn set_disabled(&self, user_id: i64, disabled: bool) -> Result<(), sqlx::Error> {
+        let query = if disabled {
+            "update users set disabled_at=current_timestamp where id=? and disabled_at is null"
+        } else {
+            "update users set disabled_at=null where id=?"
+        };
+        sqlx::query(query).bind(user_id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn delete_user(&self, username: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from users where username=?").bind(username)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn list_users(&self, limit: i64, offset: i64) -> Result<Vec<UserSummary>, sqlx::Error> {
+        let rows = sqlx::query(
+            "select u.id, u.username, cast(u.created_at as text) as created_at, u.disabled_at, \
+                 (select group_concat(r.name, ',') from user_roles ur join roles r on r.id = ur.role_id where ur.user_id = u.id) as roles, \
+                 (select a.locked_until from login_attempts a where a.key = 'user:' || u.username and a.locked_until > datetime('now')) as locked_until \
+             from users u order by u.id limit ? offset ?")
+            .bind(limit).bind(offset)
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| UserSummary {
+            id: r.get("id"),
+            username: r.get("username"),
+            created_at: r.get("created_at"),
+            disabled_at: r.get("disabled_at"),
+            roles: r.get::<Option<String>, _>("roles")
+                .map(|roles| roles.split(',').map(String::from).collect())
+                .unwrap_or_default(),
+            locked_until: r.get("locked_until"),
+        }).collect())
+    }
+
+    async fn count_users(&self) -> Result<i64, sqlx::Error> {
+        Ok(sqlx::query("select count(*) as total from users").fetch_one(&self.pool).await?.get("total"))
+    }
+}
+
+// 모든 조회 쿼리에서 공통으로 사용하는 컬럼 목록(태그 이름은 JSON 배열)
+const TODO_COLUMNS: &str = "id, title, notes, completed, due_date, project_id, parent_id, created_at, updated_at, \
+     recurrence, reminder_offset_minutes, datetime(due_date, reminder_offset_minutes || ' minutes') as remind_at, \
+     (select json_group_array(t.name) from todo_tags tt join tags t on t.id = tt.tag_id where tt.todo_id = todos.id) as tags";
+
+// 목록 조회 조건(조건이 없는 항목은 null로 바인딩하여 무시, 검색 조건은 search_clause로 별도 추가)
+const TODO_FILTER_WHERE: &str = "where owner_id=? and (? is null or completed=?) \
+     and (? is null or due_date>=?) and (? is null or due_date<=?) \
+     and (? is null or exists (select 1 from todo_tags tt join tags t on t.id = tt.tag_id where tt.todo_id = todos.id and t.name=?)) \
+     and (? is null or coalesce(project_id, 0)=?) and (? is null or coalesce(parent_id, 0)=?)";
+
+fn bind_todo_filter<'q>(query: Query<'q, Sqlite, SqliteArguments<'q>>, owner_id: i64, filter: &'q TodoFilter) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    let query = query.bind(owner_id)
+        .bind(filter.completed).bind(filter.completed)
+        .bind(&filter.due_from).bind(&filter.due_from)
+        .bind(&filter.due_to).bind(&filter.due_to)
+        .bind(&filter.tag).bind(&filter.tag)
+        .bind(filter.project_id).bind(filter.project_id)
+        .bind(filter.parent_id).bind(filter.parent_id);
+    match &filter.search {
+        Some(search) => query.bind(fts5_query(search)),
+        None => query,
+    }
+}
+
+// 전문 검색 조건(FTS5 색인의 rowid는 todo id)
+fn search_clause(filter: &TodoFilter) -> &'static str {
+    if filter.search.is_some() { " and id in (select rowid from todos_fts where todos_fts match ?)" } else { "" }
+}
+
+// FTS5 검색식 생성: 단어마다 큰따옴표로 감싸 연산자(AND, OR, NEAR, *, : 등)로 해석되지 않도록 함
+// 공백으로 구분한 단어가 모두 포함된 행과 일치
+fn fts5_query(search: &str) -> String {
+    search.split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 정렬 식(todo::Sort::key와 같은 값, 마감일이 없으면 정렬 방향에 따라 마지막에 오는 값 사용)
+fn todo_sort_key(sort: Sort) -> String {
+    match sort.field {
+        SortField::CreatedAt => "created_at".to_string(),
+        SortField::UpdatedAt => "updated_at".to_string(),
+        SortField::DueDate => format!("coalesce(due_date, '{}')", sort.no_due_date()),
+        SortField::Title => "title".to_string(),
+    }
+}
+
+// DB 결과 행(SqliteRow)을 Todo 구조체로 매핑
+fn todo_from_row(r: &SqliteRow) -> Todo {
+    let tags: String = r.get("tags");
+    let mut tags: Vec<String> = serde_json::from_str(&tags).unwrap_or_default();
+    tags.sort();
+    Todo {
+        id: r.get("id"),
+        title: r.get("title"),
+        notes: r.get("notes"),
+        completed: r.get("completed"),
+        due_date: r.get("due_date"),
+        tags,
+        project_id: r.get("project_id"),
+        parent_id: r.get("parent_id"),
+        recurrence: r.get("recurrence"),
+        reminder_offset_minutes: r.get("reminder_offset_minutes"),
+        remind_at: r.get("remind_at"),
+        created_at: r.get("created_at"),
+        updated_at: r.get("updated_at"),
+    }
+}
+
+// todo의 태그를 주어진 이름 목록으로 교체(없는 태그는 새로 만듦)
+async fn set_todo_tags(tx: &mut Transaction<'_, Sqlite>, owner_id: i64, todo_id: i64, tags: &[String]) -> Result<(), sqlx::Error> {
+    sqlx::query("delete from todo_tags where todo_id=?").bind(todo_id).execute(&mut *tx).await?;
+    for tag in tags {
+        sqlx::query("insert or ignore into tags(owner_id, name) values (?, ?)").bind(owner_id).bind(tag)
+            .execute(&mut *tx).await?;
+        sqlx::query("insert or ignore into todo_tags(todo_id, tag_id) select ?, id from tags where owner_id=? and name=?")
+            .bind(todo_id).bind(owner_id).bind(tag)
+            .execute(&mut *tx).await?;
+    }
+    Ok(())
+}
+
+// todo 추가 후 id 반환(태그 포함)
+async fn insert_todo(tx: &mut Transaction<'_, Sqlite>, owner_id: i64, todo: &NewTodo) -> Result<i64, sqlx::Error> {
+    let id = sqlx::query(
+        "insert into todos(owner_id, title, notes, due_date, project_id, parent_id, recurrence, reminder_offset_minutes) \
+         values (?, ?, ?, ?, ?, ?, ?, ?)")
+        .bind(owner_id).bind(&todo.title).bind(&todo.notes).bind(&todo.due_date).bind(todo.project_id).bind(todo.parent_id)
+        .bind(&todo.recurrence).bind(todo.reminder_offset_minutes)
+        .execute(&mut *tx).await?.last_insert_rowid();
+    set_todo_tags(tx, owner_id, id, &todo.tags).await?;
+    Ok(id)
+}
+
+// todo 저장 및 하위 todo 프로젝트 이동, todo가 없으면 false
+async fn save_todo(tx: &mut Transaction<'_, Sqlite>, owner_id: i64, todo: &Todo) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "update todos set title=?, notes=?, completed=?, due_date=?, project_id=?, parent_id=?, \
+             recurrence=?, reminder_offset_minutes=?, updated_at=current_timestamp, \
+             reminded_at = case when due_date is ? and reminder_offset_minutes is ? then reminded_at end \
+         where id=? and owner_id=?")
+        .bind(&todo.title).bind(&todo.notes).bind(todo.completed).bind(&todo.due_date).bind(todo.project_id).bind(todo.parent_id)
+        .bind(&todo.recurrence).bind(todo.reminder_offset_minutes)
+        .bind(&todo.due_date).bind(todo.reminder_offset_minutes)
+        .bind(todo.id).bind(owner_id)
+        .execute(&mut *tx).await?;
+    if result.rows_affected()==0 {
+        return Ok(false);
+    }
+    set_todo_tags(tx, owner_id, todo.id, &todo.tags).await?;
+    // 하위 todo 전체를 같은 프로젝트로 이동
+    sqlx::query(
+        "with recursive subtree(id) as (select id from todos where parent_id=? \
+         union all select t.id from todos t join subtree s on t.parent_id = s.id) \
+         update todos set project_id=? where id in (select id from subtree)")
+        .bind(todo.id).bind(todo.project_id)
+        .execute(&mut *tx).await?;
+    Ok(true)
+}
+
+#[async_trait]
+impl TodoRepository for SqliteRepository {
+    async fn list_todos(&self, owner_id: i64, filter: &TodoFilter, sort: Sort, cursor: Option<&Cursor>, limit: i64) -> Result<Vec<Todo>, sqlx::Error> {
+        let key = todo_sort_key(sort);
+        // 이전 페이지는 기준 todo 앞쪽을 역순으로 조회
+        let descending = sort.descending != cursor.is_some_and(|c| c.backward);
+        let (order, op) = if descending { ("desc", "<") } else { ("asc", ">") };
+        let after = if cursor.is_some() { format!(" and ({}, id) {} (?, ?)", key, op) } else { String::new() };
+        let sql = format!("select {} from todos {}{}{} order by {} {}, id {} limit ?",
+            TODO_COLUMNS, TODO_FILTER_WHERE, search_clause(filter), after, key, order, order);
+        let mut query = bind_todo_filter(sqlx::query(&sql), owner_id, filter);
+        if let Some(cursor) = cursor {
+            query = query.bind(&cursor.key).bind(cursor.id);
+        }
+        let rows = query.bind(limit).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(todo_from_row).collect())
+    }
+
+    async fn count_todos(&self, owner_id: i64, filter: &TodoFilter) -> Result<i64, sqlx::Error> {
+        let sql = format!("select count(*) as total from todos {}{}", TODO_FILTER_WHERE, search_clause(filter));
+        Ok(bind_todo_filter(sqlx::query(&sql), owner_id, filter).fetch_one(&self.pool).await?.get("total"))
+    }
+
+    async fn find_todo(&self, owner_id: i64, id: i64) -> Result<Option<Todo>, sqlx::Error> {
+        let query = format!("select {} from todos where id=? and owner_id=?", TODO_COLUMNS);
+        let row = sqlx::query(&query).bind(id).bind(owner_id).fetch_optional(&self.pool).await?;
+        Ok(row.as_ref().map(todo_from_row))
+    }
+
+    async fn create_todo(&self, owner_id: i64, todo: &NewTodo) -> Result<Todo, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let id = insert_todo(&mut tx, owner_id, todo).await?;
+        tx.commit().await?;
+        self.find_todo(owner_id, id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn update_todo(&self, owner_id: i64, todo: &Todo) -> Result<Option<Todo>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        if !save_todo(&mut tx, owner_id, todo).await? {
+            return Ok(None);
+        }
+        tx.commit().await?;
+        self.find_todo(owner_id, todo.id).await
+    }
+
+    async fn delete_todo(&self, owner_id: i64, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from todos where id=? and owner_id=?").bind(id).bind(owner_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn todo_ancestors(&self, owner_id: i64, id: i64) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query(
+            "with recursive ancestors(id, parent_id) as (select id, parent_id from todos where id=? and owner_id=? \
+             union all select t.id, t.parent_id from todos t join ancestors a on t.id = a.parent_id) \
+             select id from ancestors")
+            .bind(id).bind(owner_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("id")).collect())
+    }
+
+    async fn complete_recurring_todo(&self, owner_id: i64, todo: &Todo, next: Option<&NewTodo>) -> Result<Option<(Todo, Option<Todo>)>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // 반복 규칙을 먼저 지우는 데 성공한 요청만 다음 occurrence 생성(동시 완료 요청은 쓰기 잠금으로 직렬화)
+        let cleared = sqlx::query("update todos set recurrence=null where id=? and owner_id=? and recurrence is not null")
+            .bind(todo.id).bind(owner_id).execute(&mut tx).await?.rows_affected()>0;
+        if !save_todo(&mut tx, owner_id, todo).await? {
+            return Ok(None);
+        }
+        let next_id = match next {
+            Some(next) if cleared => Some(insert_todo(&mut tx, owner_id, next).await?),
+            _ => None,
+        };
+        tx.commit().await?;
+        let Some(todo) = self.find_todo(owner_id, todo.id).await? else {
+            return Ok(None);
+        };
+        let next = match next_id {
+            Some(id) => Some(self.find_todo(owner_id, id).await?.ok_or(sqlx::Error::RowNotFound)?),
+            None => None,
+        };
+        Ok(Some((todo, next)))
+    }
+
+    async fn assign_ownerless_todos(&self, owner_id: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("update todos set owner_id=? where owner_id is null").bind(owner_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+const TODO_EVENT_COLUMNS: &str = "id, owner_id, todo_id, kind, title, due_date, remind_at, created_at";
+
+fn todo_event_from_row(r: &SqliteRow) -> TodoEvent {
+    TodoEvent {
+        id: r.get("id"),
+        user_id: r.get("owner_id"),
+        todo_id: r.get("todo_id"),
+        kind: r.get("kind"),
+        title: r.get("title"),
+        due_date: r.get("due_date"),
+        remind_at: r.get("remind_at"),
+        created_at: r.get("created_at"),
+    }
+}
+
+#[async_trait]
+impl TodoEventRepository for SqliteRepository {
+    async fn emit_due_reminders(&self) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // 알림 표시와 이벤트 기록을 한 트랜잭션으로 처리(todo당 한 번만 기록)
+        let due = sqlx::query(
+            "update todos set reminded_at=current_timestamp \
+             where owner_id is not null and reminder_offset_minutes is not null and reminded_at is null and completed=0 \
+                 and datetime(due_date, reminder_offset_minutes || ' minutes') <= datetime('now') \
+             returning id, owner_id, title, due_date, datetime(due_date, reminder_offset_minutes || ' minutes') as remind_at")
+            .fetch_all(&mut tx).await?;
+        for row in &due {
+            sqlx::query("insert into todo_events(owner_id, todo_id, kind, title, due_date, remind_at) values (?, ?, 'reminder', ?, ?, ?)")
+                .bind(row.get::<i64, _>("owner_id")).bind(row.get::<i64, _>("id")).bind(row.get::<String, _>("title"))
+                .bind(row.get::<String, _>("due_date")).bind(row.get::<String, _>("remind_at"))
+                .execute(&mut tx).await?;
+        }
+        tx.commit().await?;
+        Ok(due.len() as u64)
+    }
+
+    async fn undelivered_todo_events(&self, limit: i64) -> Result<Vec<TodoEvent>, sqlx::Error> {
+        let query = format!("select {} from todo_events where delivered_at is null order by id limit ?", TODO_EVENT_COLUMNS);
+        let rows = sqlx::query(&query).bind(limit).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(todo_event_from_row).collect())
+    }
+
+    async fn mark_todo_event_delivered(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("update todo_events set delivered_at=current_timestamp where id=? and delivered_at is null")
+            .bind(id).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn list_todo_events(&self, owner_id: i64, after_id: i64, limit: i64) -> Result<Vec<TodoEvent>, sqlx::Error> {
+        let query = format!("select {} from todo_events where owner_id=? and id>? order by id limit ?", TODO_EVENT_COLUMNS);
+        let rows = sqlx::query(&query).bind(owner_id).bind(after_id).bind(limit).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(todo_event_from_row).collect())
+    }
+
+    async fn prune_todo_events(&self, keep_secs: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from todo_events where delivered_at <= datetime('now', ?)")
+            .bind(before_secs(keep_secs))
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+// 유일성 제약 위반(사용자별 이름 중복)
+fn name_error(e: sqlx::Error) -> NameError {
+    match e {
+        sqlx::Error::Database(db) if db.message().contains("UNIQUE") => NameError::Taken,
+        e => NameError::Database(e),
+    }
+}
+
+const PROJECT_COLUMNS: &str = "id, name, created_at, (select count(*) from todos where project_id = projects.id) as todo_count";
+
+fn project_from_row(r: &SqliteRow) -> Project {
+    Project {
+        id: r.get("id"),
+        name: r.get("name"),
+        created_at: r.get("created_at"),
+        todo_count: r.get("todo_count"),
+    }
+}
+
+#[async_trait]
+impl ProjectRepository for SqliteRepository {
+    async fn list_projects(&self, owner_id: i64) -> Result<Vec<Project>, sqlx::Error> {
+        let query = format!("select {} from projects where owner_id=? order by name, id", PROJECT_COLUMNS);
+        let rows = sqlx::query(&query).bind(owner_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(project_from_row).collect())
+    }
+
+    async fn find_project(&self, owner_id: i64, id: i64) -> Result<Option<Project>, sqlx::Error> {
+        let query = format!("select {} from projects where id=? and owner_id=?", PROJECT_COLUMNS);
+        let row = sqlx::query(&query).bind(id).bind(owner_id).fetch_optional(&self.pool).await?;
+        Ok(row.as_ref().map(project_from_row))
+    }
+
+    async fn create_project(&self, owner_id: i64, name: &str) -> Result<Project, NameError> {
+        let query = format!("insert into projects(owner_id, name) values (?, ?) returning {}", PROJECT_COLUMNS);
+        let row = sqlx::query(&query).bind(owner_id).bind(name).fetch_one(&self.pool).await.map_err(name_error)?;
+        Ok(project_from_row(&row))
+    }
+
+    async fn rename_project(&self, owner_id: i64, id: i64, name: &str) -> Result<Option<Project>, NameError> {
+        let query = format!("update projects set name=? where id=? and owner_id=? returning {}", PROJECT_COLUMNS);
+        let row = sqlx::query(&query).bind(name).bind(id).bind(owner_id)
+            .fetch_optional(&self.pool).await.map_err(name_error)?;
+        Ok(row.as_ref().map(project_from_row))
+    }
+
+    async fn delete_project(&self, owner_id: i64, id: i64, todos: ProjectTodos) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        match todos {
+            ProjectTodos::Delete => {
+                sqlx::query("delete from todos where project_id=? and owner_id=?").bind(id).bind(owner_id)
+                    .execute(&mut tx).await?;
+            }
+            ProjectTodos::Reassign(target) => {
+                sqlx::query("update todos set project_id=?, updated_at=current_timestamp where project_id=? and owner_id=?")
+                    .bind(target).bind(id).bind(owner_id)
+                    .execute(&mut tx).await?;
+            }
+        }
+        let result = sqlx::query("delete from projects where id=? and owner_id=?").bind(id).bind(owner_id)
+            .execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected()>0)
+    }
+}
+
+const TAG_COLUMNS: &str = "id, name, color, (select count(*) from todo_tags where tag_id = tags.id) as todo_count";
+
+fn tag_from_row(r: &SqliteRow) -> Tag {
+    Tag {
+        id: r.get("id"),
+        name: r.get("name"),
+        color: r.get("color"),
+        todo_count: r.get("todo_count"),
+    }
+}
+
+#[async_trait]
+impl TagRepository for SqliteRepository {
+    async fn list_tags(&self, owner_id: i64) -> Result<Vec<Tag>, sqlx::Error> {
+        let query = format!("select {} from tags where owner_id=? order by name", TAG_COLUMNS);
+        let rows = sqlx::query(&query).bind(owner_id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(tag_from_row).collect())
+    }
+
+    async fn find_tag(&self, owner_id: i64, id: i64) -> Result<Option<Tag>, sqlx::Error> {
+        let query = format!("select {} from tags where id=? and owner_id=?", TAG_COLUMNS);
+        let row = sqlx::query(&query).bind(id).bind(owner_id).fetch_optional(&self.pool).await?;
+        Ok(row.as_ref().map(tag_from_row))
+    }
+
+    async fn create_tag(&self, owner_id: i64, name: &str, color: &str) -> Result<Tag, NameError> {
+        let query = format!("insert into tags(owner_id, name, color) values (?, ?, ?) returning {}", TAG_COLUMNS);
+        let row = sqlx::query(&query).bind(owner_id).bind(name).bind(color)
+            .fetch_one(&self.pool).await.map_err(name_error)?;
+        Ok(tag_from_row(&row))
+    }
+
+    async fn update_tag(&self, owner_id: i64, tag: &Tag) -> Result<Option<Tag>, NameError> {
+        let query = format!("update tags set name=?, color=? where id=? and owner_id=? returning {}", TAG_COLUMNS);
+        let row = sqlx::query(&query).bind(&tag.name).bind(&tag.color).bind(tag.id).bind(owner_id)
+            .fetch_optional(&self.pool).await.map_err(name_error)?;
+        Ok(row.as_ref().map(tag_from_row))
+    }
+
+    async fn delete_tag(&self, owner_id: i64, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from tags where id=? and owner_id=?").bind(id).bind(owner_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SqliteRepository {
+    async fn create_session(&self, id: &str, user_id: i64, device_label: Option<&str>, client: &ClientInfo, grant: Option<&Grant>) -> Result<(), sqlx::Error> {
+        sqlx::query("insert into sessions(id, user_id, device_label, ip, user_agent, oauth_client_id, scope) values (?, ?, ?, ?, ?, ?, ?)")
+            .bind(id).bind(user_id).bind(device_label).bind(&client.ip).bind(&client.user_agent)
+            .bind(grant.map(|g| g.client_id)).bind(grant.map(|g| g.scope.as_str()))
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn touch_session(&self, id: &str, interval_secs: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select last_seen_at <= datetime('now', ?) as stale from sessions where id=? and revoked_at is null")
+            .bind(before_secs(interval_secs)).bind(id).fetch_optional(&self.pool).await?;
+        let Some(row) = row else { return Ok(false) };
+        if row.get::<bool, _>("stale") {
+            sqlx::query("update sessions set last_seen_at=current_timestamp where id=?").bind(id).execute(&self.pool).await?;
+        }
+        Ok(true)
+    }
+
+    async fn list_sessions(&self, username: &str) -> Result<Vec<SessionInfo>, sqlx::Error> {
+        let rows = sqlx::query(
+            "select s.id, s.device_label, s.ip, s.user_agent, s.created_at, s.last_seen_at from sessions s \
+             join users u on u.id = s.user_id where u.username=? and s.revoked_at is null \
+             order by s.last_seen_at desc, s.created_at desc")
+            .bind(username).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| SessionInfo {
+            id: r.get("id"),
+            device_label: r.get("device_label"),
+            ip: r.get("ip"),
+            user_agent: r.get("user_agent"),
+            created_at: r.get("created_at"),
+            last_seen_at: r.get("last_seen_at"),
+        }).collect())
+    }
+
+    async fn revoke_session(&self, username: &str, id: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "update sessions set revoked_at=current_timestamp where id=? and revoked_at is null \
+             and user_id=(select id from users where username=?)")
+            .bind(id).bind(username).execute(&mut tx).await?;
+        if result.rows_affected()==0 {
+            return Ok(false);
+        }
+        sqlx::query("update refresh_tokens set revoked_at=current_timestamp where family_id=? and revoked_at is null")
+            .bind(id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn revoke_other_sessions(&self, username: &str, current_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "update sessions set revoked_at=current_timestamp where id<>? and revoked_at is null \
+             and user_id=(select id from users where username=?)")
+            .bind(current_id).bind(username).execute(&mut tx).await?;
+        sqlx::query(
+            "update refresh_tokens set revoked_at=current_timestamp where family_id<>? and revoked_at is null \
+             and user_id=(select id from users where username=?)")
+            .bind(current_id).bind(username).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn revoke_all_sessions(&self, user_id: i64) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query("update sessions set revoked_at=current_timestamp where user_id=? and revoked_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        sqlx::query("update refresh_tokens set revoked_at=current_timestamp where user_id=? and revoked_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for SqliteRepository {
+    async fn insert_refresh_token(&self, user_id: i64, family_id: &str, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        sqlx::query("insert into refresh_tokens(user_id, family_id, token_hash, expires_at) values (?, ?, ?, datetime('now', ?))")
+            .bind(user_id).bind(family_id).bind(token_hash).bind(after_secs(ttl_secs))
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn find_refresh_token(&self, token_hash: &str) -> Result<Option<RefreshTokenState>, sqlx::Error> {
+        let row = sqlx::query(
+            "select r.id, r.user_id, r.family_id, r.used_at is not null as used, r.revoked_at is not null as revoked, \
+             r.expires_at <= datetime('now') as expired, u.username, s.oauth_client_id, s.scope \
+             from refresh_tokens r join users u on u.id = r.user_id join sessions s on s.id = r.family_id where r.token_hash=?")
+            .bind(token_hash).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| RefreshTokenState {
+            id: r.get("id"),
+            user_id: r.get("user_id"),
+            username: r.get("username"),
+            family_id: r.get("family_id"),
+            used: r.get("used"),
+            revoked: r.get("revoked"),
+            expired: r.get("expired"),
+            grant: r.get::<Option<i64>, _>("oauth_client_id").map(|client_id| Grant { client_id, scope: r.get::<Option<String>, _>("scope").unwrap_or_default() }),
+        }))
+    }
+
+    async fn rotate_refresh_token(&self, current: &RefreshTokenState, new_token_hash: &str, ttl_secs: u64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        // used_at이 비어 있을 때만 갱신하므로 동시 요청에서도 한 번만 성공
+        let marked = sqlx::query("update refresh_tokens set used_at=current_timestamp where id=? and used_at is null")
+            .bind(current.id).execute(&mut tx).await?;
+        if marked.rows_affected()==0 {
+            return Ok(false);
+        }
+        sqlx::query("insert into refresh_tokens(user_id, family_id, token_hash, expires_at) values (?, ?, ?, datetime('now', ?))")
+            .bind(current.user_id).bind(&current.family_id).bind(new_token_hash).bind(after_secs(ttl_secs))
+            .execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn revoke_token_family(&self, family_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("update refresh_tokens set revoked_at=current_timestamp where family_id=? and revoked_at is null")
+            .bind(family_id).execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RevocationRepository for SqliteRepository {
+    async fn revoke_token(&self, jti: &str, expires_at: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("insert or ignore into revoked_tokens(jti, expires_at) values (?, datetime(?, 'unixepoch'))")
+            .bind(jti).bind(expires_at)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select exists(select 1 from revoked_tokens where jti=?) as revoked")
+            .bind(jti).fetch_one(&self.pool).await?;
+        Ok(row.get("revoked"))
+    }
+
+    async fn count_revoked_tokens(&self) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query("select count(*) as count from revoked_tokens where expires_at > datetime('now')")
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("count"))
+    }
+
+    async fn prune_revoked_tokens(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from revoked_tokens where expires_at <= datetime('now')")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl LockoutRepository for SqliteRepository {
+    async fn lock_remaining_secs(&self, key: &str) -> Result<Option<i64>, sqlx::Error> {
+        let row = sqlx::query(
+            "select cast(strftime('%s', locked_until) - strftime('%s', 'now') as integer) as remaining \
+             from login_attempts where key=? and locked_until > datetime('now')")
+            .bind(key).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("remaining")))
+    }
+
+    async fn increment_login_failures(&self, key: &str, window_secs: i64) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query(
+            "insert into login_attempts(key, failures, last_failure_at) values (?, 1, current_timestamp) \
+             on conflict(key) do update set \
+                 failures = case when last_failure_at <= datetime('now', ?) then 1 else failures + 1 end, \
+                 last_failure_at = current_timestamp \
+             returning failures")
+            .bind(key).bind(before_secs(window_secs))
+            .fetch_one(&self.pool).await?;
+        Ok(row.get("failures"))
+    }
+
+    async fn lock_login(&self, key: &str, secs: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("update login_attempts set locked_until=datetime('now', ?) where key=?")
+            .bind(after_secs(secs as u64)).bind(key)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn clear_login_failures(&self, key: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from login_attempts where key=?").bind(key)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn prune_login_attempts(&self, window_secs: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "delete from login_attempts where (locked_until is null or locked_until <= datetime('now')) \
+             and last_failure_at <= datetime('now', ?)")
+            .bind(before_secs(window_secs))
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for SqliteRepository {
+    async fn hit_rate_limit(&self, key: &str, window_secs: i64) -> Result<(i64, i64), sqlx::Error> {
+        let row = sqlx::query(
+            "insert into rate_limits(key, hits, resets_at) values (?, 1, datetime('now', ?)) \
+             on conflict(key) do update set \
+                 hits = case when resets_at <= datetime('now') then 1 else hits + 1 end, \
+                 resets_at = case when resets_at <= datetime('now') then excluded.resets_at else resets_at end \
+             returning hits, cast(strftime('%s', resets_at) - strftime('%s', 'now') as integer) as remaining")
+            .bind(key).bind(format!("+{} seconds", window_secs))
+            .fetch_one(&self.pool).await?;
+        Ok((row.get("hits"), row.get("remaining")))
+    }
+
+    async fn prune_rate_limits(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from rate_limits where resets_at <= datetime('now')")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for SqliteRepository {
+    async fn create_password_reset(&self, user_id: i64, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update password_reset_tokens set used_at=current_timestamp where user_id=? and used_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        sqlx::query("insert into password_reset_tokens(user_id, token_hash, expires_at) values (?, ?, datetime('now', ?))")
+            .bind(user_id).bind(token_hash).bind(after_secs(ttl_secs))
+            .execute(&mut tx).await?;
+        tx.commit().await
+    }
+
+    async fn find_password_reset(&self, token_hash: &str) -> Result<Option<(i64, String)>, sqlx::Error> {
+        let row = sqlx::query(
+            "select p.user_id, u.username from password_reset_tokens p join users u on u.id = p.user_id \
+             where p.token_hash=? and p.used_at is null and p.expires_at > datetime('now')")
+            .bind(token_hash).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| (r.get("user_id"), r.get("username"))))
+    }
+
+    async fn consume_password_reset(&self, token_hash: &str, password_hash: &str) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            "update password_reset_tokens set used_at=current_timestamp \
+             where token_hash=? and used_at is null and expires_at > datetime('now') \
+             returning user_id")
+            .bind(token_hash).fetch_optional(&mut tx).await?;
+        let Some(user_id) = row.map(|r| r.get::<i64, _>("user_id")) else { return Ok(None) };
+        sqlx::query("update users set password_hash=? where id=?").bind(password_hash).bind(user_id)
+            .execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    async fn prune_password_resets(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from password_reset_tokens where used_at is not null or expires_at <= datetime('now')")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl EmailVerificationRepository for SqliteRepository {
+    async fn create_email_verification(&self, user_id: i64, token_hash: &str, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update email_verification_tokens set used_at=current_timestamp where user_id=? and used_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        sqlx::query("insert into email_verification_tokens(user_id, token_hash, expires_at) values (?, ?, datetime('now', ?))")
+            .bind(user_id).bind(token_hash).bind(after_secs(ttl_secs))
+            .execute(&mut tx).await?;
+        tx.commit().await
+    }
+
+    async fn consume_email_verification(&self, token_hash: &str) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            "update email_verification_tokens set used_at=current_timestamp \
+             where token_hash=? and used_at is null and expires_at > datetime('now') \
+             returning user_id")
+            .bind(token_hash).fetch_optional(&mut tx).await?;
+        let user_id: i64 = match row {
+            Some(row) => row.get("user_id"),
+            None => return Ok(None),
+        };
+        sqlx::query("update users set email_verified_at=current_timestamp where id=? and email_verified_at is null")
+            .bind(user_id).execute(&mut tx).await?;
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    async fn prune_email_verifications(&self, keep_used_secs: i64) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "delete from email_verification_tokens where expires_at <= datetime('now') \
+             or (used_at is not null and created_at <= datetime('now', ?))")
+            .bind(before_secs(keep_used_secs))
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl MfaRepository for SqliteRepository {
+    async fn totp_enabled(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select 1 from user_totp where user_id=? and enabled_at is not null")
+            .bind(user_id).fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+
+    async fn begin_totp_enrollment(&self, user_id: i64, secret: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "insert into user_totp(user_id, secret) values (?, ?) \
+             on conflict(user_id) do update set secret=excluded.secret, created_at=current_timestamp, last_used_step=null \
+             where user_totp.enabled_at is null")
+            .bind(user_id).bind(secret).execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn pending_totp_secret(&self, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("select secret from user_totp where user_id=? and enabled_at is null")
+            .bind(user_id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("secret")))
+    }
+
+    async fn active_totp_secret(&self, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query("select secret from user_totp where user_id=? and enabled_at is not null")
+            .bind(user_id).fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| r.get("secret")))
+    }
+
+    async fn enable_totp(&self, user_id: i64, step: i64, recovery_code_hashes: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("update user_totp set enabled_at=current_timestamp, last_used_step=? where user_id=?")
+            .bind(step).bind(user_id).execute(&mut tx).await?;
+        sqlx::query("delete from mfa_recovery_codes where user_id=?")
+            .bind(user_id).execute(&mut tx).await?;
+        for code_hash in recovery_code_hashes {
+            sqlx::query("insert into mfa_recovery_codes(user_id, code_hash) values (?, ?)")
+                .bind(user_id).bind(code_hash).execute(&mut tx).await?;
+        }
+        tx.commit().await
+    }
+
+    async fn advance_totp_step(&self, user_id: i64, step: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "update user_totp set last_used_step=? where user_id=? and (last_used_step is null or last_used_step < ?)")
+            .bind(step).bind(user_id).bind(step)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn use_recovery_code(&self, user_id: i64, code_hash: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "update mfa_recovery_codes set used_at=current_timestamp where user_id=? and code_hash=? and used_at is null")
+            .bind(user_id).bind(code_hash)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn remaining_recovery_codes(&self, user_id: i64) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query("select count(*) as remaining from mfa_recovery_codes where user_id=? and used_at is null")
+            .bind(user_id).fetch_one(&self.pool).await?;
+        Ok(row.get("remaining"))
+    }
+
+    async fn disable_totp(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("delete from user_totp where user_id=?").bind(user_id).execute(&mut tx).await?;
+        sqlx::query("delete from mfa_recovery_codes where user_id=?").bind(user_id).execute(&mut tx).await?;
+        tx.commit().await
+    }
+}
+
+#[async_trait]
+impl RoleRepository for SqliteRepository {
+    async fn grants_for(&self, user_id: i64) -> Result<Grants, sqlx::Error> {
+        let roles = sqlx::query(
+            "select r.name from user_roles ur join roles r on r.id = ur.role_id where ur.user_id=? order by r.name")
+            .bind(user_id).fetch_all(&self.pool).await?
+            .iter().map(|r| r.get("name")).collect();
+        let permissions = sqlx::query(
+            "select distinct p.name from user_roles ur \
+             join role_permissions rp on rp.role_id = ur.role_id \
+             join permissions p on p.id = rp.permission_id \
+             where ur.user_id=? order by p.name")
+            .bind(user_id).fetch_all(&self.pool).await?
+            .iter().map(|r| r.get("name")).collect();
+        Ok(Grants { roles, permissions })
+    }
+
+    async fn assign_role(&self, username: &str, role: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "insert or ignore into user_roles(user_id, role_id) \
+             select u.id, r.id from users u, roles r where u.username=? and r.name=?")
+            .bind(username).bind(role).execute(&self.pool).await?;
+        if result.rows_affected()>0 {
+            return Ok(true);
+        }
+        // 이미 부여된 역할인 경우에도 true
+        let row = sqlx::query(
+            "select 1 from user_roles ur join users u on u.id = ur.user_id join roles r on r.id = ur.role_id \
+             where u.username=? and r.name=?")
+            .bind(username).bind(role).fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+
+    async fn remove_role(&self, username: &str, role: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "delete from user_roles where user_id=(select id from users where username=?) \
+             and role_id=(select id from roles where name=?)")
+            .bind(username).bind(role).execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn role_exists(&self, role: &str) -> Result<bool, sqlx::Error> {
+        let row = sqlx::query("select 1 from roles where name=?").bind(role).fetch_optional(&self.pool).await?;
+        Ok(row.is_some())
+    }
+}
+
+impl SqliteRepository {
+    async fn redirect_uris_of(&self, id: i64) -> Result<Vec<String>, sqlx::Error> {
+        let rows = sqlx::query("select redirect_uri from oauth_redirect_uris where client_id=? order by redirect_uri")
+            .bind(id).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("redirect_uri")).collect())
+    }
+
+    async fn client_from_row(&self, row: &SqliteRow) -> Result<Client, sqlx::Error> {
+        let id: i64 = row.get("id");
+        let redirect_uris = self.redirect_uris_of(id).await?;
+        Ok(Client::new(id, row.get("client_id"), row.get("name"), row.get("client_secret_hash"), redirect_uris))
+    }
+}
+
+#[async_trait]
+impl OAuthRepository for SqliteRepository {
+    async fn insert_client(&self, client_id: &str, secret_hash: Option<&str>, name: &str, redirect_uris: &[String]) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let id = sqlx::query("insert into oauth_clients(client_id, client_secret_hash, name) values (?, ?, ?)")
+            .bind(client_id).bind(secret_hash).bind(name)
+            .execute(&mut tx).await?.last_insert_rowid();
+        for uri in redirect_uris {
+            sqlx::query("insert or ignore into oauth_redirect_uris(client_id, redirect_uri) values (?, ?)")
+                .bind(id).bind(uri).execute(&mut tx).await?;
+        }
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    async fn find_client(&self, client_id: &str) -> Result<Option<Client>, sqlx::Error> {
+        let row = sqlx::query("select id, client_id, client_secret_hash, name from oauth_clients where client_id=?")
+            .bind(client_id).fetch_optional(&self.pool).await?;
+        match row {
+            Some(row) => Ok(Some(self.client_from_row(&row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_clients(&self) -> Result<Vec<Client>, sqlx::Error> {
+        let rows = sqlx::query("select id, client_id, client_secret_hash, name from oauth_clients order by id")
+            .fetch_all(&self.pool).await?;
+        let mut clients = Vec::with_capacity(rows.len());
+        for row in &rows {
+            clients.push(self.client_from_row(row).await?);
+        }
+        Ok(clients)
+    }
+
+    async fn delete_client(&self, client_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("delete from oauth_clients where client_id=?").bind(client_id)
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected()>0)
+    }
+
+    async fn insert_authorization_code(&self, code_hash: &str, request: &AuthorizationRequest<'_>, ttl_secs: u64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into oauth_authorization_codes(code_hash, client_id, user_id, redirect_uri, scope, code_challenge, nonce, expires_at) \
+             values (?, ?, ?, ?, ?, ?, ?, datetime('now', ?))")
+            .bind(code_hash).bind(request.client.id).bind(request.user_id)
+            .bind(request.redirect_uri).bind(request.scope).bind(request.code_challenge).bind(request.nonce)
+            .bind(after_secs(ttl_secs))
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn consume_authorization_code(&self, code_hash: &str) -> Result<Option<AuthorizationCode>, sqlx::Error> {
+        let row = sqlx::query(
+            "update oauth_authorization_codes set used_at=current_timestamp \
+             where code_hash=? and used_at is null and expires_at > datetime('now') \
+             returning client_id, user_id, redirect_uri, scope, code_challenge, nonce")
+            .bind(code_hash).fetch_optional(&self.pool).await?;
+        let row = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let user_id: i64 = row.get("user_id");
+        // RETURNING 절에서는 다른 테이블을 조회할 수 없으므로 사용자 이름은 별도 조회
+        let username: String = sqlx::query("select username from users where id=?").bind(user_id)
+            .fetch_one(&self.pool).await?.get("username");
+        Ok(Some(AuthorizationCode {
+            client_id: row.get("client_id"),
+            user_id,
+            username,
+            redirect_uri: row.get("redirect_uri"),
+            scope: row.get("scope"),
+            code_challenge: row.get("code_challenge"),
+            nonce: row.get("nonce"),
+        }))
+    }
+
+    async fn prune_authorization_codes(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("delete from oauth_authorization_codes where used_at is not null or expires_at <= datetime('now')")
+            .execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+}
+
+const AUDIT_FILTER_WHERE: &str = "where (? is null or event=?) and (? is null or outcome=?) and (? is null or actor=?) \
+     and (? is null or actor_id=?) and (? is null or ip=?) and (? is null or created_at>=?) and (? is null or created_at<?)";
+
+const AUDIT_COLUMNS: &str = "select id, cast(created_at as text) as created_at, event, outcome, actor_id, actor, ip, user_agent, detail from audit_events";
+
+// 조건이 없는 항목은 null로 바인딩하여 무시
+fn bind_audit_filter<'q>(query: Query<'q, Sqlite, SqliteArguments<'q>>, filter: &'q Filter) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query.bind(&filter.event).bind(&filter.event)
+        .bind(&filter.outcome).bind(&filter.outcome)
+        .bind(&filter.actor).bind(&filter.actor)
+        .bind(filter.actor_id).bind(filter.actor_id)
+        .bind(&filter.ip).bind(&filter.ip)
+        .bind(&filter.since).bind(&filter.since)
+        .bind(&filter.until).bind(&filter.until)
+}
+
+fn audit_event_from_row(row: &SqliteRow) -> AuditEvent {
+    AuditEvent {
+        id: row.get("id"),
+        created_at: row.get("created_at"),
+        event: row.get("event"),
+        outcome: row.get("outcome"),
+        actor_id: row.get("actor_id"),
+        actor: row.get("actor"),
+        ip: row.get("ip"),
+        user_agent: row.get("user_agent"),
+        detail: row.get("detail"),
+    }
+}
+
+#[async_trait]
+impl AuditRepository for SqliteRepository {
+    async fn insert_audit_event(&self, client: &ClientInfo, entry: &Entry<'_>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "insert into audit_events(event, outcome, actor_id, actor, ip, user_agent, detail) values (?, ?, ?, ?, ?, ?, ?)")
+            .bind(entry.event).bind(entry.outcome).bind(entry.actor_id).bind(entry.actor)
+            .bind(&client.ip).bind(&client.user_agent).bind(entry.detail)
+            .execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn list_audit_events(&self, filter: &Filter, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        let sql = format!("{} {} order by id desc limit ? offset ?", AUDIT_COLUMNS, AUDIT_FILTER_WHERE);
+        let rows = bind_audit_filter(sqlx::query(&sql), filter).bind(limit).bind(offset)
+            .fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(audit_event_from_row).collect())
+    }
+
+    async fn count_audit_events(&self, filter: &Filter) -> Result<i64, sqlx::Error> {
+        let sql = format!("select count(*) as total from audit_events {}", AUDIT_FILTER_WHERE);
+        Ok(bind_audit_filter(sqlx::query(&sql), filter).fetch_one(&self.pool).await?.get("total"))
+    }
+
+    async fn export_audit_events(&self, filter: &Filter) -> Result<Vec<AuditEvent>, sqlx::Error> {
+        let sql = format!("{} {} order by id", AUDIT_COLUMNS, AUDIT_FILTER_WHERE);
+        let rows = bind_audit_filter(sqlx::query(&sql), filter).fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(audit_event_from_row).collect())
+    }
+}
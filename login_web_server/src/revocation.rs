@@ -0,0 +1,24 @@
+use crate::repository::RevocationRepository;
+
+// 폐기된 access 토큰(jti) 등록
+// 토큰 만료 시각(exp)까지만 보관하면 되므로 등록할 때마다 만료된 항목을 정리
+pub async fn revoke(repo: &dyn RevocationRepository, jti: &str, expires_at: usize) -> Result<(), sqlx::Error> {
+    repo.revoke_token(jti, expires_at as i64).await?;
+    prune_expired(repo).await?;
+    Ok(())
+}
+
+// jti가 폐기 목록에 있는지 확인
+pub async fn is_revoked(repo: &dyn RevocationRepository, jti: &str) -> Result<bool, sqlx::Error> {
+    repo.is_token_revoked(jti).await
+}
+
+// 아직 만료되지 않은 폐기 토큰 수(메트릭용)
+pub async fn count_active(repo: &dyn RevocationRepository) -> Result<i64, sqlx::Error> {
+    repo.count_revoked_tokens().await
+}
+
+// 만료되어 더 이상 검증을 통과할 수 없는 토큰의 폐기 기록 삭제 후 삭제된 행 수 반환
+pub async fn prune_expired(repo: &dyn RevocationRepository) -> Result<u64, sqlx::Error> {
+    repo.prune_revoked_tokens().await
+}
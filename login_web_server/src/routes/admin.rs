@@ -0,0 +1,169 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use crate::error::ApiError;
+use crate::repository::Repository;
+use crate::session::ClientInfo;
+use crate::{audit, lockout, rbac, session};
+use crate::auth::AuthContext;
+
+// 사용자 목록 한 페이지 기본/최대 크기
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Deserialize)]
+pub struct ListUsersQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+fn user_not_found() -> ApiError {
+    ApiError::not_found("user_not_found", "User not found.")
+}
+
+// 사용자 이름으로 id 조회(없으면 404)
+async fn find_user_id(repo: &dyn Repository, username: &str) -> Result<i64, ApiError> {
+    repo.find_user_id(username).await?.ok_or_else(user_not_found)
+}
+
+// 관리자가 자기 자신을 비활성화/삭제하여 관리자가 사라지는 것을 방지
+fn reject_self(caller: &str, username: &str) -> Result<(), ApiError> {
+    if caller==username {
+        return Err(ApiError::conflict("cannot_modify_self", "Administrators cannot perform this action on their own account."));
+    }
+    Ok(())
+}
+
+// 관리 작업 감사 로그(행위자는 관리자, detail에 대상 사용자와 역할)
+async fn record(repo: &dyn Repository, req: &HttpRequest, auth: &AuthContext, event: &'static str, username: &str, role: Option<&str>) {
+    let detail = match role {
+        Some(role) => format!("user={} role={}", username, role),
+        None => format!("user={}", username),
+    };
+    let entry = audit::Entry::success(event).detail(&detail).actor(Some(auth.user_id), &auth.username);
+    audit::record(repo, &ClientInfo::from_request(req), entry).await;
+}
+
+// GET /api/admin/users?limit=&offset=
+// 전체 사용자 목록(역할, 비활성화 및 잠금 상태 포함)
+pub async fn list_users(repo: web::Data<dyn Repository>, query: web::Query<ListUsersQuery>) -> Result<HttpResponse, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let users = repo.list_users(limit, offset).await?;
+    let total = repo.count_users().await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"users": users, "total": total, "limit": limit, "offset": offset})))
+}
+
+// POST /api/admin/users/{username}/disable
+// 계정 비활성화(로그인 차단) 후 모든 세션 폐기
+pub async fn disable_user(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let username = path.into_inner();
+    reject_self(caller, &username)?;
+    let user_id = find_user_id(repo.get_ref(), &username).await?;
+
+    repo.set_disabled(user_id, true).await?;
+    let revoked = session::revoke_all(repo.get_ref(), user_id).await?;
+    tracing::info!("Admin {} disabled user {} ({} sessions revoked)", caller, username, revoked);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_DISABLE_USER, &username, None).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"username": username, "disabled": true, "revoked_sessions": revoked})))
+}
+
+// POST /api/admin/users/{username}/enable
+// 비활성화된 계정 다시 활성화
+pub async fn enable_user(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let username = path.into_inner();
+    let user_id = find_user_id(repo.get_ref(), &username).await?;
+
+    repo.set_disabled(user_id, false).await?;
+    tracing::info!("Admin {} enabled user {}", caller, username);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_ENABLE_USER, &username, None).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"username": username, "disabled": false})))
+}
+
+// DELETE /api/admin/users/{username}
+// 사용자 삭제(todo, 세션, refresh 토큰 등은 외래 키로 함께 삭제)
+pub async fn delete_user(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let username = path.into_inner();
+    reject_self(caller, &username)?;
+
+    if !repo.delete_user(&username).await? {
+        return Err(user_not_found());
+    }
+    // 삭제된 사용자의 로그인 실패 기록도 정리
+    lockout::unlock(repo.get_ref(), &username).await?;
+    tracing::info!("Admin {} deleted user {}", caller, username);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_DELETE_USER, &username, None).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// POST /api/admin/users/{username}/unlock
+// 로그인 실패로 잠긴 계정의 잠금 해제
+pub async fn unlock_user(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let username = path.into_inner();
+    let unlocked = lockout::unlock(repo.get_ref(), &username).await?;
+    tracing::info!("Admin {} unlocked user {} (was locked: {})", caller, username, unlocked);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_UNLOCK_USER, &username, None).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"username": username, "unlocked": unlocked})))
+}
+
+// POST /api/admin/users/{username}/logout
+// 사용자의 모든 세션 강제 종료(access 토큰은 세션 확인에서, refresh 토큰은 폐기로 거부됨)
+pub async fn force_logout(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let username = path.into_inner();
+    let user_id = find_user_id(repo.get_ref(), &username).await?;
+    let revoked = session::revoke_all(repo.get_ref(), user_id).await?;
+    tracing::info!("Admin {} forced logout of user {} ({} sessions revoked)", caller, username, revoked);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_FORCE_LOGOUT, &username, None).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"username": username, "revoked_sessions": revoked})))
+}
+
+// POST /api/admin/users/{username}/legacy-todos
+// 소유자 개념 도입 이전의 todo(마이그레이션에서 소유자 없이 이관됨)를 모두 사용자에게 이관
+pub async fn assign_legacy_todos(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let username = path.into_inner();
+    let user_id = find_user_id(repo.get_ref(), &username).await?;
+    let assigned = repo.assign_ownerless_todos(user_id).await?;
+    tracing::info!("Admin {} assigned {} legacy todos to user {}", caller, assigned, username);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_ASSIGN_LEGACY_TODOS, &username, None).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"username": username, "assigned_todos": assigned})))
+}
+
+// PUT /api/admin/users/{username}/roles/{role}
+// 역할 부여(다음 로그인 또는 토큰 갱신부터 적용)
+pub async fn assign_role(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<(String, String)>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let (username, role) = path.into_inner();
+    if !rbac::role_exists(repo.get_ref(), &role).await? {
+        return Err(ApiError::not_found("role_not_found", "Role not found."));
+    }
+    find_user_id(repo.get_ref(), &username).await?;
+    rbac::assign_role(repo.get_ref(), &username, &role).await?;
+    tracing::info!("Admin {} assigned role {} to user {}", caller, role, username);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_ASSIGN_ROLE, &username, Some(&role)).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// DELETE /api/admin/users/{username}/roles/{role}
+// 역할 회수(관리자가 자신의 admin 역할을 회수하는 것은 금지)
+// 회수한 역할이 담긴 access 토큰이 만료될 때까지 쓰이지 않도록 사용자의 모든 세션을 폐기(다시 로그인 필요)
+pub async fn remove_role(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<(String, String)>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let caller = &auth.username;
+    let (username, role) = path.into_inner();
+    if role==rbac::ADMIN_ROLE {
+        reject_self(caller, &username)?;
+    }
+    let user_id = find_user_id(repo.get_ref(), &username).await?;
+    if !rbac::remove_role(repo.get_ref(), &username, &role).await? {
+        return Err(ApiError::not_found("role_not_assigned", "The user does not have this role."));
+    }
+    let revoked = session::revoke_all(repo.get_ref(), user_id).await?;
+    tracing::info!("Admin {} removed role {} from user {} (revoked {} sessions)", caller, role, username, revoked);
+    record(repo.get_ref(), &req, &auth, audit::ADMIN_REMOVE_ROLE, &username, Some(&role)).await;
+    Ok(HttpResponse::NoContent().finish())
+}
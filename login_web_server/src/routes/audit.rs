@@ -0,0 +1,83 @@
+use actix_web::{http::header, web, HttpResponse};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Deserialize;
+use crate::audit::{self, Filter};
+use crate::error::ApiError;
+use crate::repository::Repository;
+
+// 감사 로그 한 페이지 기본/최대 크기
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+// 저장된 시각(created_at) 형식
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    event: Option<String>,
+    outcome: Option<String>,
+    actor: Option<String>,
+    actor_id: Option<i64>,
+    ip: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+}
+
+// 시각 조건 파싱(RFC 3339, "YYYY-MM-DD HH:MM:SS"(UTC), "YYYY-MM-DD") 후 저장 형식으로 변환
+fn parse_time(name: &'static str, value: &str) -> Result<String, ApiError> {
+    let parsed = DateTime::parse_from_rfc3339(value).map(|t| t.with_timezone(&Utc).naive_utc())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, TIME_FORMAT))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default()))
+        .map_err(|_| ApiError::bad_request("invalid_query", format!("{} must be an RFC 3339 timestamp or YYYY-MM-DD.", name)))?;
+    Ok(parsed.format(TIME_FORMAT).to_string())
+}
+
+impl AuditQuery {
+    fn filter(&self) -> Result<Filter, ApiError> {
+        if let Some(outcome) = &self.outcome
+            && outcome!=audit::SUCCESS && outcome!=audit::FAILURE {
+            return Err(ApiError::bad_request("invalid_query", "outcome must be success or failure."));
+        }
+        Ok(Filter {
+            event: self.event.clone(),
+            outcome: self.outcome.clone(),
+            actor: self.actor.clone(),
+            actor_id: self.actor_id,
+            ip: self.ip.clone(),
+            since: self.since.as_deref().map(|v| parse_time("since", v)).transpose()?,
+            until: self.until.as_deref().map(|v| parse_time("until", v)).transpose()?,
+        })
+    }
+}
+
+// GET /api/admin/audit?event=&outcome=&actor=&actor_id=&ip=&since=&until=&limit=&offset=
+// 감사 로그 조회(최신순)
+pub async fn list_audit_events(repo: web::Data<dyn Repository>, query: web::Query<AuditQuery>) -> Result<HttpResponse, ApiError> {
+    let filter = query.filter()?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let events = audit::list(repo.get_ref(), &filter, limit, offset).await?;
+    let total = audit::count(repo.get_ref(), &filter).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"events": events, "total": total, "limit": limit, "offset": offset})))
+}
+
+// GET /api/admin/audit/export?event=&outcome=&actor=&actor_id=&ip=&since=&until=
+// 조건에 맞는 모든 감사 로그를 JSON Lines(한 줄에 이벤트 하나, 기록순)로 내보내기
+pub async fn export_audit_events(repo: web::Data<dyn Repository>, query: web::Query<AuditQuery>) -> Result<HttpResponse, ApiError> {
+    let filter = query.filter()?;
+    let events = audit::export(repo.get_ref(), &filter).await?;
+    let mut body = String::new();
+    for event in &events {
+        let line = serde_json::to_string(event)
+            .map_err(|_| ApiError::internal("audit_export_failed", "Error exporting audit events."))?;
+        body.push_str(&line);
+        body.push('\n');
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "application/x-ndjson"))
+        .insert_header((header::CONTENT_DISPOSITION, "attachment; filename=\"audit-events.jsonl\""))
+        .body(body))
+}
@@ -1,12 +1,25 @@
-use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
-use sqlx::{Row, SqlitePool};
-use bcrypt::{hash, verify};
-use crate::auth::{create_jwt, decode_jwt};
-use crate::generator::generate_password as generate_random_password_string;    // crate 루트 기준 generate_password import
+use crate::repository::{NewUserError, Repository};
+use crate::auth::{create_jwt, create_mfa_challenge, decode_claims, decode_jwt, AuthContext};
+use crate::audit;
+use crate::config;
+use crate::password_hasher;
+use crate::error::ApiError;
+use crate::refresh_token::{self, RefreshError};
+use crate::revocation;
+use crate::session::{self, ClientInfo};
+use crate::lockout;
+use crate::rate_limit;
+use crate::mfa;
+use crate::rbac;
+use crate::password_policy::{PasswordPolicy, Violation};
+use crate::password_reset;
+use crate::mailer::{Email, Mailer};
+use crate::email_verification;
+use super::email::{send_mail, send_verification_mail};
 
-use std::sync::Arc;
-use crate::Denylist;
+use crate::generator::generate_password as generate_random_password_string;    // crate 루트 기준 generate_password import
 
 #[derive(Deserialize)]
 pub struct VerifyTokenRequest {
@@ -23,24 +36,39 @@ pub struct VerifyTokenResponse {
 pub struct RegisterInfo {
     username: String,
     password: String,
+    email: String,  // 인증 메일 수신 주소(인증 전에는 로그인 불가)
 }
 
 // JWT 토큰 문자열을 받아서 유효성 검증 후 결과를 응답하는 핸들러
 // 이 엔드포인트는 인증 없이 토큰 검증만 수행하므로 AuthMiddleware 보호 밖에 라우팅될 것임.
-pub async fn verify_token(info: web::Json<VerifyTokenRequest>) -> impl Responder { // 요청 본문으로 VerifyTokenRequest 받음
+pub async fn verify_token(repo: web::Data<dyn Repository>, info: web::Json<VerifyTokenRequest>, req: HttpRequest) -> Result<HttpResponse, ApiError> { // 요청 본문으로 VerifyTokenRequest 받음
     let token = &info.token; // 검증할 토큰 문자열 참조
+    let client = ClientInfo::from_request(&req);
 
-    // decode_jwt는 유효한 토큰이면 Ok(username), 유효하지 않으면 Err 를 반환.
+    // decode_jwt는 유효한 access 토큰이면 Ok(auth), 유효하지 않거나 2단계 인증 대기 토큰이면 Err 를 반환.
     match decode_jwt(token) {
-        Ok(username) => { // 토큰 유효성 검증 성공 시 (서명, 만료 시간 등 모두 통과)
-            println!("Token verification successful for user: {}", username);
+        Ok(auth) => { // 토큰 유효성 검증 성공 시 (서명, 발급자, 대상, 유효 기간 등 모두 통과)
+            // 서명이 유효해도 로그아웃으로 폐기된 토큰이면 유효하지 않음
+            if revocation::is_revoked(repo.get_ref(), &auth.token_id).await? {
+                let entry = audit::Entry::failure(audit::VERIFY_TOKEN, "revoked_token").actor(Some(auth.user_id), &auth.username);
+                audit::record(repo.get_ref(), &client, entry).await;
+                return Ok(HttpResponse::Ok().json(VerifyTokenResponse { valid: false, username: None }));
+            }
+            // OAuth 클라이언트에 발급한 토큰은 AuthMiddleware와 마찬가지로 이 서버의 API에 사용할 수 없으므로 유효하지 않음
+            if auth.is_client_token() {
+                let entry = audit::Entry::failure(audit::VERIFY_TOKEN, "insufficient_scope").actor(Some(auth.user_id), &auth.username);
+                audit::record(repo.get_ref(), &client, entry).await;
+                return Ok(HttpResponse::Ok().json(VerifyTokenResponse { valid: false, username: None }));
+            }
+            tracing::info!("Token verification successful for user: {}", auth.username);
             // 유효한 토큰이므로 valid: true 와 사용자 이름 반환
-            HttpResponse::Ok().json(VerifyTokenResponse { valid: true, username: Some(username) })
+            Ok(HttpResponse::Ok().json(VerifyTokenResponse { valid: true, username: Some(auth.username) }))
         }
         Err(e) => { // 토큰 유효성 검증 실패 시 (만료, 잘못된 서명, 형식 오류 등)
-            eprintln!("Token verification failed: {:?}", e); // 에러 로그 남김
+            tracing::warn!("Token verification failed: {:?}", e); // 에러 로그 남김
+            audit::record(repo.get_ref(), &client, audit::Entry::failure(audit::VERIFY_TOKEN, "invalid_token")).await;
             // 유효하지 않은 토큰이므로 valid: false 와 사용자 이름 없음 반환
-            HttpResponse::Ok().json(VerifyTokenResponse { valid: false, username: None })
+            Ok(HttpResponse::Ok().json(VerifyTokenResponse { valid: false, username: None }))
             // Note: 보안상 401 Unauthorized 로 응답할 수도 있으나,
             // 여기서는 토큰 자체의 유효성만 묻는 요청이므로 200 OK 에 valid: false 로 응답하는 것도 일반적.
             // 프론트엔드는 valid: false 를 보고 Unauthorized 로 판단.
@@ -48,169 +76,536 @@ pub async fn verify_token(info: web::Json<VerifyTokenRequest>) -> impl Responder
     }
 }
 
+// 비밀번호 해싱(설정된 알고리즘 사용, 실패 시 500 에러)
+fn hash_password(password: &str) -> Result<String, ApiError> {
+    password_hasher::hashers().hash(password).map_err(|e| {
+        tracing::error!("Error hashing password: {:?}", e);
+        ApiError::internal("password_hash_failed", "Error hashing password.")
+    })
+}
+
+// 저장된 해시와 비교(해시 형식 오류 등은 불일치로 처리)
+fn verify_password(password: &str, hash: &str) -> bool {
+    password_hasher::hashers().verify(password, hash).unwrap_or_else(|e| {
+        tracing::error!("Error verifying password: {:?}", e);
+        false
+    })
+}
+
+// 다른 알고리즘이나 이전 매개변수로 만든 해시면 로그인에 성공한 비밀번호로 다시 해싱하여 교체
+// 실패해도 로그인은 계속 진행(다음 로그인 때 다시 시도)
+async fn upgrade_password_hash(repo: &dyn Repository, user_id: i64, password: &str, hash: &str) {
+    let hashers = password_hasher::hashers();
+    if !hashers.needs_rehash(hash) {
+        return;
+    }
+    let result = match hashers.hash(password) {
+        // 동시에 비밀번호가 변경된 경우 덮어쓰지 않도록 기존 해시가 그대로일 때만 교체
+        Ok(new_hash) => repo.replace_password_hash(user_id, hash, &new_hash).await.map(|_| ()).map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => tracing::info!("Upgraded password hash of user {} to {}", user_id, hashers.algorithm().as_str()),
+        Err(e) => tracing::error!("Error upgrading password hash of user {}: {:?}", user_id, e),
+    }
+}
+
 // register 핸들러
 // 공개 비동기 함수
-pub async fn register(pool: web::Data<SqlitePool>, info: web::Json<RegisterInfo>) -> impl Responder {   // DB 풀 객체를 담은 web::Data 익스트랙터, 요청 본문의 json 데이터 RegisterInfo 구조체로 역직렬화, Actix-web 응답 반환
+pub async fn register(repo: web::Data<dyn Repository>, policy: web::Data<PasswordPolicy>, mailer: web::Data<dyn Mailer>, info: web::Json<RegisterInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {   // DB 풀 객체를 담은 web::Data 익스트랙터, 요청 본문의 json 데이터 RegisterInfo 구조체로 역직렬화, Actix-web 응답 반환
     // password validity process
-    let password = &info.password;
-    
-    // 길이 검사(8글자 이상)
-    if password.len()<8 {
-        return HttpResponse::BadRequest().body("Password must be at least 8 characters long...");
+    // 설정된 비밀번호 정책(길이, 문자 종류, 엔트로피, 사용자 이름 포함 여부, 유출 목록) 검사
+    let violations = policy.check(&info.username, &info.password);
+    if !violations.is_empty() {
+        return Err(ApiError::PasswordPolicy(violations));
     }
+    let email = email_verification::normalize_email(&info.email)
+        .ok_or_else(|| ApiError::bad_request("invalid_email", "Invalid email address."))?;
     
-    // 소문자, 대문자, 특수문자 포함 여부 검사
-    let mut has_lower = false;
-    let mut has_upper = false;
-    let mut has_special = false; // 특수 문자는 영숫자가 아닌 문자로 간단하게 검사
-
-    for c in password.chars() {
-        if c.is_lowercase() {
-            has_lower = true;
-        } else if c.is_uppercase() {
-            has_upper = true;
-        } else if !c.is_alphanumeric() { // 영문자나 숫자가 아니면 특수 문자로 간주
-            has_special = true;
-        }
+    // password hashing
+    let hashed = hash_password(&info.password)?;   // info 내의 password의 참조를 설정된 알고리즘으로 hashing
+    
+    // hashing password와 user infomation DB 삽입(이메일 인증 전까지 email_verified_at은 null)
+    // 기본 역할도 같은 트랜잭션에서 부여(역할 없는 계정이 남지 않도록)
+    let client = ClientInfo::from_request(&req);
+    let user_id = match repo.create_user(&info.username, &hashed, &email, rbac::DEFAULT_ROLE).await {
+            Ok(user_id) => user_id,
+            // username 또는 email 유일성 제약 위반이면 409 Conflict, 그 외에는 DB 에러
+            Err(NewUserError::Database(e)) => return Err(e.into()),
+            Err(taken) => {
+                let error = if matches!(taken, NewUserError::EmailTaken) {
+                    ApiError::conflict("email_taken", "Email address is already registered.")
+                } else {
+                    ApiError::conflict("username_taken", "Username already exists.")
+                };
+                // 기존 계정의 사용자 이름이므로 행위자 id는 기록하지 않음
+                audit::record(repo.get_ref(), &client, audit::Entry::failure(audit::REGISTER, error.code()).actor(None, &info.username)).await;
+                return Err(error);
+            }
+    };
+    audit::record(repo.get_ref(), &client, audit::Entry::success(audit::REGISTER).actor(Some(user_id), &info.username)).await;
+    // 인증 메일 발송(발송에 실패해도 계정은 유지되며 재발송 요청 가능)
+    // 가입 메일도 주소 기준 발송 횟수에 포함(가입 직후 재발송 요청은 제한)
+    if let Err(e) = rate_limit::hit_all(repo.get_ref(), &email_verification::address_limits(&email)).await {
+        tracing::error!("Error recording verification mail for user {}: {:?}", &info.username, e);
+    }
+    let token = email_verification::create(repo.get_ref(), user_id).await?;
+    if let Err(e) = send_verification_mail(&mailer, &email, &token).await {
+        tracing::error!("Error sending verification mail to user {}: {:?}", &info.username, e);
+        return Ok(HttpResponse::Created().body("User registered! We could not send the verification email. Please request a new one via /api/email/verify/resend."));
+    }
+    Ok(HttpResponse::Created().body("User registered! Check your email to verify your account."))   // 삽입 성공 시 201 Created 응답
+}
 
-        // 세 가지 조건을 모두 만족하면 더 이상 검사할 필요 없음
-        if has_lower && has_upper && has_special {
-            break;
-        }
+#[derive(Deserialize)]
+pub struct ChangePasswordInfo {
+    current_password: String,
+    new_password: String,
+}
+
+// change_password 핸들러
+// 현재 비밀번호 확인 후 정책을 통과한 새 비밀번호로 변경하고, 현재 세션을 제외한 모든 세션 폐기
+pub async fn change_password(repo: web::Data<dyn Repository>, policy: web::Data<PasswordPolicy>, auth: AuthContext, info: web::Json<ChangePasswordInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    // AuthMiddleware가 저장한 인증 정보의 사용자 이름
+    let username = &auth.username;
+    let client = ClientInfo::from_request(&req);
+
+    let credentials = repo.find_credentials(username).await?.ok_or(sqlx::Error::RowNotFound)?;
+    if !verify_password(&info.current_password, &credentials.password_hash) {
+        let entry = audit::Entry::failure(audit::CHANGE_PASSWORD, "invalid_current_password").actor(Some(auth.user_id), username);
+        audit::record(repo.get_ref(), &client, entry).await;
+        return Err(ApiError::unauthorized("invalid_current_password", "Current password is incorrect."));
     }
-    
-    // 세 가지 조건 중 하나라도 만족하지 않으면 에러 반환
-    if !has_lower || !has_upper || !has_special {
-        return HttpResponse::BadRequest().body("Password must contain at least one lowercase letter, one uppercase letter, and one special character.");
+
+    let mut violations = policy.check(username, &info.new_password);
+    if info.new_password==info.current_password {
+        violations.push(Violation { rule: "unchanged", message: "New password must differ from the current password.".to_string() });
     }
-    
-    // password hashing
-    let hashed = match hash(&info.password, 10) {   // info 내의 password의 참조를 10라운드로 hashing
-        Ok(h) => h, // hashing 성공 시 결과 저장
-        Err(_) => return HttpResponse::InternalServerError().body("Error hasing password...")   // hashing 실패 시 500 에러 응답 반환
+    if !violations.is_empty() {
+        return Err(ApiError::PasswordPolicy(violations));
+    }
+
+    let hashed = hash_password(&info.new_password)?;
+    // 이전 비밀번호로 로그인한 다른 기기(탈취 가능성 포함)의 세션 폐기
+    // 비밀번호 변경과 한 트랜잭션으로 처리(폐기에 실패하면 비밀번호도 바뀌지 않음)
+    let revoked = repo.change_password_hash(credentials.id, &hashed, &auth.session_id).await?;
+    tracing::info!("User {} changed password ({} other sessions revoked)", username, revoked);
+    audit::record(repo.get_ref(), &client, audit::Entry::success(audit::CHANGE_PASSWORD).actor(Some(auth.user_id), username)).await;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"revoked_sessions": revoked})))
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetRequestInfo {
+    username: String,
+}
+
+// request_password_reset 핸들러
+// 재설정 토큰을 발급하여 메일로 전달
+// 사용자 존재 여부를 노출하지 않도록 결과(메일 발송 실패 포함)와 관계없이 항상 같은 응답 반환
+// 사용자 이름 및 IP 기준 요청 횟수 제한은 존재 여부와 관계없이 적용(초과 시 429)
+pub async fn request_password_reset(repo: web::Data<dyn Repository>, mailer: web::Data<dyn Mailer>, info: web::Json<PasswordResetRequestInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let accepted = HttpResponse::Ok().body("If the account exists, a password reset message has been sent.");
+
+    let client = ClientInfo::from_request(&req);
+    let mut keys = vec![(password_reset::username_key(&info.username), password_reset::USERNAME_LIMIT)];
+    if let Some(ip) = &client.ip {
+        keys.push((password_reset::ip_key(ip), password_reset::IP_LIMIT));
+    }
+    if let Some(retry_after) = rate_limit::hit_all(repo.get_ref(), &keys).await? {
+        tracing::warn!("Password reset rate limited for user {} (retry after {}s)", &info.username, retry_after);
+        return Err(ApiError::RateLimited { retry_after });
+    }
+
+    // 이메일 인증 도입 이전에 가입하여 주소가 없는 계정은 사용자 이름을 수신자로 사용
+    let (user_id, recipient) = match repo.mail_recipient(&info.username).await? {
+        Some(recipient) => recipient,
+        None => {
+            tracing::warn!("Password reset requested for unknown user: {}", &info.username);
+            return Ok(accepted);
+        }
     };
-    
-    // hashing password와 user infomation DB 삽입
-    match sqlx::query("insert into users(username, password_hash) values (?, ?)").bind(&info.username).bind(&hashed)    // 쿼리 내의 placeholder에 문자열 바인딩 시 체인 형식으로 바인딩해야 함
-        .execute(pool.get_ref()).await {    // DB 풀 참조를 사용해 쿼리 실행 후 완료 대기
-            Ok(_) => HttpResponse::Ok().body("User registered!"),   // 삽입 성공 시 200 OK 응답
-            Err(e) => {
-                eprintln!("{:?}", e);
-                HttpResponse::BadRequest().body("Username already exists or DB Error...")   // 삽입 실패 시 400 Bad Request 응답
-            }
+
+    let token = password_reset::create(repo.get_ref(), user_id).await?;
+    let email = Email {
+        to: recipient,
+        subject: "Password reset".to_string(),
+        body: format!(
+            "A password reset was requested for your account.\r\n\r\nReset token: {}\r\n\r\nThis token expires in {} minutes and can be used only once. If you did not request this, you can ignore this message.",
+            token, config::get().tokens.password_reset_ttl_secs/60),
+    };
+    // 발송 실패를 다른 응답으로 알리면 계정 존재 여부가 드러나므로 기록만 하고 같은 응답 반환
+    if let Err(e) = send_mail(&mailer, email).await {
+        tracing::error!("Error sending password reset mail to user {}: {:?}", &info.username, e);
+    }
+    Ok(accepted)
+}
+
+#[derive(Deserialize)]
+pub struct PasswordResetConfirmInfo {
+    token: String,
+    new_password: String,
+}
+
+// 존재하지 않거나, 만료되었거나, 이미 사용된 재설정 토큰
+fn invalid_reset_token() -> ApiError {
+    ApiError::bad_request("invalid_reset_token", "Invalid or expired reset token.")
+}
+
+// confirm_password_reset 핸들러
+// 재설정 토큰(일회용)을 확인하고 새 비밀번호로 변경한 뒤 모든 세션 폐기
+pub async fn confirm_password_reset(repo: web::Data<dyn Repository>, policy: web::Data<PasswordPolicy>, info: web::Json<PasswordResetConfirmInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let client = ClientInfo::from_request(&req);
+    // 토큰 소유자 확인(정책 위반 시 토큰을 다시 사용할 수 있도록 이 단계에서는 소모하지 않음)
+    let (user_id, username) = match password_reset::lookup(repo.get_ref(), &info.token).await? {
+        Some(owner) => owner,
+        None => {
+            let error = invalid_reset_token();
+            audit::record(repo.get_ref(), &client, audit::Entry::failure(audit::RESET_PASSWORD, error.code())).await;
+            return Err(error);
+        }
+    };
+
+    let violations = policy.check(&username, &info.new_password);
+    if !violations.is_empty() {
+        return Err(ApiError::PasswordPolicy(violations));
     }
+
+    // 토큰 사용 처리와 비밀번호 변경을 한 트랜잭션으로 수행(동시에 같은 토큰이 제출되면 하나만 성공)
+    let hashed = hash_password(&info.new_password)?;
+    password_reset::consume(repo.get_ref(), &info.token, &hashed).await?
+        .ok_or_else(invalid_reset_token)?;
+    // 계정 탈취 후 재설정한 경우를 고려하여 모든 기기의 로그인 폐기, 로그인 잠금도 해제
+    session::revoke_all(repo.get_ref(), user_id).await?;
+    if let Err(e) = lockout::unlock(repo.get_ref(), &username).await {
+        tracing::error!("Error clearing login failures for user {}: {:?}", username, e);
+    }
+    tracing::info!("User {} reset password", username);
+    audit::record(repo.get_ref(), &client, audit::Entry::success(audit::RESET_PASSWORD).actor(Some(user_id), &username)).await;
+    Ok(HttpResponse::Ok().body("Password has been reset. Please log in again."))
 }
 
 #[derive(Serialize)]
 struct LoginSuccessResponse {
-    token: String,
+    token: String,  // access 토큰(JWT)
+    refresh_token: String,  // access 토큰 재발급용 불투명(opaque) 토큰
+    expires_in: u64,    // access 토큰 유효 기간(초)
     username: String,
 }
 
+// 토큰 생성 실패(서명 키 설정 오류 등)
+fn token_creation_failed(username: &str) -> ApiError {
+    tracing::error!("Error creating JWT for user {}...", username);
+    ApiError::internal("token_creation_failed", "Error creating token.")
+}
+
+// 관리자에 의해 비활성화된 계정이면 403 에러
+async fn ensure_active(repo: &dyn Repository, user_id: i64) -> Result<(), ApiError> {
+    if !is_active(repo, user_id).await? {
+        return Err(ApiError::forbidden("account_disabled", "This account has been disabled."));
+    }
+    Ok(())
+}
+
+// 관리자에 의해 비활성화되지 않은 계정인지 확인(OAuth 토큰 엔드포인트와 공유)
+pub(super) async fn is_active(repo: &dyn Repository, user_id: i64) -> Result<bool, sqlx::Error> {
+    repo.is_active(user_id).await
+}
+
+// access 토큰과 refresh 토큰을 함께 발급하여 로그인 성공 응답 생성
+async fn issue_tokens(repo: &dyn Repository, user_id: i64, username: &str, device_label: Option<&str>, client: &ClientInfo) -> Result<HttpResponse, ApiError> {
+    // 로그인마다 새 세션(refresh 토큰 family) 시작
+    let family_id = refresh_token::new_family_id();
+    session::create(repo, &family_id, user_id, device_label, client, None).await?;
+    // 토큰에 담을 역할 및 권한 조회
+    let grants = rbac::grants_for(repo, user_id).await?;
+    let token = create_jwt(user_id, username, &family_id, &grants).map_err(|_| token_creation_failed(username))?;  // username에 대한 JWT 생성
+    let refresh_token = refresh_token::issue(repo, user_id, &family_id).await?;
+    Ok(HttpResponse::Ok().json(LoginSuccessResponse {
+        token,
+        refresh_token,
+        expires_in: config::get().tokens.access_ttl_secs,
+        username: username.to_string(),
+    }))
+}
+
 #[derive(Deserialize)]
 pub struct LoginInfo {
     username: String,
     password: String,
+    device_label: Option<String>,   // 세션 목록에 표시할 기기 이름(없으면 User-Agent 사용)
+}
+
+// 사용자 이름 및 클라이언트 IP 단위 실패 기록 키와 잠금 임계치
+fn failure_keys(username: &str, client: &ClientInfo) -> Vec<(String, i64)> {
+    let mut keys = vec![(lockout::username_key(username), lockout::USERNAME_FAILURE_THRESHOLD)];
+    keys.extend(client.ip.as_deref().map(|ip| (lockout::ip_key(ip), lockout::IP_FAILURE_THRESHOLD)));
+    keys
+}
+
+// 로그인 실패 기록(임계치 도달 시 잠금), 이번 실패로 잠금이 걸렸다면 가장 긴 잠금 시간(초) 반환
+async fn record_login_failures(repo: &dyn Repository, failures: Vec<(String, i64)>) -> Option<u64> {
+    let mut locked: Option<u64> = None;
+    for (key, threshold) in failures {
+        match lockout::record_failure(repo, &key, threshold).await {
+            Ok(Some(secs)) => {
+                tracing::warn!("Login locked for {} ({}s)", key, secs);
+                locked = Some(locked.map_or(secs, |l| l.max(secs)));
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!("Error recording login failure for {}: {:?}", key, e),
+        }
+    }
+    locked
+}
+
+// 로그인이 끝났을 때(2단계 인증까지 통과) 사용자 이름 단위 실패 기록 초기화
+// 비밀번호만 맞힌 단계에서 초기화하면 다시 로그인하는 것만으로 코드 추측 횟수 제한을 우회할 수 있음
+async fn clear_login_failures(repo: &dyn Repository, username: &str) {
+    if let Err(e) = lockout::record_success(repo, &lockout::username_key(username)).await {
+        tracing::error!("Error clearing login failures for user {}: {:?}", username, e);
+    }
+}
+
+// 2단계 인증 코드 실패 기록(비밀번호 실패와 같은 키와 잠금 정책 적용)
+// 이번 실패로 잠금이 걸리면 다음 요청을 기다리지 않고 429 응답
+async fn mfa_code_failed(repo: &dyn Repository, username: &str, client: &ClientInfo) -> ApiError {
+    tracing::warn!("Invalid second factor for user: {}", username);
+    match record_login_failures(repo, failure_keys(username, client)).await {
+        Some(retry_after) => ApiError::TooManyAttempts { retry_after },
+        None => ApiError::unauthorized("invalid_mfa_code", "Invalid two-factor authentication code."),
+    }
+}
+
+// 비밀번호 확인 이후 로그인 처리
+// 비밀번호가 맞더라도 비활성화된 계정이나 이메일 인증 전인 계정은 로그인 불가
+async fn complete_login(repo: &dyn Repository, user_id: i64, info: &LoginInfo, client: &ClientInfo) -> Result<HttpResponse, ApiError> {
+    ensure_active(repo, user_id).await?;
+    if !email_verification::is_verified(repo, user_id).await? {
+        return Err(ApiError::forbidden("email_not_verified", "Please verify your email address before logging in."));
+    }
+    // 2단계 인증이 활성화된 계정은 토큰 대신 challenge 토큰 발급(/api/login/mfa에서 코드와 교환)
+    if mfa::is_enabled(repo, user_id).await? {
+        tracing::info!("User {} passed password check, awaiting second factor", &info.username);
+        return mfa_challenge(user_id, &info.username);
+    }
+    // 비밀번호 검증 성공 시 access 토큰 및 refresh 토큰 생성
+    clear_login_failures(repo, &info.username).await;
+    tracing::info!("User {} logged in successfully!", &info.username);
+    issue_tokens(repo, user_id, &info.username, info.device_label.as_deref(), client).await  // 토큰 생성 성공 시 토큰을 포함한 json 객체와 200 OK 응답
+}
+
+// OAuth 인가 화면에서 비밀번호 확인 이후 인가 코드를 발급할 수 있는지 확인
+// 토큰 로그인과 같은 조건(비활성화, 이메일 미인증 거부)을 적용하고,
+// 2단계 인증이 활성화된 계정은 challenge 토큰 대신 같은 화면에서 입력받은 코드를 확인
+pub(super) async fn check_authorize_login(repo: &dyn Repository, user_id: i64, username: &str, mfa_code: Option<&str>, client: &ClientInfo) -> Result<(), ApiError> {
+    ensure_active(repo, user_id).await?;
+    if !email_verification::is_verified(repo, user_id).await? {
+        return Err(ApiError::forbidden("email_not_verified", "Please verify your email address before logging in."));
+    }
+    if mfa::is_enabled(repo, user_id).await? {
+        let Some(code) = mfa_code.map(str::trim).filter(|code| !code.is_empty()) else {
+            return Err(ApiError::unauthorized("mfa_required", "Two-factor authentication code required."));
+        };
+        if !mfa::verify(repo, user_id, code).await? {
+            return Err(mfa_code_failed(repo, username, client).await);
+        }
+    }
+    clear_login_failures(repo, username).await;
+    Ok(())
 }
 
 // login 핸들러
 // 공개 비동기 함수
-pub async fn login(pool: web::Data<SqlitePool>, info: web::Json<LoginInfo>, denylist: web::Data<Arc<Denylist>>) -> impl Responder {
+pub async fn login(repo: web::Data<dyn Repository>, info: web::Json<LoginInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let client = ClientInfo::from_request(&req);
+    let user_id = check_password(repo.get_ref(), &info.username, &info.password, &client, audit::LOGIN).await?;
+    let result = complete_login(repo.get_ref(), user_id, &info, &client).await;
+    // 비밀번호 확인 이후의 결과(비활성화, 이메일 미인증 등 실패 포함) 기록
+    audit::record(repo.get_ref(), &client, audit::Entry::from_result(audit::LOGIN, &result).actor(Some(user_id), &info.username)).await;
+    result
+}
+
+// 사용자 이름과 비밀번호 확인 후 사용자 id 반환(로그인 및 OAuth 인가 화면에서 공유)
+// 잠금 상태면 429, 실패하면 감사 로그와 실패 기록을 남기고 401
+// 성공 이후의 감사 로그와 실패 기록 초기화는 호출하는 쪽에서 2단계 인증 여부에 따라 처리
+pub(super) async fn check_password(repo: &dyn Repository, username: &str, password: &str, client: &ClientInfo, event: &'static str) -> Result<i64, ApiError> {
+    // 사용자 이름 및 클라이언트 IP 단위 실패 기록 키
+    let failures = failure_keys(username, client);
+
+    // 잠금 상태 확인(잠겨 있으면 비밀번호 검증 없이 429 Too Many Requests 응답)
+    let keys: Vec<String> = failures.iter().map(|(key, _)| key.clone()).collect();
+    if let Some(retry_after) = lockout::check(repo, &keys).await? {
+        tracing::warn!("Login blocked for user {} (locked for {}s)", username, retry_after);
+        let error = ApiError::TooManyAttempts { retry_after };
+        audit::record(repo, client, audit::Entry::failure(event, error.code()).actor(None, username)).await;
+        return Err(error);
+    }
+
     // username으로 DB에서 사용자의 password_hash 조회
-    let row = match sqlx::query("select password_hash from users where username=?").bind(&info.username)    // 쿼리 바인딩
-        .fetch_one(pool.get_ref()).await {  // fetch_one(): 쿼리 결과 중 첫 번째 행만 획득
-            Ok(r) => r, // 사용자 존재 시 결과 행 저장
-            Err(_) => return HttpResponse::Unauthorized().body("Invalid username or password..."),  // 사용자가 없거나 DB 에러 시 401 Unauthorized 응답 반환
-    };
+    let credentials = repo.find_credentials(username).await?;  // 사용자가 없으면 None
     
     // 입력 비밀번호와 DB 저장 해시값 비교(검증)
         // 해시는 단방향 암호화이기 때문에 동일한 메시지는 동일한 다이제스트를 가짐
-    if verify(&info.password, (&row).get("password_hash")).unwrap_or(false) {   // verify() & unwrap_or(false): 입력 password의 참조와 DB 해시의 참조 비교 후 결과(bool) 획득
-        denylist.0.lock().unwrap().remove(&info.username.to_string());
-        // 비밀번호 검증 성공 시 JWT 토큰 생성
-        match create_jwt(&info.username) {  // username에 대한 JWT 생성
-            Ok(token) => {
-                println!("User {} logged in successfully!", &info.username);
-                let response_data = LoginSuccessResponse {
-                    token: token,
-                    username: info.username.clone(),
-                };
-                HttpResponse::Ok().json(response_data)  // 토큰 생성 성공 시 토큰을 포함한 json 객체와 200 OK 응답
-            }
-            Err(_) => {
-                eprintln!("Error creating JWT for user {}...", &info.username);
-                HttpResponse::InternalServerError().body("Error creating token...")  // 토큰 생성 실패 시 500 에러 응답 반환
-            }
+    // 사용자가 없는 경우도 같은 실패로 처리하고, 고정된 더미 해시로 검증해 응답 시간 차이로 존재 여부가 드러나지 않게 함
+    let verified = match &credentials {
+        Some(c) => verify_password(password, &c.password_hash),   // 입력 password의 참조와 DB 해시의 참조 비교 후 결과(bool) 획득
+        None => password_hasher::hashers().verify_dummy(password),
+    };
+    match credentials {
+        Some(credentials) if verified => {
+            upgrade_password_hash(repo, credentials.id, password, &credentials.password_hash).await;
+            Ok(credentials.id)
+        }
+        _ => {
+            tracing::warn!("Login failed invalid password for user: {}", username);
+            // 존재하는 사용자면 id도 기록(특정 계정을 노린 시도 추적)
+            let user_id = credentials.as_ref().map(|c| c.id);
+            audit::record(repo, client, audit::Entry::failure(event, "invalid_credentials").actor(user_id, username)).await;
+            // 사용자 이름 및 IP 단위로 실패 기록(임계치 도달 시 잠금)
+            record_login_failures(repo, failures).await;
+            Err(ApiError::unauthorized("invalid_credentials", "Invalid username or password."))    // 비밀번호 검증 실패 시 401 Unauthorized 응답 반환
         }
-    } else {
-        eprintln!("Login failed invalid password for user: {}", &info.username);
-        HttpResponse::Unauthorized().body("Invalid username or password...")    // 비밀번호 검증 실패 시 401 Unauthorized 응답 반환
     }
 }
 
-// logout 핸들러
-pub async fn logout(req: HttpRequest, denylist: web::Data<Arc<Denylist>>) -> impl Responder {
-    let temp_extensions = req.extensions();
-    // RequestExtensions에서 인증된 사용자 이름 얻기
-    let username = match temp_extensions.get::<String>() {
-        Some(username) => username,
-        None => {
-            // AuthMiddleware를 거치지 않았거나 설정 오류
-            return HttpResponse::InternalServerError().body("Authentication context missing...");
-        }
+#[derive(Serialize)]
+struct MfaChallengeResponse {
+    mfa_required: bool,
+    challenge_token: String,    // 2단계 인증 대기 토큰(API 접근 불가, /api/login/mfa 전용)
+    expires_in: u64,
+}
+
+// 2단계 인증 대기 응답 생성
+fn mfa_challenge(user_id: i64, username: &str) -> Result<HttpResponse, ApiError> {
+    let challenge_token = create_mfa_challenge(user_id, username).map_err(|_| token_creation_failed(username))?;
+    Ok(HttpResponse::Ok().json(MfaChallengeResponse {
+        mfa_required: true,
+        challenge_token,
+        expires_in: config::get().tokens.mfa_challenge_ttl_secs,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct LoginMfaInfo {
+    challenge_token: String,
+    code: String,   // TOTP 코드 또는 복구 코드
+    device_label: Option<String>,
+}
+
+// 유효하지 않거나 만료, 이미 사용된 challenge 토큰
+fn invalid_challenge() -> ApiError {
+    ApiError::unauthorized("invalid_mfa_challenge", "Invalid or expired challenge token.")
+}
+
+// login_mfa 핸들러
+// challenge 토큰과 2단계 인증 코드를 확인한 뒤 access 토큰 및 refresh 토큰 발급
+pub async fn login_mfa(repo: web::Data<dyn Repository>, info: web::Json<LoginMfaInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let claims = match decode_claims(&info.challenge_token) {
+        Ok(claims) if claims.mfa_pending => claims,
+        _ => return Err(invalid_challenge()),
     };
-    println!("{}", username);
-    
-    // Authorization 헤더에서 현재 사용된 토큰 추출
-    // (Denylist에 토큰 문자열 자체를 넣거나 사용자 이름과 함께 관리하기 위해 필요)
-    // 사용자 이름 자체를 Denylist에 추가하여, 해당 사용자의 모든 토큰을 무효화하는 방식으로 구현합니다.
-    // 이는 해당 사용자가 다시 로그인하기 전까지는 어떤 유효한 토큰으로도 접근이 불가능하게 합니다.
+    let client = ClientInfo::from_request(&req);
 
-    // Mutex Lock 획득 후 Denylist에 사용자 이름 추가
-    // if 문 블록 이탈 시 Mutex Lock 해제
-    if denylist.0.lock().unwrap().insert(username.to_string()) { // HashSet에 사용자 이름 삽입. 삽입 성공 시 true 반환.
-        HttpResponse::Ok().body("Logged out successfully...") // 삽입 성공 (새로 무효화)
-    } else {
-        HttpResponse::Ok().body("Already logged out or invalid token...") // 이미 무효화되어 있었음
+    // challenge 토큰은 일회용(성공 시 폐기 목록에 등록)
+    if revocation::is_revoked(repo.get_ref(), &claims.jti).await? {
+        return Err(invalid_challenge());
+    }
+    // 코드 추측 공격도 비밀번호 실패와 같은 잠금 정책 적용(비밀번호 확인 때의 실패 기록은 아직 초기화되지 않음)
+    let keys: Vec<String> = failure_keys(&claims.sub, &client).into_iter().map(|(key, _)| key).collect();
+    if let Some(retry_after) = lockout::check(repo.get_ref(), &keys).await? {
+        return Err(ApiError::TooManyAttempts { retry_after });
+    }
+
+    // challenge 토큰 발급 이후 삭제된 사용자(삭제 후 같은 id가 재사용된 경우 포함)
+    if !repo.user_matches(claims.uid, &claims.sub).await? {
+        return Err(invalid_challenge());
+    }
+    let user_id = claims.uid;
+    // challenge 토큰 발급 이후 비활성화된 계정
+    ensure_active(repo.get_ref(), user_id).await?;
+    if !mfa::verify(repo.get_ref(), user_id, &info.code).await? {
+        audit::record(repo.get_ref(), &client, audit::Entry::failure(audit::LOGIN_MFA, "invalid_mfa_code").actor(Some(user_id), &claims.sub)).await;
+        return Err(mfa_code_failed(repo.get_ref(), &claims.sub, &client).await);
     }
+
+    revocation::revoke(repo.get_ref(), &claims.jti, claims.exp).await?;
+    clear_login_failures(repo.get_ref(), &claims.sub).await;
+    tracing::info!("User {} logged in successfully with two-factor authentication!", claims.sub);
+    let result = issue_tokens(repo.get_ref(), user_id, &claims.sub, info.device_label.as_deref(), &client).await;
+    audit::record(repo.get_ref(), &client, audit::Entry::from_result(audit::LOGIN_MFA, &result).actor(Some(user_id), &claims.sub)).await;
+    result
 }
 
-// delete 핸들러
-pub async fn delete_user(pool: web::Data<SqlitePool>, denylist: web::Data<Arc<Denylist>>, req: HttpRequest) -> impl Responder {
-    let temp_extenstions = req.extensions();
-    // RequestExtension에서 인증된 사용자 이름 얻기
-    let username = match temp_extenstions.get::<String>() {
-        Some(username) => username,
-        None => {
-            // AuthMiddleware를 거치지 않았거나 설정 오류
-            return HttpResponse::InternalServerError().body("Authentication context missing...");
+#[derive(Deserialize)]
+pub struct RefreshInfo {
+    refresh_token: String,
+}
+
+// refresh 핸들러
+// refresh 토큰을 교체(rotation)하고 새 access 토큰 발급
+// access 토큰이 만료된 상태에서 호출되므로 AuthMiddleware 보호 밖에 라우팅
+pub async fn refresh(repo: web::Data<dyn Repository>, info: web::Json<RefreshInfo>) -> Result<HttpResponse, ApiError> {
+    // OAuth 클라이언트에 발급한 refresh 토큰은 거부(클라이언트의 /oauth/token에서만 교체 가능)
+    let rotated = match refresh_token::rotate(repo.get_ref(), &info.refresh_token, None).await {
+        Ok(rotated) => rotated,
+        Err(RefreshError::Reused) => {
+            // 이미 사용된 토큰 재사용 -> 탈취 의심으로 해당 family 전체 폐기됨
+            tracing::warn!("Refresh token reuse detected, token family revoked");
+            return Err(ApiError::unauthorized("refresh_token_reused", "Refresh token reuse detected. Please log in again."));
+        }
+        Err(RefreshError::Invalid) | Err(RefreshError::Expired) => {
+            return Err(ApiError::unauthorized("invalid_refresh_token", "Invalid or expired refresh token."));
         }
+        Err(RefreshError::Database(e)) => return Err(e.into()),
     };
-    
-    println!("{}", username);
+    // 비활성화 시 세션과 refresh 토큰이 모두 폐기되지만, 역할 변경 반영을 위해 갱신 시마다 다시 조회
+    ensure_active(repo.get_ref(), rotated.user_id).await?;
+    let grants = rbac::grants_for(repo.get_ref(), rotated.user_id).await?;
+    let token = create_jwt(rotated.user_id, &rotated.username, &rotated.family_id, &grants).map_err(|_| token_creation_failed(&rotated.username))?;
+    tracing::info!("Tokens refreshed for user {} (id {})", &rotated.username, rotated.user_id);
+    Ok(HttpResponse::Ok().json(LoginSuccessResponse {
+        token,
+        refresh_token: rotated.refresh_token,
+        expires_in: config::get().tokens.access_ttl_secs,
+        username: rotated.username,
+    }))
+}
+
+// logout 핸들러
+// 현재 요청에 사용된 토큰(jti)과 그 세션만 폐기하므로 다른 기기의 로그인은 유지됨
+pub async fn logout(repo: web::Data<dyn Repository>, auth: AuthContext, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    // 폐기 목록(revoked_tokens)에 jti 등록 -> DB에 저장되므로 서버 재시작 후에도 유지
+    revocation::revoke(repo.get_ref(), &auth.token_id, auth.expires_at).await?;
+    // 로그아웃 이후 refresh 토큰으로 재발급받을 수 없도록 해당 세션(및 refresh 토큰) 폐기
+    session::revoke(repo.get_ref(), &auth.username, &auth.session_id).await?;
+    audit::record(repo.get_ref(), &ClientInfo::from_request(&req), audit::Entry::success(audit::LOGOUT).actor(Some(auth.user_id), &auth.username)).await;
+    tracing::info!("User {} logged out", auth.username);
+    Ok(HttpResponse::Ok().body("Logged out successfully..."))
+}
+
+// delete 핸들러
+pub async fn delete_user(repo: web::Data<dyn Repository>, auth: AuthContext, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    // AuthMiddleware가 저장한 인증 정보의 사용자 이름
+    let username = &auth.username;
     
     // DB에서 사용자 삭제 쿼리 실행
-    match sqlx::query("delete from users where username=?").bind(username)
-        .execute(pool.get_ref()).await {
-            Ok(result) => {
-                // 삭제된 행 수 확인
-                if result.rows_affected()>0 { // 사용자가 존재했을 경우
-                    println!("ok!");
-                    // 해당 사용자의 모든 토큰 무효화
-                    denylist.0.lock().unwrap().insert(username.clone());
-                    HttpResponse::Ok().body("User deleted successfully.")
-                } else {    // 사용자가 이미 없었거나 잘못된 사용자 이름이었다면
-                    HttpResponse::NotFound().body("User not found in database...")
-                }
-            }    
-            Err(_) => {
-                HttpResponse::InternalServerError().body("Database error during deletion...")
-            }   
-        }
+    // 세션과 refresh 토큰은 외래 키(on delete cascade)로 함께 삭제되고,
+    // 이미 발급된 다른 access 토큰은 AuthMiddleware의 세션 확인에서 거부됨
+    if !repo.delete_user(username).await? {  // 사용자가 이미 없었거나 잘못된 사용자 이름이었다면
+        return Err(ApiError::not_found("user_not_found", "User not found."));
+    }
+    tracing::info!("User {} deleted account", username);
+    // 삭제된 사용자의 기록도 유지(audit_events는 users를 참조하지 않음)
+    audit::record(repo.get_ref(), &ClientInfo::from_request(&req), audit::Entry::success(audit::DELETE_USER).actor(Some(auth.user_id), username)).await;
+    // 현재 토큰 무효화
+    if let Err(e) = revocation::revoke(repo.get_ref(), &auth.token_id, auth.expires_at).await {
+        tracing::error!("Error revoking token for user {}: {:?}", username, e);
+    }
+    Ok(HttpResponse::Ok().body("User deleted successfully."))
 }
 
 // generate_password 핸들러
 pub async fn generate_password() -> impl Responder {
     let password = generate_random_password_string();
     HttpResponse::Ok().json(serde_json::json!({"password": password}))
-}
\ No newline at end of file
+}
@@ -0,0 +1,76 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use crate::{config, email_verification, rate_limit};
+use crate::error::ApiError;
+use crate::mailer::{Email, Mailer};
+use crate::repository::Repository;
+use crate::session::ClientInfo;
+
+// 메일 발송(Mailer::send는 블로킹 I/O이므로 actix 스레드 풀에서 실행)
+pub(super) async fn send_mail(mailer: &web::Data<dyn Mailer>, email: Email) -> anyhow::Result<()> {
+    let mailer = mailer.clone();
+    web::block(move || mailer.send(&email)).await?
+}
+
+// 인증 토큰을 담은 메일 발송(실패는 호출하는 쪽에서 기록, 재발송 요청으로 다시 받을 수 있음)
+pub(super) async fn send_verification_mail(mailer: &web::Data<dyn Mailer>, to: &str, token: &str) -> anyhow::Result<()> {
+    let email = Email {
+        to: to.to_string(),
+        subject: "Verify your email address".to_string(),
+        body: format!(
+            "Please verify your email address to activate your account.\r\n\r\nVerification token: {}\r\n\r\nThis token expires in {} hours. If you did not create an account, you can ignore this message.",
+            token, config::get().tokens.email_verification_ttl_secs/3600),
+    };
+    send_mail(mailer, email).await
+}
+
+#[derive(Deserialize)]
+pub struct VerifyEmailInfo {
+    token: String,
+}
+
+// POST /api/email/verify
+// 메일로 받은 토큰으로 이메일 인증(이후 로그인 가능)
+pub async fn verify_email(repo: web::Data<dyn Repository>, info: web::Json<VerifyEmailInfo>) -> Result<HttpResponse, ApiError> {
+    let user_id = email_verification::consume(repo.get_ref(), &info.token).await?
+        .ok_or_else(|| ApiError::bad_request("invalid_verification_token", "Invalid or expired verification token."))?;
+    tracing::info!("User {} verified email address", user_id);
+    Ok(HttpResponse::Ok().json(serde_json::json!({"verified": true})))
+}
+
+#[derive(Deserialize)]
+pub struct ResendVerificationInfo {
+    email: String,
+}
+
+// POST /api/email/verify/resend
+// 인증 메일 재발송(로그인할 수 없는 상태에서 호출되므로 AuthMiddleware 미적용)
+// 주소 존재 여부를 노출하지 않도록 결과(메일 발송 실패 포함)와 관계없이 같은 응답 반환
+// 주소 및 IP 기준 요청 횟수 제한은 존재 여부와 관계없이 적용(초과 시 429)
+pub async fn resend_verification(repo: web::Data<dyn Repository>, mailer: web::Data<dyn Mailer>, info: web::Json<ResendVerificationInfo>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let accepted = HttpResponse::Ok().body("If the address belongs to an unverified account, a verification message has been sent.");
+    let email = match email_verification::normalize_email(&info.email) {
+        Some(email) => email,
+        None => return Ok(accepted),
+    };
+
+    let mut keys = email_verification::address_limits(&email);
+    if let Some(ip) = ClientInfo::from_request(&req).ip {
+        keys.push((email_verification::ip_key(&ip), email_verification::IP_LIMIT));
+    }
+    if let Some(retry_after) = rate_limit::hit_all(repo.get_ref(), &keys).await? {
+        tracing::warn!("Verification resend rate limited for {} (retry after {}s)", email, retry_after);
+        return Err(ApiError::RateLimited { retry_after });
+    }
+
+    let user_id = match repo.find_unverified_user_id(&email).await? {
+        Some(user_id) => user_id,
+        None => return Ok(accepted),
+    };
+    let token = email_verification::create(repo.get_ref(), user_id).await?;
+    // 발송 실패를 다른 응답으로 알리면 계정 존재 여부가 드러나므로 기록만 하고 같은 응답 반환
+    if let Err(e) = send_verification_mail(&mailer, &email, &token).await {
+        tracing::error!("Error sending verification mail to {}: {:?}", email, e);
+    }
+    Ok(accepted)
+}
@@ -0,0 +1,27 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use crate::auth::AuthContext;
+use crate::error::ApiError;
+use crate::repository::Repository;
+
+// 이벤트 한 번 조회 기본/최대 개수
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Deserialize)]
+pub struct ListEventsQuery {
+    after: Option<i64>,     // 이전 응답의 next_after(처음이면 생략)
+    limit: Option<i64>,
+}
+
+// GET /api/events?after=&limit=
+// 인증된 사용자의 todo 이벤트(마감 알림) 중 id가 after보다 큰 것(기록순)
+// 클라이언트는 next_after를 저장해 두고 다음 요청에 전달(전달한 이벤트는 7일간 보관)
+pub async fn list_events(repo: web::Data<dyn Repository>, auth: AuthContext, query: web::Query<ListEventsQuery>) -> Result<HttpResponse, ApiError> {
+    let after = query.after.unwrap_or(0).max(0);
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let events = repo.list_todo_events(auth.user_id, after, limit).await?;
+    let next_after = events.last().map_or(after, |e| e.id);
+    Ok(HttpResponse::Ok().json(serde_json::json!({"events": events, "next_after": next_after, "limit": limit})))
+}
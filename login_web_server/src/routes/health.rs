@@ -0,0 +1,27 @@
+use actix_web::{web, HttpResponse};
+use serde_json::json;
+use web_tracing::health::{check_database, check_migrations, readiness_response, Component};
+use crate::auth::key_set;
+use crate::repository::Repository;
+
+pub use web_tracing::health::healthz;
+
+// JWT 서명 키 로드 여부 확인
+fn check_keys() -> Component {
+    match key_set() {
+        Ok(keys) => Component::ok(json!({"signing_kid": keys.signing_key().kid})),
+        Err(e) => Component::error("keys", e),
+    }
+}
+
+// GET /readyz
+// 요청을 처리할 준비가 되었는지 확인(DB 연결, 사용 중인 DB 종류의 마이그레이션 적용, JWT 키 로드)
+// 하나라도 실패하면 503과 함께 구성 요소별 결과 응답
+pub async fn readyz(repo: web::Data<dyn Repository>) -> HttpResponse {
+    let database = check_database(repo.backend().as_str(), repo.ping()).await;
+    let expected = repo.migrator().iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version);
+    let migrations = check_migrations(expected, repo.applied_migrations()).await;
+    readiness_response(vec![("database", database), ("migrations", migrations), ("keys", check_keys())])
+}
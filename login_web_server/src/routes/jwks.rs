@@ -0,0 +1,13 @@
+use actix_web::HttpResponse;
+use crate::auth::key_set;
+use crate::error::ApiError;
+
+// GET /.well-known/jwks.json
+// 토큰 검증용 공개 키 목록(JWK Set), 다른 서비스는 토큰 헤더의 kid로 키를 선택해 검증
+pub async fn jwks() -> Result<HttpResponse, ApiError> {
+    let keys = key_set().map_err(|_| ApiError::internal("keys_not_initialized", "Signing keys are not initialized."))?;
+    Ok(HttpResponse::Ok()
+        // 키 교체 시 새 키가 빨리 반영되도록 짧게 캐시
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(keys.jwks()))
+}
@@ -0,0 +1,21 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use crate::error::ApiError;
+use crate::metrics::{metrics, MetricsAccess};
+use crate::repository::Repository;
+
+// Prometheus 텍스트 형식(exposition format) 버전
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// GET /metrics
+// Prometheus 수집용 메트릭(metrics.bearer_token 설정 시 Authorization: Bearer <token> 필요)
+pub async fn metrics_handler(repo: web::Data<dyn Repository>, access: web::Data<MetricsAccess>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let authorization = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    if !access.is_allowed(authorization) {
+        return Err(ApiError::unauthorized("invalid_metrics_token", "Missing or invalid metrics token."));
+    }
+    let body = metrics().render(repo.get_ref()).await.map_err(|e| {
+        tracing::error!("Error rendering metrics: {:?}", e);
+        ApiError::internal("metrics_unavailable", "Error collecting metrics.")
+    })?;
+    Ok(HttpResponse::Ok().insert_header((header::CONTENT_TYPE, CONTENT_TYPE)).body(body))
+}
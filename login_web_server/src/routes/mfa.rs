@@ -0,0 +1,67 @@
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use crate::error::ApiError;
+use crate::{mfa, totp};
+use crate::auth::AuthContext;
+use crate::repository::Repository;
+
+// 인증 앱에 표시될 발급자 이름
+const TOTP_ISSUER: &str = "Toy_Project";
+
+#[derive(Serialize)]
+struct EnrollmentResponse {
+    secret: String, // 인증 앱에 직접 입력할 base32 비밀 키
+    otpauth_uri: String,    // otpauth://totp/... URI
+    qr_svg: Option<String>, // otpauth URI를 담은 QR 코드(SVG)
+}
+
+#[derive(Deserialize)]
+pub struct MfaCodeInfo {
+    code: String,
+}
+
+// GET /api/mfa
+// 2단계 인증 활성화 여부와 남은 복구 코드 수
+pub async fn mfa_status(repo: web::Data<dyn Repository>, auth: AuthContext) -> Result<HttpResponse, ApiError> {
+    let user_id = auth.user_id;
+    let enabled = mfa::is_enabled(repo.get_ref(), user_id).await?;
+    let remaining = mfa::remaining_recovery_codes(repo.get_ref(), user_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"enabled": enabled, "recovery_codes_remaining": remaining})))
+}
+
+// POST /api/mfa/totp/enroll
+// 새 TOTP 비밀 키 발급(확인 코드를 제출하기 전까지는 로그인에 적용되지 않음)
+pub async fn enroll_totp(repo: web::Data<dyn Repository>, auth: AuthContext) -> Result<HttpResponse, ApiError> {
+    let user_id = auth.user_id;
+    let secret = mfa::begin_enrollment(repo.get_ref(), user_id).await?
+        .ok_or_else(|| ApiError::conflict("mfa_already_enabled", "Two-factor authentication is already enabled."))?;
+    // 인증된 사용자 이름을 계정 이름으로 사용
+    let otpauth_uri = totp::otpauth_uri(TOTP_ISSUER, &auth.username, &secret);
+    Ok(HttpResponse::Ok().json(EnrollmentResponse {
+        qr_svg: totp::qr_svg(&otpauth_uri),
+        secret,
+        otpauth_uri,
+    }))
+}
+
+// POST /api/mfa/totp/confirm
+// 인증 앱의 코드로 등록을 확인하고 2단계 인증 활성화, 복구 코드는 이 응답에서 한 번만 전달
+pub async fn confirm_totp(repo: web::Data<dyn Repository>, auth: AuthContext, info: web::Json<MfaCodeInfo>) -> Result<HttpResponse, ApiError> {
+    let user_id = auth.user_id;
+    let recovery_codes = mfa::confirm_enrollment(repo.get_ref(), user_id, &info.code).await?
+        .ok_or_else(|| ApiError::bad_request("invalid_mfa_code", "Invalid code or no pending enrollment."))?;
+    tracing::info!("User {} enabled two-factor authentication", user_id);
+    Ok(HttpResponse::Ok().json(serde_json::json!({"enabled": true, "recovery_codes": recovery_codes})))
+}
+
+// POST /api/mfa/totp/disable
+// 현재 TOTP 코드(또는 복구 코드)를 확인한 뒤 2단계 인증 해제
+pub async fn disable_totp(repo: web::Data<dyn Repository>, auth: AuthContext, info: web::Json<MfaCodeInfo>) -> Result<HttpResponse, ApiError> {
+    let user_id = auth.user_id;
+    if !mfa::verify(repo.get_ref(), user_id, &info.code).await? {
+        return Err(ApiError::bad_request("invalid_mfa_code", "Invalid code or two-factor authentication is not enabled."));
+    }
+    mfa::disable(repo.get_ref(), user_id).await?;
+    tracing::info!("User {} disabled two-factor authentication", user_id);
+    Ok(HttpResponse::Ok().json(serde_json::json!({"enabled": false})))
+}
@@ -1,13 +1,32 @@
 // routes 하위 rs 파일들 import
 mod auth;
 mod todo;
+mod project;
+mod tag;
+mod event;
+mod session;
+mod admin;
+mod mfa;
+mod jwks;
+mod oauth;
+mod email;
+mod audit;
+mod metrics;
+mod health;
 
 use actix_web::web;
-use crate::middleware::auth_middleware::AuthMiddleware; // crate 루트 기준 AuthMiddleware 구조체 import
-use self::{auth::{register, login, logout, delete_user, generate_password, verify_token}, todo::list_todos};  // 현재 모듈 내에서 항목 import
+use crate::error;
+use crate::middleware::auth_middleware::{AuthMiddleware, OAuthMiddleware}; // crate 루트 기준 AuthMiddleware 구조체 import
+use crate::middleware::rbac_guard::RequirePermission;   // 관리 API 권한 가드
+use crate::rbac::{PERM_USERS_READ, PERM_USERS_WRITE, PERM_USERS_UNLOCK, PERM_SESSIONS_REVOKE, PERM_ROLES_WRITE, PERM_OAUTH_CLIENTS_WRITE, PERM_AUDIT_READ};
+use self::{auth::{register, login, login_mfa, refresh, logout, delete_user, change_password, request_password_reset, confirm_password_reset, generate_password, verify_token}, todo::{list_todos, create_todo, get_todo, update_todo, delete_todo}, project::{list_projects, create_project, get_project, update_project, delete_project}, tag::{list_tags, create_tag, update_tag, delete_tag}, event::list_events, session::{list_sessions, revoke_session, revoke_other_sessions}, admin::{list_users, disable_user, enable_user, delete_user as admin_delete_user, unlock_user, force_logout, assign_legacy_todos, assign_role, remove_role}, mfa::{mfa_status, enroll_totp, confirm_totp, disable_totp}, jwks::jwks, email::{verify_email, resend_verification}, oauth::{discovery, authorize_form, authorize, token, userinfo, register_client, list_clients, delete_client}, audit::{list_audit_events, export_audit_events}, metrics::metrics_handler, health::{healthz, readyz}};  // 현재 모듈 내에서 항목 import
 
 // main.rs에서 App::configure로 호출되어 라우트 설정 담당
 pub fn init(cfg: &mut web::ServiceConfig) { // web::ServiceConfig를 가변 참조로 받아 설정 변경
+    // 요청 본문(JSON), 경로 파라미터 및 쿼리 문자열 파싱 실패도 problem+json으로 응답
+    cfg.app_data(web::JsonConfig::default().error_handler(error::json_error_handler))
+        .app_data(web::PathConfig::default().error_handler(error::path_error_handler))
+        .app_data(web::QueryConfig::default().error_handler(error::query_error_handler));
     // cfg 서비스 등록
     cfg.service(
         // "/api/register" 경로 설정(post 요청을 register 함수가 처리)
@@ -15,10 +34,96 @@ pub fn init(cfg: &mut web::ServiceConfig) { // web::ServiceConfig를 가변 참
     ).service(
         // "/api/login" 경로 설정(post 요청을 login 함수가 처리)
         web::resource("/api/login").route(web::post().to(login))
+    ).service(
+        // "/api/login/mfa" 경로 설정(login이 반환한 challenge 토큰과 2단계 인증 코드로 토큰 발급)
+        web::resource("/api/login/mfa").route(web::post().to(login_mfa))
+    ).service(
+        // "/api/token/refresh" 경로 설정(access 토큰 만료 후 호출되므로 AuthMiddleware 미적용)
+        web::resource("/api/token/refresh").route(web::post().to(refresh))
     ).service(
         web::resource("api/auth/verify-token").route(web::post().to(verify_token))
     ).service(
-        web::resource("/api/todos").route(web::get().to(list_todos))
+        // 인증된 사용자 본인의 todo 목록 조회 및 생성
+        web::resource("/api/todos")
+            .route(web::get().to(list_todos))
+            .route(web::post().to(create_todo))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 인증된 사용자 본인의 단일 todo 조회, 수정, 삭제
+        web::resource("/api/todos/{id}")
+            .route(web::get().to(get_todo))
+            .route(web::patch().to(update_todo))
+            .route(web::delete().to(delete_todo))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 인증된 사용자 본인의 프로젝트 목록 조회 및 생성
+        web::resource("/api/projects")
+            .route(web::get().to(list_projects))
+            .route(web::post().to(create_project))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 프로젝트 조회, 이름 변경, 삭제(소속 todo는 함께 삭제하거나 다른 프로젝트로 이동)
+        web::resource("/api/projects/{id}")
+            .route(web::get().to(get_project))
+            .route(web::patch().to(update_project))
+            .route(web::delete().to(delete_project))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 인증된 사용자 본인의 태그 목록 조회 및 생성
+        web::resource("/api/tags")
+            .route(web::get().to(list_tags))
+            .route(web::post().to(create_tag))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 태그 이름/색상 변경 및 삭제
+        web::resource("/api/tags/{id}")
+            .route(web::patch().to(update_tag))
+            .route(web::delete().to(delete_tag))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 인증된 사용자 본인의 todo 이벤트(마감 알림) 조회
+        web::resource("/api/events")
+            .route(web::get().to(list_events))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 인증된 사용자의 세션(로그인한 기기) 목록 조회 및 현재 세션 외 모두 폐기
+        web::resource("/api/sessions")
+            .route(web::get().to(list_sessions))
+            .route(web::delete().to(revoke_other_sessions))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 세션 하나 폐기
+        web::resource("/api/sessions/{id}")
+            .route(web::delete().to(revoke_session))
+            .wrap(AuthMiddleware)
+    ).service(
+        // 회원가입 시 받은 메일의 토큰으로 이메일 인증
+        web::resource("/api/email/verify").route(web::post().to(verify_email))
+    ).service(
+        // 인증 메일 재발송(계정별 횟수 제한)
+        web::resource("/api/email/verify/resend").route(web::post().to(resend_verification))
+    ).service(
+        // 인증된 사용자의 비밀번호 변경(현재 비밀번호 확인)
+        web::resource("/api/password/change").route(web::post().to(change_password))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 비밀번호 재설정 토큰 메일 발송 요청(로그인할 수 없는 상태에서 호출되므로 AuthMiddleware 미적용)
+        web::resource("/api/password/reset/request").route(web::post().to(request_password_reset))
+    ).service(
+        // 재설정 토큰으로 새 비밀번호 설정
+        web::resource("/api/password/reset/confirm").route(web::post().to(confirm_password_reset))
+    ).service(
+        // 2단계 인증(TOTP) 상태 조회, 등록, 확인, 해제
+        web::resource("/api/mfa").route(web::get().to(mfa_status))
+        .wrap(AuthMiddleware)
+    ).service(
+        web::resource("/api/mfa/totp/enroll").route(web::post().to(enroll_totp))
+        .wrap(AuthMiddleware)
+    ).service(
+        web::resource("/api/mfa/totp/confirm").route(web::post().to(confirm_totp))
+        .wrap(AuthMiddleware)
+    ).service(
+        web::resource("/api/mfa/totp/disable").route(web::post().to(disable_totp))
         .wrap(AuthMiddleware)
     ).service(
         web::resource("/api/logout").route(web::post().to(logout))
@@ -27,7 +132,99 @@ pub fn init(cfg: &mut web::ServiceConfig) { // web::ServiceConfig를 가변 참
         // 인증된 본인을 삭제하는 기능이므로 "/api/user" 경로에 delete 요청으로 처리 
         web::resource("/user").route(web::delete().to(delete_user))
         .wrap(AuthMiddleware)
+    ).service(
+        // 관리 API: 각 리소스에 필요한 권한을 RequirePermission으로 확인
+        // (wrap 순서상 AuthMiddleware가 먼저 실행되어 클레임을 저장한 뒤 권한 가드 실행)
+        web::resource("/api/admin/users").route(web::get().to(list_users))
+        .wrap(RequirePermission(PERM_USERS_READ))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 사용자 삭제
+        web::resource("/api/admin/users/{username}").route(web::delete().to(admin_delete_user))
+        .wrap(RequirePermission(PERM_USERS_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 계정 비활성화(로그인 차단 및 모든 세션 폐기)
+        web::resource("/api/admin/users/{username}/disable").route(web::post().to(disable_user))
+        .wrap(RequirePermission(PERM_USERS_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        web::resource("/api/admin/users/{username}/enable").route(web::post().to(enable_user))
+        .wrap(RequirePermission(PERM_USERS_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 관리자에 의한 로그인 잠금 해제
+        web::resource("/api/admin/users/{username}/unlock").route(web::post().to(unlock_user))
+        .wrap(RequirePermission(PERM_USERS_UNLOCK))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 강제 로그아웃(모든 세션 폐기)
+        web::resource("/api/admin/users/{username}/logout").route(web::post().to(force_logout))
+        .wrap(RequirePermission(PERM_SESSIONS_REVOKE))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 소유자 없는 기존 todo를 사용자에게 이관
+        web::resource("/api/admin/users/{username}/legacy-todos").route(web::post().to(assign_legacy_todos))
+        .wrap(RequirePermission(PERM_USERS_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 역할 부여 및 회수
+        web::resource("/api/admin/users/{username}/roles/{role}")
+        .route(web::put().to(assign_role))
+        .route(web::delete().to(remove_role))
+        .wrap(RequirePermission(PERM_ROLES_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 보안 감사 로그 조회(필터 및 페이지 지원)
+        web::resource("/api/admin/audit").route(web::get().to(list_audit_events))
+        .wrap(RequirePermission(PERM_AUDIT_READ))
+        .wrap(AuthMiddleware)
+    ).service(
+        // 감사 로그 JSON Lines 내보내기
+        web::resource("/api/admin/audit/export").route(web::get().to(export_audit_events))
+        .wrap(RequirePermission(PERM_AUDIT_READ))
+        .wrap(AuthMiddleware)
     ).service(
         web::resource("/api/generate-password").route(web::get().to(generate_password))
+    ).service(
+        // 토큰 검증용 공개 키 목록(JWKS)
+        web::resource("/.well-known/jwks.json").route(web::get().to(jwks))
+    ).service(
+        // OpenID Connect discovery 문서
+        web::resource("/.well-known/openid-configuration").route(web::get().to(discovery))
+    ).service(
+        // OAuth2 인가 요청(GET: 로그인 폼, POST: 로그인 후 인가 코드 발급, 에러는 폼에 표시)
+        web::resource("/oauth/authorize")
+        .route(web::get().to(authorize_form))
+        .route(web::post().to(authorize))
+    ).service(
+        // 인가 코드/refresh 토큰을 토큰으로 교환(클라이언트 인증, 에러는 RFC 6749 형식)
+        web::resource("/oauth/token").route(web::post().to(token))
+        .app_data(web::FormConfig::default().error_handler(oauth::form_error_handler))
+    ).service(
+        // OAuth 클라이언트에 발급한 scope 토큰으로 접근
+        web::resource("/oauth/userinfo")
+        .route(web::get().to(userinfo))
+        .route(web::post().to(userinfo))
+        .wrap(OAuthMiddleware)
+    ).service(
+        // OAuth 클라이언트 등록 관리
+        web::resource("/api/admin/oauth/clients")
+        .route(web::get().to(list_clients))
+        .route(web::post().to(register_client))
+        .wrap(RequirePermission(PERM_OAUTH_CLIENTS_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        web::resource("/api/admin/oauth/clients/{client_id}").route(web::delete().to(delete_client))
+        .wrap(RequirePermission(PERM_OAUTH_CLIENTS_WRITE))
+        .wrap(AuthMiddleware)
+    ).service(
+        // Prometheus 메트릭(사용자 토큰 대신 metrics.bearer_token으로 보호)
+        web::resource("/metrics").route(web::get().to(metrics_handler))
+    ).service(
+        // 생존 확인(liveness) 및 준비 상태 확인(readiness, DB 연결, 마이그레이션, JWT 키)
+        web::resource("/healthz").route(web::get().to(healthz))
+    ).service(
+        web::resource("/readyz").route(web::get().to(readyz))
     );
 }
\ No newline at end of file
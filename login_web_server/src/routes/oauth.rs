@@ -0,0 +1,508 @@
+use actix_web::{http::{header, StatusCode}, web, HttpRequest, HttpResponse, ResponseError};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::Url;
+use crate::auth::{create_id_token, create_oauth_access_token, key_set, AuthContext};
+use crate::audit;
+use crate::config;
+use crate::error::ApiError;
+use crate::oauth::{self, AuthorizationRequest, Client, Grant, SUPPORTED_SCOPES};
+use crate::refresh_token::{self, RefreshError};
+use crate::session::{self, ClientInfo};
+use super::auth::{check_authorize_login, check_password, is_active};
+use crate::repository::Repository;
+
+// OAuth2 / OpenID Connect 제공자 엔드포인트
+// 다른 내부 앱은 authorization code + PKCE(S256) 흐름으로 이 서버의 계정을 사용해 로그인
+//   1. 클라이언트가 사용자 브라우저를 GET /oauth/authorize로 이동 -> 로그인 폼 제출(POST) 후 redirect_uri로 code 전달
+//   2. 클라이언트가 POST /oauth/token으로 code와 code_verifier를 토큰(access, refresh, id_token)으로 교환
+//   3. 클라이언트가 access 토큰으로 GET /oauth/userinfo 호출
+// 클라이언트에 발급한 access 토큰에는 역할 및 권한 대신 승인된 scope만 담기므로 이 서버의 API에는 사용할 수 없고,
+// refresh 토큰은 발급받은 클라이언트만 교체 가능
+
+// 토큰 엔드포인트 에러(RFC 6749 5.2 형식: {"error", "error_description"})
+// 표준 OAuth 클라이언트 라이브러리가 해석할 수 있도록 problem+json 대신 사용
+#[derive(Debug)]
+pub struct OAuthError {
+    status: StatusCode,
+    error: &'static str,
+    description: String,
+}
+
+impl OAuthError {
+    fn new(status: StatusCode, error: &'static str, description: impl Into<String>) -> Self {
+        OAuthError { status, error, description: description.into() }
+    }
+
+    fn invalid_request(description: impl Into<String>) -> Self {
+        OAuthError::new(StatusCode::BAD_REQUEST, "invalid_request", description)
+    }
+
+    fn invalid_client() -> Self {
+        OAuthError::new(StatusCode::UNAUTHORIZED, "invalid_client", "Client authentication failed.")
+    }
+
+    fn invalid_grant(description: impl Into<String>) -> Self {
+        OAuthError::new(StatusCode::BAD_REQUEST, "invalid_grant", description)
+    }
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl ResponseError for OAuthError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut builder = HttpResponse::build(self.status);
+        builder.insert_header((header::CACHE_CONTROL, "no-store"));
+        if self.status==StatusCode::UNAUTHORIZED {
+            builder.insert_header((header::WWW_AUTHENTICATE, "Basic realm=\"oauth\""));
+        }
+        builder.json(serde_json::json!({"error": self.error, "error_description": self.description}))
+    }
+}
+
+impl From<sqlx::Error> for OAuthError {
+    fn from(e: sqlx::Error) -> Self {
+        tracing::error!("Database error: {:?}", e);
+        OAuthError::new(StatusCode::INTERNAL_SERVER_ERROR, "server_error", "A database error occurred.")
+    }
+}
+
+// 토큰 요청 본문(application/x-www-form-urlencoded) 파싱 실패
+pub fn form_error_handler(err: actix_web::error::UrlencodedError, _req: &HttpRequest) -> actix_web::Error {
+    OAuthError::invalid_request(format!("Invalid token request: {}", err)).into()
+}
+
+// 요청의 scheme/host로 이 서버의 기준 URL 생성(discovery 문서의 엔드포인트 주소)
+fn base_url(req: &HttpRequest) -> String {
+    let info = req.connection_info();
+    format!("{}://{}", info.scheme(), info.host())
+}
+
+// GET /.well-known/openid-configuration
+// OpenID Connect discovery 문서
+// issuer는 토큰의 iss 클레임과 같아야 하므로 OIDC 클라이언트를 사용할 때는 JWT_ISSUER를 공개 URL로 설정
+pub async fn discovery(req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let base = base_url(&req);
+    let algorithm = key_set().map_err(|_| ApiError::internal("keys_not_initialized", "Signing keys are not initialized."))?
+        .signing_key().algorithm;
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "public, max-age=300"))
+        .json(serde_json::json!({
+            "issuer": config::get().tokens.issuer,
+            "authorization_endpoint": format!("{}/oauth/authorize", base),
+            "token_endpoint": format!("{}/oauth/token", base),
+            "userinfo_endpoint": format!("{}/oauth/userinfo", base),
+            "jwks_uri": format!("{}/.well-known/jwks.json", base),
+            "response_types_supported": ["code"],
+            "grant_types_supported": ["authorization_code", "refresh_token"],
+            "subject_types_supported": ["public"],
+            "id_token_signing_alg_values_supported": [format!("{:?}", algorithm)],
+            "scopes_supported": SUPPORTED_SCOPES,
+            "token_endpoint_auth_methods_supported": ["none", "client_secret_basic", "client_secret_post"],
+            "code_challenge_methods_supported": ["S256"],
+            "claims_supported": ["iss", "sub", "aud", "iat", "exp", "nonce", "preferred_username"],
+        })))
+}
+
+#[derive(Deserialize)]
+pub struct AuthorizeParams {
+    response_type: Option<String>,
+    client_id: Option<String>,
+    redirect_uri: Option<String>,
+    scope: Option<String>,
+    state: Option<String>,
+    code_challenge: Option<String>,
+    code_challenge_method: Option<String>,
+    nonce: Option<String>,
+}
+
+impl AuthorizeParams {
+    // 로그인 폼의 hidden 필드로 다시 전달할 값(요청에 없던 값은 제외)
+    fn fields(&self) -> [(&'static str, Option<&str>); 8] {
+        [
+            ("response_type", self.response_type.as_deref()),
+            ("client_id", self.client_id.as_deref()),
+            ("redirect_uri", self.redirect_uri.as_deref()),
+            ("scope", self.scope.as_deref()),
+            ("state", self.state.as_deref()),
+            ("code_challenge", self.code_challenge.as_deref()),
+            ("code_challenge_method", self.code_challenge_method.as_deref()),
+            ("nonce", self.nonce.as_deref()),
+        ]
+    }
+}
+
+// redirect_uri로 결과 전달(302), state는 그대로 돌려줌
+fn redirect_with(redirect_uri: &str, params: &[(&str, &str)], state: Option<&str>) -> HttpResponse {
+    // redirect_uri는 등록 시 URL 형식을 검증했으므로 파싱 실패는 발생하지 않음
+    let mut url = Url::parse(redirect_uri).expect("registered redirect_uri is a valid URL");
+    {
+        let mut query = url.query_pairs_mut();
+        for (name, value) in params {
+            query.append_pair(name, value);
+        }
+        if let Some(state) = state {
+            query.append_pair("state", state);
+        }
+    }
+    HttpResponse::Found().insert_header((header::LOCATION, url.to_string())).finish()
+}
+
+// 등록된 redirect_uri 중 요청과 일치하는 값 선택
+// 요청에 redirect_uri가 없으면 등록된 값이 하나일 때만 그 값을 사용
+fn resolve_redirect_uri(client: &Client, requested: Option<&str>) -> Option<String> {
+    match requested {
+        Some(uri) if client.allows_redirect_uri(uri) => Some(uri.to_string()),
+        Some(_) => None,
+        None if client.redirect_uris.len()==1 => client.redirect_uris.first().cloned(),
+        None => None,
+    }
+}
+
+// 검증을 통과한 인가 요청
+struct ValidRequest<'a> {
+    client: Client,
+    redirect_uri: String,
+    scope: String,
+    code_challenge: &'a str,
+}
+
+// 인가 요청 검증(로그인 폼 표시 전과 폼 제출 시 모두 확인, hidden 필드는 변조될 수 있음)
+// 실패하면 그대로 돌려줄 응답
+async fn validate_request<'a>(repo: &dyn Repository, params: &'a AuthorizeParams) -> Result<ValidRequest<'a>, HttpResponse> {
+    // client_id 또는 redirect_uri가 잘못된 경우에는 공격자의 주소로 이동하지 않도록 redirect 대신 에러 응답
+    let client = match params.client_id.as_deref() {
+        Some(client_id) => oauth::find_client(repo, client_id).await.map_err(|e| ApiError::from(e).error_response())?,
+        None => None,
+    }.ok_or_else(|| ApiError::bad_request("invalid_client", "Unknown client_id.").error_response())?;
+    let redirect_uri = resolve_redirect_uri(&client, params.redirect_uri.as_deref())
+        .ok_or_else(|| ApiError::bad_request("invalid_redirect_uri", "redirect_uri is not registered for this client.").error_response())?;
+
+    // 이후 에러는 RFC 6749 4.1.2.1에 따라 redirect_uri로 전달
+    let state = params.state.as_deref();
+    let error = |error: &str, description: &str| Err(redirect_with(&redirect_uri, &[("error", error), ("error_description", description)], state));
+    if params.response_type.as_deref()!=Some("code") {
+        return error("unsupported_response_type", "Only response_type=code is supported.");
+    }
+    // PKCE는 모든 클라이언트에 필수이며 S256만 허용(plain은 code_challenge가 노출되면 무의미)
+    let code_challenge = match params.code_challenge.as_deref() {
+        Some(challenge) if !challenge.is_empty() => challenge,
+        _ => return error("invalid_request", "code_challenge is required."),
+    };
+    if params.code_challenge_method.as_deref()!=Some("S256") {
+        return error("invalid_request", "code_challenge_method must be S256.");
+    }
+    let scope = match oauth::normalize_scope(params.scope.as_deref()) {
+        Some(scope) => scope,
+        None => return error("invalid_scope", "Unsupported scope."),
+    };
+    Ok(ValidRequest { client, redirect_uri, scope, code_challenge })
+}
+
+// HTML 특수 문자 치환(폼에 다시 출력하는 요청 값 및 에러 메시지)
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 인가 화면(로그인 폼)
+// 인가 요청 값은 hidden 필드로 다시 제출, 다른 사이트의 frame 안에 표시 금지(클릭재킹 방지)
+fn login_page(status: StatusCode, client: &Client, params: &AuthorizeParams, username: &str, error: Option<&str>) -> HttpResponse {
+    let hidden: String = params.fields().iter()
+        .filter_map(|(name, value)| value.map(|value| format!("<input type=\"hidden\" name=\"{}\" value=\"{}\">", name, escape_html(value))))
+        .collect();
+    let error = error.map(|message| format!("<p role=\"alert\">{}</p>", escape_html(message))).unwrap_or_default();
+    let body = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Sign in</title></head><body>\
+         <h1>Sign in to {}</h1>{}\
+         <form method=\"post\" action=\"/oauth/authorize\">{}\
+         <label>Username <input name=\"username\" value=\"{}\" autocomplete=\"username\" required></label>\
+         <label>Password <input type=\"password\" name=\"password\" autocomplete=\"current-password\" required></label>\
+         <label>Two-factor code (if enabled) <input name=\"mfa_code\" autocomplete=\"one-time-code\"></label>\
+         <button type=\"submit\">Sign in</button></form></body></html>",
+        escape_html(&client.name), error, hidden, escape_html(username));
+    HttpResponse::build(status)
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((header::X_FRAME_OPTIONS, "DENY"))
+        .insert_header((header::CONTENT_SECURITY_POLICY, "default-src 'none'; frame-ancestors 'none'"))
+        .content_type("text/html; charset=utf-8")
+        .body(body)
+}
+
+// GET /oauth/authorize
+// 인가 요청을 검증한 뒤 사용자 로그인 폼 표시(브라우저 redirect로 열리므로 Bearer 토큰 없이 접근)
+pub async fn authorize_form(repo: web::Data<dyn Repository>, params: web::Query<AuthorizeParams>) -> HttpResponse {
+    match validate_request(repo.get_ref(), &params).await {
+        Ok(request) => login_page(StatusCode::OK, &request.client, &params, "", None),
+        Err(response) => response,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AuthorizeForm {
+    #[serde(flatten)]
+    params: AuthorizeParams,
+    username: String,
+    password: String,
+    mfa_code: Option<String>,   // 2단계 인증이 활성화된 계정의 TOTP 코드 또는 복구 코드
+}
+
+// POST /oauth/authorize
+// 로그인 폼의 사용자 이름, 비밀번호(및 2단계 인증 코드)를 확인하고 인가 코드를 발급하여 클라이언트의 redirect_uri로 이동
+// 로그인 실패는 에러 메시지와 함께 폼을 다시 표시(잠금, 감사 로그는 /api/login과 동일)
+// 클라이언트는 관리자가 등록한 내부 앱이므로 별도 동의 화면 없이 승인
+pub async fn authorize(repo: web::Data<dyn Repository>, req: HttpRequest, form: web::Form<AuthorizeForm>) -> Result<HttpResponse, ApiError> {
+    let request = match validate_request(repo.get_ref(), &form.params).await {
+        Ok(request) => request,
+        Err(response) => return Ok(response),
+    };
+    // 서버 오류는 폼 대신 에러 응답
+    let retry = |e: ApiError| match e {
+        ApiError::Database(_) | ApiError::Internal { .. } => Err(e),
+        e => Ok(login_page(e.status_code(), &request.client, &form.params, &form.username, Some(&e.to_string()))),
+    };
+
+    let client = ClientInfo::from_request(&req);
+    let user_id = match check_password(repo.get_ref(), &form.username, &form.password, &client, audit::OAUTH_AUTHORIZE).await {
+        Ok(user_id) => user_id,
+        Err(e) => return retry(e),
+    };
+    let result = check_authorize_login(repo.get_ref(), user_id, &form.username, form.mfa_code.as_deref(), &client).await;
+    audit::record(repo.get_ref(), &client, audit::Entry::from_result(audit::OAUTH_AUTHORIZE, &result).actor(Some(user_id), &form.username)).await;
+    if let Err(e) = result {
+        return retry(e);
+    }
+
+    let code = oauth::create_code(repo.get_ref(), &AuthorizationRequest {
+        client: &request.client,
+        user_id,
+        redirect_uri: &request.redirect_uri,
+        scope: &request.scope,
+        code_challenge: request.code_challenge,
+        nonce: form.params.nonce.as_deref(),
+    }).await?;
+    tracing::info!("Issued authorization code for user {} to client {}", form.username, request.client.name);
+    Ok(redirect_with(&request.redirect_uri, &[("code", &code)], form.params.state.as_deref()))
+}
+
+#[derive(Deserialize)]
+pub struct TokenRequest {
+    grant_type: String,
+    code: Option<String>,
+    redirect_uri: Option<String>,
+    code_verifier: Option<String>,
+    refresh_token: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,  // client_secret_post 방식
+}
+
+#[derive(Serialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: &'static str,
+    expires_in: u64,
+    refresh_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id_token: Option<String>,
+}
+
+// Authorization: Basic base64(client_id:client_secret) 헤더 파싱(client_secret_basic 방식)
+fn basic_credentials(req: &HttpRequest) -> Option<(String, String)> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let decoded = STANDARD.decode(value.strip_prefix("Basic ")?).ok()?;
+    let decoded = String::from_utf8(decoded).ok()?;
+    let (client_id, secret) = decoded.split_once(':')?;
+    Some((client_id.to_string(), secret.to_string()))
+}
+
+// 토큰 요청의 클라이언트 인증
+async fn authenticate_client(repo: &dyn Repository, req: &HttpRequest, form: &TokenRequest) -> Result<Client, OAuthError> {
+    let (client_id, secret) = match basic_credentials(req) {
+        Some((client_id, secret)) => (client_id, Some(secret)),
+        None => match &form.client_id {
+            Some(client_id) => (client_id.clone(), form.client_secret.clone()),
+            None => return Err(OAuthError::invalid_request("client_id is required.")),
+        },
+    };
+    match oauth::find_client(repo, &client_id).await? {
+        Some(client) if client.authenticate(secret.as_deref()) => Ok(client),
+        _ => Err(OAuthError::invalid_client()),
+    }
+}
+
+// 토큰 응답(캐시 금지)
+fn token_response(body: TokenResponse) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .insert_header((header::PRAGMA, "no-cache"))
+        .json(body)
+}
+
+fn token_creation_failed() -> OAuthError {
+    OAuthError::new(StatusCode::INTERNAL_SERVER_ERROR, "server_error", "Error creating token.")
+}
+
+// POST /oauth/token
+// grant_type=authorization_code: 인가 코드와 PKCE code_verifier로 토큰 발급
+// grant_type=refresh_token: refresh 토큰 교체 후 새 access 토큰 발급
+pub async fn token(repo: web::Data<dyn Repository>, req: HttpRequest, form: web::Form<TokenRequest>) -> Result<HttpResponse, OAuthError> {
+    let client = authenticate_client(repo.get_ref(), &req, &form).await?;
+    match form.grant_type.as_str() {
+        "authorization_code" => exchange_code(repo.get_ref(), &req, &client, &form).await,
+        "refresh_token" => refresh(repo.get_ref(), &client, &form).await,
+        _ => Err(OAuthError::new(StatusCode::BAD_REQUEST, "unsupported_grant_type", "Unsupported grant_type.")),
+    }
+}
+
+async fn exchange_code(repo: &dyn Repository, req: &HttpRequest, client: &Client, form: &TokenRequest) -> Result<HttpResponse, OAuthError> {
+    let code = form.code.as_deref().ok_or_else(|| OAuthError::invalid_request("code is required."))?;
+    let code_verifier = form.code_verifier.as_deref().ok_or_else(|| OAuthError::invalid_request("code_verifier is required."))?;
+    // 코드는 검증 실패 시에도 소모되므로 같은 코드로 반복 시도할 수 없음
+    let authorization = oauth::consume_code(repo, code).await?
+        .ok_or_else(|| OAuthError::invalid_grant("Invalid or expired authorization code."))?;
+    if authorization.client_id!=client.id || form.redirect_uri.as_deref()!=Some(authorization.redirect_uri.as_str()) {
+        return Err(OAuthError::invalid_grant("Authorization code was issued to another client or redirect_uri."));
+    }
+    if !oauth::verify_pkce(code_verifier, &authorization.code_challenge) {
+        return Err(OAuthError::invalid_grant("PKCE verification failed."));
+    }
+    // 코드 발급 이후 비활성화된 계정
+    if !is_active(repo, authorization.user_id).await? {
+        return Err(OAuthError::invalid_grant("This account has been disabled."));
+    }
+
+    // 클라이언트 앱 로그인도 하나의 세션으로 관리(세션 목록에 앱 이름으로 표시, 폐기 가능)
+    // 세션에 클라이언트와 승인된 scope를 기록(refresh 토큰 교체 시 확인)
+    let family_id = refresh_token::new_family_id();
+    let grant = Grant { client_id: client.id, scope: authorization.scope.clone() };
+    session::create(repo, &family_id, authorization.user_id, Some(&client.name), &ClientInfo::from_request(req), Some(&grant)).await?;
+    let access_token = create_oauth_access_token(authorization.user_id, &authorization.username, &family_id, &authorization.scope)
+        .map_err(|_| token_creation_failed())?;
+    let refresh_token = refresh_token::issue(repo, authorization.user_id, &family_id).await?;
+    let scopes: Vec<&str> = authorization.scope.split(' ').collect();
+    let id_token = if scopes.contains(&"openid") {
+        let username = scopes.contains(&"profile").then_some(authorization.username.as_str());
+        Some(create_id_token(authorization.user_id, username, &client.client_id, authorization.nonce.as_deref())
+            .map_err(|_| token_creation_failed())?)
+    } else {
+        None
+    };
+    tracing::info!("Client {} exchanged authorization code for user {}", client.name, authorization.username);
+    Ok(token_response(TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: config::get().tokens.access_ttl_secs,
+        refresh_token,
+        scope: Some(authorization.scope),
+        id_token,
+    }))
+}
+
+async fn refresh(repo: &dyn Repository, client: &Client, form: &TokenRequest) -> Result<HttpResponse, OAuthError> {
+    let token = form.refresh_token.as_deref().ok_or_else(|| OAuthError::invalid_request("refresh_token is required."))?;
+    // 다른 클라이언트 또는 /api/login으로 발급된 refresh 토큰은 거부
+    let rotated = match refresh_token::rotate(repo, token, Some(client.id)).await {
+        Ok(rotated) => rotated,
+        Err(RefreshError::Database(e)) => return Err(e.into()),
+        // 재사용 감지 시 해당 family는 이미 전체 폐기됨
+        Err(_) => return Err(OAuthError::invalid_grant("Invalid or expired refresh token.")),
+    };
+    if !is_active(repo, rotated.user_id).await? {
+        return Err(OAuthError::invalid_grant("This account has been disabled."));
+    }
+    // rotate에서 클라이언트를 확인했으므로 grant가 항상 있음
+    let scope = rotated.grant.map(|grant| grant.scope).unwrap_or_default();
+    let access_token = create_oauth_access_token(rotated.user_id, &rotated.username, &rotated.family_id, &scope)
+        .map_err(|_| token_creation_failed())?;
+    Ok(token_response(TokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in: config::get().tokens.access_ttl_secs,
+        refresh_token: rotated.refresh_token,
+        scope: Some(scope),
+        id_token: None,
+    }))
+}
+
+// GET /oauth/userinfo
+// access 토큰 소유자 정보(sub는 id_token의 sub와 같은 사용자 id)
+// preferred_username은 profile scope가 승인된 경우에만 제공
+pub async fn userinfo(auth: AuthContext) -> HttpResponse {
+    let mut body = serde_json::json!({"sub": auth.user_id.to_string()});
+    if auth.has_scope("profile") {
+        body["preferred_username"] = serde_json::Value::String(auth.username);
+    }
+    HttpResponse::Ok().json(body)
+}
+
+#[derive(Deserialize)]
+pub struct RegisterClientInfo {
+    name: String,
+    redirect_uris: Vec<String>,
+    #[serde(default)]
+    confidential: bool, // true면 client_secret 발급(서버 측 앱)
+}
+
+// redirect_uri는 fragment가 없는 절대 http(s) URL만 허용
+fn valid_redirect_uri(uri: &str) -> bool {
+    match Url::parse(uri) {
+        Ok(url) => matches!(url.scheme(), "http" | "https") && url.fragment().is_none() && url.has_host(),
+        Err(_) => false,
+    }
+}
+
+// POST /api/admin/oauth/clients
+// 클라이언트 등록, client_secret 원문은 이 응답에서 한 번만 전달
+pub async fn register_client(repo: web::Data<dyn Repository>, auth: AuthContext, info: web::Json<RegisterClientInfo>) -> Result<HttpResponse, ApiError> {
+    let name = info.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::bad_request("invalid_client_name", "Client name must not be empty."));
+    }
+    if info.redirect_uris.is_empty() || !info.redirect_uris.iter().all(|uri| valid_redirect_uri(uri)) {
+        return Err(ApiError::bad_request("invalid_redirect_uri", "At least one absolute http(s) redirect_uri without a fragment is required."));
+    }
+    let registered = oauth::register_client(repo.get_ref(), name, &info.redirect_uris, info.confidential).await?;
+    tracing::info!("Admin {} registered OAuth client {} ({})", auth.username, registered.client.name, registered.client.client_id);
+    let mut body = serde_json::to_value(&registered.client).unwrap_or_default();
+    if let Some(secret) = registered.client_secret {
+        body["client_secret"] = serde_json::Value::String(secret);
+    }
+    Ok(HttpResponse::Created().json(body))
+}
+
+// GET /api/admin/oauth/clients
+pub async fn list_clients(repo: web::Data<dyn Repository>) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(oauth::list_clients(repo.get_ref()).await?))
+}
+
+// DELETE /api/admin/oauth/clients/{client_id}
+// 클라이언트 삭제(클라이언트의 세션도 함께 삭제되므로 이미 발급된 access 토큰과 refresh 토큰은 즉시 사용 불가)
+pub async fn delete_client(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>) -> Result<HttpResponse, ApiError> {
+    let client_id = path.into_inner();
+    if !oauth::delete_client(repo.get_ref(), &client_id).await? {
+        return Err(ApiError::not_found("client_not_found", "OAuth client not found."));
+    }
+    tracing::info!("Admin {} deleted OAuth client {}", auth.username, client_id);
+    Ok(HttpResponse::NoContent().finish())
+}
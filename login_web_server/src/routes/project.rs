@@ -0,0 +1,103 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use crate::auth::AuthContext;
+use crate::error::ApiError;
+use crate::repository::{NameError, Project, ProjectTodos, Repository};
+
+// 프로젝트 이름 최대 길이(문자 수)
+const MAX_NAME_LENGTH: usize = 100;
+
+#[derive(Deserialize)]
+pub struct ProjectInfo {
+    name: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteProjectQuery {
+    todos: Option<String>,  // delete(함께 삭제) 또는 reassign(다른 프로젝트로 이동)
+    to: Option<i64>,        // reassign 대상 프로젝트(없으면 프로젝트에서 제외)
+}
+
+// 이름 검증(공백 제거 후 1~100자)
+fn validate_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count()>MAX_NAME_LENGTH {
+        return Err(ApiError::bad_request("invalid_name", format!("Project name must be 1 to {} characters.", MAX_NAME_LENGTH)));
+    }
+    Ok(name.to_string())
+}
+
+fn project_not_found() -> ApiError {
+    ApiError::not_found("project_not_found", "Project not found.")
+}
+
+fn name_taken(e: NameError) -> ApiError {
+    match e {
+        NameError::Taken => ApiError::conflict("project_exists", "A project with this name already exists."),
+        NameError::Database(e) => e.into(),
+    }
+}
+
+async fn find_project(repo: &dyn Repository, owner_id: i64, id: i64) -> Result<Project, ApiError> {
+    repo.find_project(owner_id, id).await?.ok_or_else(project_not_found)
+}
+
+// GET /api/projects
+// 인증된 사용자의 프로젝트 목록(이름순, todo 수 포함)
+pub async fn list_projects(repo: web::Data<dyn Repository>, auth: AuthContext) -> Result<HttpResponse, ApiError> {
+    let projects = repo.list_projects(auth.user_id).await?;
+    Ok(HttpResponse::Ok().json(projects))
+}
+
+// POST /api/projects
+pub async fn create_project(repo: web::Data<dyn Repository>, auth: AuthContext, info: web::Json<ProjectInfo>) -> Result<HttpResponse, ApiError> {
+    let name = validate_name(&info.name)?;
+    let project = repo.create_project(auth.user_id, &name).await.map_err(name_taken)?;
+    Ok(HttpResponse::Created().json(project))
+}
+
+// GET /api/projects/{id}
+pub async fn get_project(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>) -> Result<HttpResponse, ApiError> {
+    let project = find_project(repo.get_ref(), auth.user_id, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(project))
+}
+
+// PATCH /api/projects/{id}
+// 프로젝트 이름 변경
+pub async fn update_project(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>, info: web::Json<ProjectInfo>) -> Result<HttpResponse, ApiError> {
+    let name = validate_name(&info.name)?;
+    match repo.rename_project(auth.user_id, path.into_inner(), &name).await.map_err(name_taken)? {
+        Some(project) => Ok(HttpResponse::Ok().json(project)),
+        None => Err(project_not_found()),
+    }
+}
+
+// DELETE /api/projects/{id}?todos=delete|reassign&to=
+// todo가 있는 프로젝트는 todos로 처리 방법을 지정해야 삭제 가능
+//   todos=delete: 소속 todo(하위 todo 포함)도 함께 삭제
+//   todos=reassign&to={id}: 소속 todo를 다른 프로젝트로 이동(to가 없으면 프로젝트에서 제외)
+pub async fn delete_project(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>, query: web::Query<DeleteProjectQuery>) -> Result<HttpResponse, ApiError> {
+    let owner_id = auth.user_id;
+    let id = path.into_inner();
+    let project = find_project(repo.get_ref(), owner_id, id).await?;
+
+    let todos = match query.todos.as_deref() {
+        None if project.todo_count>0 => {
+            return Err(ApiError::conflict("project_not_empty", "Project has todos. Specify todos=delete or todos=reassign."));
+        }
+        None | Some("delete") => ProjectTodos::Delete,
+        Some("reassign") => {
+            if let Some(to) = query.to
+                && (to==id || repo.find_project(owner_id, to).await?.is_none()) {
+                return Err(ApiError::bad_request("invalid_project", "Target project not found."));
+            }
+            ProjectTodos::Reassign(query.to)
+        }
+        Some(_) => return Err(ApiError::bad_request("invalid_query", "todos must be delete or reassign.")),
+    };
+
+    if !repo.delete_project(owner_id, id, todos).await? {
+        return Err(project_not_found());
+    }
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,31 @@
+use actix_web::{web, HttpResponse};
+use crate::error::ApiError;
+use crate::session;
+use crate::auth::AuthContext;
+use crate::repository::Repository;
+
+// GET /api/sessions
+// 인증된 사용자의 활성 세션(로그인한 기기) 목록
+pub async fn list_sessions(repo: web::Data<dyn Repository>, auth: AuthContext) -> Result<HttpResponse, ApiError> {
+    let sessions = session::list(repo.get_ref(), &auth.username, &auth.session_id).await?;
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+// DELETE /api/sessions/{id}
+// 세션 하나 폐기(해당 세션의 access/refresh 토큰 모두 사용 불가)
+pub async fn revoke_session(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<String>) -> Result<HttpResponse, ApiError> {
+    let id = path.into_inner();
+    if session::revoke(repo.get_ref(), &auth.username, &id).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        // 다른 사용자의 세션이거나 이미 폐기된 세션
+        Err(ApiError::not_found("session_not_found", "Session not found."))
+    }
+}
+
+// DELETE /api/sessions
+// 현재 세션을 제외한 모든 세션 폐기
+pub async fn revoke_other_sessions(repo: web::Data<dyn Repository>, auth: AuthContext) -> Result<HttpResponse, ApiError> {
+    let revoked = session::revoke_others(repo.get_ref(), &auth.username, &auth.session_id).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({"revoked": revoked})))
+}
@@ -0,0 +1,83 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use crate::auth::AuthContext;
+use crate::error::ApiError;
+use crate::repository::{NameError, Repository};
+use super::todo::validate_tag;
+
+// 색상을 지정하지 않은 태그의 기본 색상(todo에 처음 붙이면서 만들어진 태그와 같음)
+const DEFAULT_COLOR: &str = "#9e9e9e";
+
+#[derive(Deserialize)]
+pub struct CreateTag {
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTag {
+    name: Option<String>,
+    color: Option<String>,
+}
+
+// 색상 검증(#rrggbb, 소문자로 저장)
+fn validate_color(color: &str) -> Result<String, ApiError> {
+    let valid = color.len()==7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ApiError::bad_request("invalid_color", "Color must be in #rrggbb format."));
+    }
+    Ok(color.to_ascii_lowercase())
+}
+
+fn tag_not_found() -> ApiError {
+    ApiError::not_found("tag_not_found", "Tag not found.")
+}
+
+fn name_taken(e: NameError) -> ApiError {
+    match e {
+        NameError::Taken => ApiError::conflict("tag_exists", "A tag with this name already exists."),
+        NameError::Database(e) => e.into(),
+    }
+}
+
+// GET /api/tags
+// 인증된 사용자의 태그 목록(이름순, 태그가 붙은 todo 수 포함)
+pub async fn list_tags(repo: web::Data<dyn Repository>, auth: AuthContext) -> Result<HttpResponse, ApiError> {
+    let tags = repo.list_tags(auth.user_id).await?;
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+// POST /api/tags
+pub async fn create_tag(repo: web::Data<dyn Repository>, auth: AuthContext, info: web::Json<CreateTag>) -> Result<HttpResponse, ApiError> {
+    let name = validate_tag(&info.name)?;
+    let color = validate_color(info.color.as_deref().unwrap_or(DEFAULT_COLOR))?;
+    let tag = repo.create_tag(auth.user_id, &name, &color).await.map_err(name_taken)?;
+    Ok(HttpResponse::Created().json(tag))
+}
+
+// PATCH /api/tags/{id}
+// 태그 이름 또는 색상 변경(이름을 바꾸면 태그가 붙은 todo에도 반영)
+pub async fn update_tag(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>, info: web::Json<UpdateTag>) -> Result<HttpResponse, ApiError> {
+    let owner_id = auth.user_id;
+    let mut tag = repo.find_tag(owner_id, path.into_inner()).await?.ok_or_else(tag_not_found)?;
+    if let Some(name) = &info.name {
+        tag.name = validate_tag(name)?;
+    }
+    if let Some(color) = &info.color {
+        tag.color = validate_color(color)?;
+    }
+    match repo.update_tag(owner_id, &tag).await.map_err(name_taken)? {
+        Some(tag) => Ok(HttpResponse::Ok().json(tag)),
+        None => Err(tag_not_found()),
+    }
+}
+
+// DELETE /api/tags/{id}
+// 태그가 붙은 todo에서도 제거
+pub async fn delete_tag(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>) -> Result<HttpResponse, ApiError> {
+    if repo.delete_tag(auth.user_id, path.into_inner()).await? {
+        Ok(HttpResponse::NoContent().finish())
+    } else {
+        Err(tag_not_found())
+    }
+}
@@ -1,32 +1,418 @@
-use actix_web::{web, HttpMessage, HttpResponse, Responder};
-use sqlx::{SqlitePool, Row};
-use serde::Serialize;
- 
-#[derive(Serialize)]
-struct Todo {
-    id: i64,
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Deserializer, Serialize};
+use chrono::NaiveDate;
+use crate::auth::AuthContext;
+use crate::error::ApiError;
+use crate::recurrence::Rule;
+use crate::repository::{NewTodo, Repository, Todo};
+use crate::todo::{self, Cursor, Filter, Sort, NONE};
+
+// 마감일 형식(YYYY-MM-DD)
+const DUE_DATE_FORMAT: &str = "%Y-%m-%d";
+
+// todo 목록 한 페이지 기본/최대 크기
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+// 메모 최대 길이(문자 수), todo 하나의 최대 태그 수, 태그 이름 최대 길이
+const MAX_NOTES_LENGTH: usize = 10_000;
+const MAX_TAGS: usize = 20;
+const MAX_TAG_LENGTH: usize = 32;
+
+// 알림 시각 범위(마감일 0시 기준 분): 30일 전부터 마감일 23:59까지
+const MIN_REMINDER_OFFSET_MINUTES: i64 = -30 * 24 * 60;
+const MAX_REMINDER_OFFSET_MINUTES: i64 = 24 * 60 - 1;
+
+#[derive(Deserialize)]
+pub struct CreateTodo {
     title: String,
-    created_at: String,
-}
- 
-pub async fn list_todos(pool: web::Data<SqlitePool>, req: actix_web::HttpRequest) -> impl Responder {
-    // username 추출
-    let _username = req.extensions_mut().get::<String>().cloned().unwrap_or("Unknown".to_string());
- 
-    // web::Data<SqlitePool> 익스트랙터를 통해 main에서 등록한 DB 풀 객체를 받음
-    match sqlx::query("select id, title, created_at from todos")
-    .fetch_all(pool.get_ref()).await {  // DB 풀 참조를 사용하여 모든 결과 행 가져오기 및 완료 대기(결과 Vec<SqliteRow> 타입)
-        Ok(rows) => {   // 성공적으로 결과 행을 가져왔을 때
-            let todos: Vec<Todo> = rows.into_iter().map(|r| Todo {  // 각 행(r: SqliteRow)을 Todo 구조체로 매핑
-                id: r.get("id"),
-                title: r.get("title"),
-                created_at: r.get("created_at"),
-            }).collect();   // 매핑된 Todo 객체들을 Vec<Todo>으로 수집
-            HttpResponse::Ok().json(todos)  // Vec<Todo>을 json 형태로 직렬화하여 200 OK 응답
+    notes: Option<String>,
+    due_date: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    project_id: Option<i64>,    // 지정하지 않으면 상위 todo의 프로젝트(상위 todo가 없으면 프로젝트 없음)
+    parent_id: Option<i64>,     // 하위 todo로 만들 상위 todo
+    recurrence: Option<String>, // daily, weekly, monthly, weekdays 또는 RRULE(마감일 필요)
+    reminder_offset_minutes: Option<i64>,   // 마감일 0시(UTC) 기준 알림 시각(분, 음수면 전날 이전, 마감일 필요)
+}
+
+#[derive(Deserialize)]
+pub struct UpdateTodo {
+    title: Option<String>,
+    // 필드 누락(None)과 null(Some(None))을 구분하여 null이면 메모 삭제
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    notes: Option<Option<String>>,
+    completed: Option<bool>,
+    // 필드 누락(None)과 null(Some(None))을 구분하여 null이면 마감일 삭제
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    due_date: Option<Option<String>>,
+    tags: Option<Vec<String>>,  // 지정하면 태그 목록 전체를 교체
+    // null이면 프로젝트에서 제외
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    project_id: Option<Option<i64>>,
+    // null이면 최상위 todo로 변경
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    parent_id: Option<Option<i64>>,
+    // null이면 반복 해제
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    recurrence: Option<Option<String>>,
+    // null이면 알림 해제
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    reminder_offset_minutes: Option<Option<i64>>,
+}
+
+// 반복 todo를 완료하면 새로 만든 다음 occurrence를 함께 응답
+#[derive(Serialize)]
+struct UpdatedTodo {
+    #[serde(flatten)]
+    todo: Todo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_occurrence: Option<Todo>,
+}
+
+#[derive(Deserialize)]
+pub struct ListTodosQuery {
+    status: Option<String>,     // all(기본값), open, completed
+    due_from: Option<String>,
+    due_to: Option<String>,
+    tag: Option<String>,
+    project_id: Option<String>, // 프로젝트 id 또는 none(프로젝트에 속하지 않은 todo)
+    parent_id: Option<String>,  // 상위 todo id(그 todo의 하위 todo) 또는 none(최상위 todo)
+    q: Option<String>,          // 제목과 메모 전문 검색
+    sort: Option<String>,       // created_at(기본값), updated_at, due_date, title(앞에 '-'를 붙이면 내림차순)
+    limit: Option<i64>,
+    cursor: Option<String>,     // 이전 응답의 next_cursor 또는 prev_cursor
+}
+
+// 값이 존재하면(null 포함) Some으로 감싸는 역직렬화 함수
+fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+// 제목 검증(공백 제거 후 비어 있으면 에러)
+fn validate_title(title: &str) -> Result<String, ApiError> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(ApiError::bad_request("invalid_title", "Title must not be empty."));
+    }
+    Ok(title.to_string())
+}
+
+// 마감일 검증(YYYY-MM-DD 형식만 허용)
+fn validate_due_date(due_date: &str) -> Result<String, ApiError> {
+    match NaiveDate::parse_from_str(due_date, DUE_DATE_FORMAT) {
+        Ok(date) => Ok(date.format(DUE_DATE_FORMAT).to_string()),
+        Err(_) => Err(ApiError::bad_request("invalid_due_date", "Due date must be in YYYY-MM-DD format.")),
+    }
+}
+
+// 메모 검증(공백만 있으면 메모 없음으로 저장)
+fn validate_notes(notes: &str) -> Result<Option<String>, ApiError> {
+    if notes.chars().count()>MAX_NOTES_LENGTH {
+        return Err(ApiError::bad_request("invalid_notes", format!("Notes must be at most {} characters.", MAX_NOTES_LENGTH)));
+    }
+    Ok(Some(notes.to_string()).filter(|n| !n.trim().is_empty()))
+}
+
+// 태그 이름 검증(공백 제거 후 1~32자)
+pub(super) fn validate_tag(tag: &str) -> Result<String, ApiError> {
+    let tag = tag.trim();
+    if tag.is_empty() || tag.chars().count()>MAX_TAG_LENGTH {
+        return Err(ApiError::bad_request("invalid_tag", format!("Tags must be 1 to {} characters.", MAX_TAG_LENGTH)));
+    }
+    Ok(tag.to_string())
+}
+
+// 태그 목록 검증(중복 제거 후 이름순)
+fn validate_tags(tags: &[String]) -> Result<Vec<String>, ApiError> {
+    let mut tags = tags.iter().map(|t| validate_tag(t)).collect::<Result<Vec<_>, _>>()?;
+    tags.sort();
+    tags.dedup();
+    if tags.len()>MAX_TAGS {
+        return Err(ApiError::bad_request("invalid_tag", format!("A todo can have at most {} tags.", MAX_TAGS)));
+    }
+    Ok(tags)
+}
+
+// 반복 규칙 검증 후 정규화한 RRULE 반환(매월 반복은 마감일의 날짜에 고정)
+fn validate_recurrence(recurrence: &str, due_date: Option<&str>) -> Result<String, ApiError> {
+    let rule = Rule::parse(recurrence).map_err(|message| ApiError::bad_request("invalid_recurrence", message))?;
+    let due_date = due_date.and_then(|d| NaiveDate::parse_from_str(d, DUE_DATE_FORMAT).ok())
+        .ok_or_else(|| ApiError::bad_request("invalid_recurrence", "A recurring todo needs a due date."))?;
+    Ok(rule.anchored(due_date).to_string())
+}
+
+// 저장된 반복 규칙을 바뀐 마감일에 맞춰 다시 고정
+// 마감일을 지운 경우는 check_due_date에서 거부하고, 파싱할 수 없는 규칙은 그대로 둠(next_occurrence에서 무시)
+fn reanchor_recurrence(recurrence: &str, due_date: Option<&str>) -> String {
+    let due_date = due_date.and_then(|d| NaiveDate::parse_from_str(d, DUE_DATE_FORMAT).ok());
+    match (Rule::parse(recurrence), due_date) {
+        (Ok(rule), Some(due_date)) => rule.reanchored(due_date).to_string(),
+        _ => recurrence.to_string(),
+    }
+}
+
+fn validate_reminder(offset: i64) -> Result<i64, ApiError> {
+    if !(MIN_REMINDER_OFFSET_MINUTES..=MAX_REMINDER_OFFSET_MINUTES).contains(&offset) {
+        return Err(ApiError::bad_request("invalid_reminder", format!(
+            "reminder_offset_minutes must be between {} and {}.", MIN_REMINDER_OFFSET_MINUTES, MAX_REMINDER_OFFSET_MINUTES)));
+    }
+    Ok(offset)
+}
+
+// 반복과 알림은 마감일 기준이므로 마감일을 지우려면 함께 해제해야 함
+fn check_due_date(due_date: &Option<String>, recurrence: &Option<String>, reminder_offset_minutes: Option<i64>) -> Result<(), ApiError> {
+    if due_date.is_some() {
+        return Ok(());
+    }
+    if recurrence.is_some() {
+        return Err(ApiError::bad_request("invalid_recurrence", "A recurring todo needs a due date."));
+    }
+    if reminder_offset_minutes.is_some() {
+        return Err(ApiError::bad_request("invalid_reminder", "A reminder needs a due date."));
+    }
+    Ok(())
+}
+
+// 완료한 occurrence 다음의 todo(제목, 메모, 태그, 프로젝트, 상위 todo, 알림은 그대로), 반복이 끝났으면 None
+fn next_occurrence(todo: &Todo, recurrence: &str) -> Option<NewTodo> {
+    let rule = Rule::parse(recurrence)
+        .inspect_err(|e| tracing::warn!("Ignoring invalid stored recurrence of todo {}: {}", todo.id, e)).ok()?;
+    let due_date = NaiveDate::parse_from_str(todo.due_date.as_deref()?, DUE_DATE_FORMAT).ok()?;
+    let (due_date, rule) = rule.next(due_date)?;
+    Some(NewTodo {
+        title: todo.title.clone(),
+        notes: todo.notes.clone(),
+        due_date: Some(due_date.format(DUE_DATE_FORMAT).to_string()),
+        tags: todo.tags.clone(),
+        project_id: todo.project_id,
+        parent_id: todo.parent_id,
+        recurrence: Some(rule.to_string()),
+        reminder_offset_minutes: todo.reminder_offset_minutes,
+    })
+}
+
+fn invalid_query(message: &str) -> ApiError {
+    ApiError::bad_request("invalid_query", message)
+}
+
+// id 조건 파싱(none이면 NONE)
+fn parse_id_filter(name: &str, value: &Option<String>) -> Result<Option<i64>, ApiError> {
+    match value.as_deref() {
+        None => Ok(None),
+        Some("none") => Ok(Some(NONE)),
+        Some(value) => match value.parse::<i64>() {
+            Ok(id) if id>0 => Ok(Some(id)),
+            _ => Err(invalid_query(&format!("{} must be a todo or project id, or none.", name))),
+        },
+    }
+}
+
+impl ListTodosQuery {
+    fn filter(&self) -> Result<Filter, ApiError> {
+        let completed = match self.status.as_deref() {
+            None | Some("all") => None,
+            Some("open") => Some(false),
+            Some("completed") => Some(true),
+            Some(_) => return Err(invalid_query("status must be all, open or completed.")),
+        };
+        let due_date = |value: &Option<String>| value.as_deref().map(validate_due_date).transpose()
+            .map_err(|_| invalid_query("due_from and due_to must be in YYYY-MM-DD format."));
+        Ok(Filter {
+            completed,
+            due_from: due_date(&self.due_from)?,
+            due_to: due_date(&self.due_to)?,
+            tag: self.tag.as_deref().map(validate_tag).transpose()?,
+            search: self.q.as_deref().map(str::trim).filter(|q| !q.is_empty()).map(str::to_string),
+            project_id: parse_id_filter("project_id", &self.project_id)?,
+            parent_id: parse_id_filter("parent_id", &self.parent_id)?,
+        })
+    }
+
+    fn sort(&self) -> Result<Sort, ApiError> {
+        match self.sort.as_deref() {
+            None => Ok(Sort::default()),
+            Some(value) => Sort::parse(value)
+                .ok_or_else(|| invalid_query("sort must be created_at, updated_at, due_date or title, optionally prefixed with '-'.")),
         }
-        Err(e) => { // 에러 발생 시
-            eprintln!("Error listing exams: {:?}", e);  // 에러 로그 출력(표준 에러)
-            HttpResponse::InternalServerError().body("Error listing exams.")    // 500 Internal Server Error 응답
+    }
+
+    // 커서는 같은 정렬 기준으로 만든 것만 허용
+    fn cursor(&self, sort: &Sort) -> Result<Option<Cursor>, ApiError> {
+        let Some(value) = &self.cursor else { return Ok(None) };
+        match Cursor::decode(value) {
+            Some(cursor) if cursor.sort==sort.as_string() => Ok(Some(cursor)),
+            _ => Err(ApiError::bad_request("invalid_cursor", "Invalid pagination cursor.")),
         }
-    }   
-}
\ No newline at end of file
+    }
+}
+
+// 현재 요청의 쿼리 문자열에서 cursor만 바꾼 페이지 링크
+fn page_link(req: &HttpRequest, cursor: &Cursor) -> String {
+    let mut query = url::form_urlencoded::Serializer::new(String::new());
+    for (name, value) in url::form_urlencoded::parse(req.query_string().as_bytes()) {
+        if name!="cursor" {
+            query.append_pair(&name, &value);
+        }
+    }
+    query.append_pair("cursor", &cursor.encode());
+    format!("{}?{}", req.path(), query.finish())
+}
+
+// 상위 todo와 프로젝트를 확인하여 (project_id, parent_id) 반환
+// 하위 todo는 상위 todo와 같은 프로젝트에 속해야 하며, 프로젝트를 지정하지 않으면 상위 todo의 프로젝트를 따름
+// todo_id: 수정 중인 todo(자기 자신이나 자신의 하위 todo 아래로 옮기는 순환 방지)
+// current: 상위 todo가 없고 프로젝트도 지정하지 않았을 때 유지할 프로젝트
+async fn placement(repo: &dyn Repository, owner_id: i64, todo_id: Option<i64>, parent_id: Option<i64>, project_id: Option<Option<i64>>, current: Option<i64>)
+    -> Result<(Option<i64>, Option<i64>), ApiError> {
+    if let Some(Some(project_id)) = project_id
+        && repo.find_project(owner_id, project_id).await?.is_none() {
+        return Err(ApiError::bad_request("invalid_project", "Project not found."));
+    }
+    let Some(parent_id) = parent_id else {
+        return Ok((project_id.unwrap_or(current), None));
+    };
+    let parent = repo.find_todo(owner_id, parent_id).await?
+        .ok_or_else(|| ApiError::bad_request("invalid_parent", "Parent todo not found."))?;
+    if let Some(todo_id) = todo_id
+        && repo.todo_ancestors(owner_id, parent_id).await?.contains(&todo_id) {
+        return Err(ApiError::conflict("invalid_parent", "A todo cannot be moved under itself or one of its subtasks."));
+    }
+    match project_id {
+        Some(project_id) if project_id!=parent.project_id =>
+            Err(ApiError::conflict("project_mismatch", "A subtask must belong to the same project as its parent todo.")),
+        _ => Ok((parent.project_id, Some(parent_id))),
+    }
+}
+
+// 다른 사용자의 todo는 존재 여부를 노출하지 않도록 같은 404로 응답
+fn todo_not_found() -> ApiError {
+    ApiError::not_found("todo_not_found", "Todo not found.")
+}
+
+// GET /api/todos?status=&due_from=&due_to=&tag=&project_id=&parent_id=&q=&sort=&limit=&cursor=
+// 인증된 사용자 소유의 todo 목록 조회(커서 기반 페이지 나누기)
+// 다음/이전 페이지 위치는 본문의 next_cursor, prev_cursor와 Link 헤더(rel="next", rel="prev")로 전달
+pub async fn list_todos(repo: web::Data<dyn Repository>, auth: AuthContext, query: web::Query<ListTodosQuery>, req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    let filter = query.filter()?;
+    let sort = query.sort()?;
+    let cursor = query.cursor(&sort)?;
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let page = todo::list(repo.get_ref(), auth.user_id, &filter, sort, cursor.as_ref(), limit).await?;
+    let total = repo.count_todos(auth.user_id, &filter).await?;
+
+    let mut links = Vec::new();
+    if let Some(next) = &page.next {
+        links.push(format!("<{}>; rel=\"next\"", page_link(&req, next)));
+    }
+    if let Some(prev) = &page.prev {
+        links.push(format!("<{}>; rel=\"prev\"", page_link(&req, prev)));
+    }
+    let mut response = HttpResponse::Ok();
+    if !links.is_empty() {
+        response.insert_header((header::LINK, links.join(", ")));
+    }
+    Ok(response.json(serde_json::json!({
+        "todos": page.todos,
+        "total": total,
+        "limit": limit,
+        "next_cursor": page.next.as_ref().map(Cursor::encode),
+        "prev_cursor": page.prev.as_ref().map(Cursor::encode),
+    })))
+}
+
+// POST /api/todos
+pub async fn create_todo(repo: web::Data<dyn Repository>, auth: AuthContext, info: web::Json<CreateTodo>) -> Result<HttpResponse, ApiError> {
+    let (project_id, parent_id) = placement(repo.get_ref(), auth.user_id, None, info.parent_id, info.project_id.map(Some), None).await?;
+    let due_date = info.due_date.as_deref().map(validate_due_date).transpose()?;
+    let recurrence = info.recurrence.as_deref().map(|r| validate_recurrence(r, due_date.as_deref())).transpose()?;
+    let reminder_offset_minutes = info.reminder_offset_minutes.map(validate_reminder).transpose()?;
+    check_due_date(&due_date, &recurrence, reminder_offset_minutes)?;
+    let todo = NewTodo {
+        title: validate_title(&info.title)?,
+        notes: info.notes.as_deref().map(validate_notes).transpose()?.flatten(),
+        due_date,
+        tags: validate_tags(&info.tags)?,
+        project_id,
+        parent_id,
+        recurrence,
+        reminder_offset_minutes,
+    };
+
+    let todo = repo.create_todo(auth.user_id, &todo).await?;
+    Ok(HttpResponse::Created().json(todo))  // 생성된 todo와 201 Created 응답
+}
+
+// GET /api/todos/{id}
+pub async fn get_todo(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>) -> Result<HttpResponse, ApiError> {
+    match repo.find_todo(auth.user_id, path.into_inner()).await? {
+        Some(todo) => Ok(HttpResponse::Ok().json(todo)),
+        None => Err(todo_not_found()),
+    }
+}
+
+// PATCH /api/todos/{id}
+// 반복 todo를 완료하면 반복 규칙을 다음 occurrence(새 todo)로 옮기고 응답의 next_occurrence로 반환
+pub async fn update_todo(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>, info: web::Json<UpdateTodo>) -> Result<HttpResponse, ApiError> {
+    let owner_id = auth.user_id;
+    let id = path.into_inner();
+
+    // 기존 todo 조회 후 요청에 포함된 필드만 변경
+    let mut todo = repo.find_todo(owner_id, id).await?.ok_or_else(todo_not_found)?;
+    let completing = info.completed==Some(true) && !todo.completed;
+    if let Some(title) = &info.title {
+        todo.title = validate_title(title)?;
+    }
+    if let Some(notes) = &info.notes {
+        todo.notes = notes.as_deref().map(validate_notes).transpose()?.flatten();
+    }
+    if let Some(completed) = info.completed {
+        todo.completed = completed;
+    }
+    if let Some(due_date) = &info.due_date {
+        todo.due_date = due_date.as_deref().map(validate_due_date).transpose()?;
+    }
+    if let Some(tags) = &info.tags {
+        todo.tags = validate_tags(tags)?;
+    }
+    // 반복 규칙 없이 마감일만 바꾸면 저장된 규칙을 새 마감일에 다시 고정(매월 반복이 이전 날짜에 남지 않도록)
+    if info.due_date.is_some() && info.recurrence.is_none() {
+        todo.recurrence = todo.recurrence.as_deref().map(|r| reanchor_recurrence(r, todo.due_date.as_deref()));
+    }
+    // 프로젝트 또는 상위 todo를 바꾸는 경우(하위 todo들도 함께 이동)
+    if info.project_id.is_some() || info.parent_id.is_some() {
+        let parent_id = info.parent_id.unwrap_or(todo.parent_id);
+        (todo.project_id, todo.parent_id) = placement(repo.get_ref(), owner_id, Some(id), parent_id, info.project_id, todo.project_id).await?;
+    }
+    if let Some(recurrence) = &info.recurrence {
+        todo.recurrence = recurrence.as_deref().map(|r| validate_recurrence(r, todo.due_date.as_deref())).transpose()?;
+    }
+    if let Some(reminder_offset_minutes) = info.reminder_offset_minutes {
+        todo.reminder_offset_minutes = reminder_offset_minutes.map(validate_reminder).transpose()?;
+    }
+    check_due_date(&todo.due_date, &todo.recurrence, todo.reminder_offset_minutes)?;
+
+    // 반복 todo 완료는 반복 규칙 제거, 저장, 다음 occurrence 생성을 한 트랜잭션으로 처리(동시 완료 요청에서 중복 생성 방지)
+    let (todo, next_occurrence) = if completing && let Some(recurrence) = todo.recurrence.take() {
+        let next = next_occurrence(&todo, &recurrence);
+        repo.complete_recurring_todo(owner_id, &todo, next.as_ref()).await?.ok_or_else(todo_not_found)?
+    } else {
+        (repo.update_todo(owner_id, &todo).await?.ok_or_else(todo_not_found)?, None)
+    };
+    Ok(HttpResponse::Ok().json(UpdatedTodo { todo, next_occurrence }))
+}
+
+// DELETE /api/todos/{id}
+// 하위 todo도 함께 삭제
+pub async fn delete_todo(repo: web::Data<dyn Repository>, auth: AuthContext, path: web::Path<i64>) -> Result<HttpResponse, ApiError> {
+    if repo.delete_todo(auth.user_id, path.into_inner()).await? {
+        Ok(HttpResponse::NoContent().finish())  // 삭제 성공 시 204 No Content 응답
+    } else {    // 존재하지 않거나 다른 사용자의 todo
+        Err(todo_not_found())
+    }
+}
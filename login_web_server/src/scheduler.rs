@@ -0,0 +1,97 @@
+use actix_web::rt::{self, task::JoinHandle};
+use anyhow::Result;
+use futures_util::future::LocalBoxFuture;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::Instrument;
+
+// 서버 안에서 실행하는 주기 작업(job) 스케줄러
+// 작업마다 별도 태스크에서 시작 즉시 한 번 실행한 뒤 interval마다 반복하고,
+// 실행 결과(요약 또는 에러)와 소요 시간을 job span과 함께 로그로 남김
+//     let jobs = Scheduler::new().every("name", Duration::from_secs(60), || async { Ok("done".to_string()) }).start();
+//     ...
+//     jobs.shutdown().await;  // 실행 중인 작업은 끝날 때까지 기다린 뒤 중지
+
+type JobFn = Box<dyn Fn() -> LocalBoxFuture<'static, Result<String>>>;
+
+struct Job {
+    name: &'static str,
+    interval: Duration,
+    run: JobFn,
+}
+
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<Job>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Scheduler::default()
+    }
+
+    // interval마다 실행할 작업 등록(작업은 로그에 남길 결과 요약 반환)
+    pub fn every<F, Fut>(mut self, name: &'static str, interval: Duration, job: F) -> Self
+    where
+        F: Fn() -> Fut+'static,
+        Fut: Future<Output=Result<String>>+'static,
+    {
+        self.jobs.push(Job { name, interval, run: Box::new(move || Box::pin(job())) });
+        self
+    }
+
+    // 등록한 작업 시작(현재 actix 런타임의 태스크로 실행)
+    pub fn start(self) -> SchedulerHandle {
+        let (shutdown, _) = watch::channel(false);
+        let tasks = self.jobs.into_iter()
+            .map(|job| {
+                tracing::info!("Scheduled job {} every {}s", job.name, job.interval.as_secs());
+                rt::spawn(run_job(job, shutdown.subscribe()))
+            })
+            .collect();
+        SchedulerHandle { shutdown, tasks }
+    }
+}
+
+// 작업 하나의 반복 실행 루프
+// 종료 신호는 다음 실행을 기다리는 동안에만 확인하므로 실행 중인 작업은 중간에 끊기지 않음
+async fn run_job(job: Job, mut shutdown: watch::Receiver<bool>) {
+    let mut ticker = interval(job.interval);
+    // 작업이 interval보다 오래 걸리면 밀린 실행을 몰아서 하지 않고 완료 시점부터 다시 대기
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            _ = ticker.tick() => run_once(&job).instrument(tracing::info_span!("job", name = job.name)).await,
+        }
+    }
+}
+
+async fn run_once(job: &Job) {
+    let started = Instant::now();
+    match (job.run)().await {
+        Ok(summary) => tracing::info!("Job {} finished in {}ms: {}", job.name, started.elapsed().as_millis(), summary),
+        Err(e) => tracing::error!("Job {} failed after {}ms: {:?}", job.name, started.elapsed().as_millis(), e),
+    }
+}
+
+// 실행 중인 스케줄러(shutdown으로 중지)
+pub struct SchedulerHandle {
+    shutdown: watch::Sender<bool>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl SchedulerHandle {
+    // 모든 작업에 종료 신호를 보낸 뒤 실행 중인 작업이 끝날 때까지 대기
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        for task in self.tasks {
+            if let Err(e) = task.await {
+                tracing::error!("Job task ended abnormally: {:?}", e);
+            }
+        }
+        tracing::info!("Background jobs stopped");
+    }
+}
@@ -0,0 +1,78 @@
+use actix_web::HttpRequest;
+use serde::Serialize;
+use crate::oauth::Grant;
+use crate::repository::{SessionInfo, SessionRepository};
+
+// 저장할 문자열 최대 길이(비정상적으로 긴 헤더 방지)
+const MAX_DEVICE_LABEL_LEN: usize = 100;
+const MAX_USER_AGENT_LEN: usize = 512;
+// 마지막 사용 시각 갱신 간격(요청마다 쓰기가 발생하지 않도록 이 시간이 지난 경우에만 갱신)
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+// 로그인 요청을 보낸 클라이언트 정보
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl ClientInfo {
+    // 요청의 peer 주소와 User-Agent 헤더에서 클라이언트 정보 추출
+    pub fn from_request(req: &HttpRequest) -> Self {
+        ClientInfo {
+            ip: req.peer_addr().map(|addr| addr.ip().to_string()),
+            user_agent: req.headers().get("User-Agent")
+                .and_then(|v| v.to_str().ok())
+                .map(|ua| truncate(ua, MAX_USER_AGENT_LEN)),
+        }
+    }
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    s.chars().take(max_len).collect()
+}
+
+#[derive(Serialize)]
+pub struct Session {
+    #[serde(flatten)]
+    info: SessionInfo,
+    current: bool,  // 조회 요청에 사용된 토큰의 세션인지 여부
+}
+
+// 로그인 시 세션 생성(id는 refresh 토큰 family id 및 access 토큰 sid 클레임과 동일)
+// device_label이 없으면 User-Agent를 기기 이름으로 사용
+// OAuth 클라이언트 앱 로그인이면 grant에 클라이언트와 승인된 scope 기록
+pub async fn create(repo: &dyn SessionRepository, id: &str, user_id: i64, device_label: Option<&str>, client: &ClientInfo, grant: Option<&Grant>) -> Result<(), sqlx::Error> {
+    let device_label = device_label
+        .map(str::trim).filter(|label| !label.is_empty())
+        .or(client.user_agent.as_deref())
+        .map(|label| truncate(label, MAX_DEVICE_LABEL_LEN));
+    repo.create_session(id, user_id, device_label.as_deref(), client, grant).await
+}
+
+// 세션이 유효하면 true 반환, 마지막 사용 시각은 TOUCH_INTERVAL_SECS보다 오래된 경우에만 갱신
+// 폐기되었거나 사용자 삭제로 사라진 세션이면 false
+pub async fn touch(repo: &dyn SessionRepository, id: &str) -> Result<bool, sqlx::Error> {
+    repo.touch_session(id, TOUCH_INTERVAL_SECS).await
+}
+
+// 사용자의 활성 세션 목록(최근 사용 순)
+pub async fn list(repo: &dyn SessionRepository, username: &str, current_id: &str) -> Result<Vec<Session>, sqlx::Error> {
+    let sessions = repo.list_sessions(username).await?;
+    Ok(sessions.into_iter().map(|info| Session { current: info.id==current_id, info }).collect())
+}
+
+// 사용자 본인의 세션 하나를 폐기(refresh 토큰 family도 함께 폐기)
+// 해당 사용자의 활성 세션이 아니면 false
+pub async fn revoke(repo: &dyn SessionRepository, username: &str, id: &str) -> Result<bool, sqlx::Error> {
+    repo.revoke_session(username, id).await
+}
+
+// 현재 세션을 제외한 사용자의 모든 세션 폐기 후 폐기된 세션 수 반환
+pub async fn revoke_others(repo: &dyn SessionRepository, username: &str, current_id: &str) -> Result<u64, sqlx::Error> {
+    repo.revoke_other_sessions(username, current_id).await
+}
+
+// 사용자의 모든 세션과 refresh 토큰 폐기 후 폐기된 세션 수 반환(비밀번호 재설정 시)
+pub async fn revoke_all(repo: &dyn SessionRepository, user_id: i64) -> Result<u64, sqlx::Error> {
+    repo.revoke_all_sessions(user_id).await
+}
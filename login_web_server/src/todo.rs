@@ -0,0 +1,146 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use crate::repository::{Todo, TodoRepository};
+
+// todo 목록 조회(필터, 정렬, 커서 기반 페이지 나누기)
+// 정렬 키와 id를 함께 비교하는 keyset 방식이므로 페이지 사이에 todo가 추가/삭제되어도 항목이 중복되거나 빠지지 않음
+
+// 마감일이 없는 todo는 정렬 방향과 관계없이 마지막에 오도록 대신 사용하는 정렬 키
+pub const NO_DUE_DATE_ASC: &str = "9999-12-31";
+pub const NO_DUE_DATE_DESC: &str = "0000-00-00";
+
+// 조회 조건(모두 선택)
+#[derive(Default, Debug)]
+pub struct Filter {
+    pub completed: Option<bool>,
+    pub due_from: Option<String>,   // 이 날짜 이후(포함, YYYY-MM-DD)
+    pub due_to: Option<String>,     // 이 날짜 이전(포함, YYYY-MM-DD)
+    pub tag: Option<String>,
+    pub search: Option<String>,     // 제목과 메모 전문 검색(공백으로 구분한 단어가 모두 포함된 todo)
+    pub project_id: Option<i64>,    // NONE이면 프로젝트에 속하지 않은 todo
+    pub parent_id: Option<i64>,     // NONE이면 최상위 todo, 아니면 그 todo의 하위 todo
+}
+
+// project_id, parent_id 조건에서 "없음"을 나타내는 값(id는 1부터 시작)
+pub const NONE: i64 = 0;
+
+// 정렬 기준 컬럼
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortField {
+    CreatedAt,
+    UpdatedAt,
+    DueDate,
+    Title,
+}
+
+// 정렬 기준(같은 값이면 id 순서)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sort {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl Default for Sort {
+    fn default() -> Self {
+        Sort { field: SortField::CreatedAt, descending: false }
+    }
+}
+
+impl Sort {
+    // "created_at", "-due_date"처럼 앞에 '-'가 붙으면 내림차순
+    pub fn parse(value: &str) -> Option<Self> {
+        let (descending, name) = match value.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, value),
+        };
+        let field = match name {
+            "created_at" => SortField::CreatedAt,
+            "updated_at" => SortField::UpdatedAt,
+            "due_date" => SortField::DueDate,
+            "title" => SortField::Title,
+            _ => return None,
+        };
+        Some(Sort { field, descending })
+    }
+
+    pub fn as_string(&self) -> String {
+        let name = match self.field {
+            SortField::CreatedAt => "created_at",
+            SortField::UpdatedAt => "updated_at",
+            SortField::DueDate => "due_date",
+            SortField::Title => "title",
+        };
+        if self.descending { format!("-{}", name) } else { name.to_string() }
+    }
+
+    // 마감일이 없을 때 사용하는 정렬 키
+    pub fn no_due_date(&self) -> &'static str {
+        if self.descending { NO_DUE_DATE_DESC } else { NO_DUE_DATE_ASC }
+    }
+
+    // todo의 정렬 키(저장소 구현의 정렬 식과 같은 값)
+    fn key(&self, todo: &Todo) -> String {
+        match self.field {
+            SortField::CreatedAt => todo.created_at.clone(),
+            SortField::UpdatedAt => todo.updated_at.clone(),
+            SortField::DueDate => todo.due_date.clone().unwrap_or_else(|| self.no_due_date().to_string()),
+            SortField::Title => todo.title.clone(),
+        }
+    }
+}
+
+// 페이지 위치(기준 todo의 정렬 키와 id)
+// backward가 true면 기준 todo 앞쪽(이전 페이지), 아니면 뒤쪽(다음 페이지)
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Cursor {
+    #[serde(rename = "s")]
+    pub sort: String,   // 커서를 만든 정렬 기준(다른 정렬에 사용하면 거부)
+    #[serde(rename = "k")]
+    pub key: String,
+    #[serde(rename = "i")]
+    pub id: i64,
+    #[serde(rename = "b", default)]
+    pub backward: bool,
+}
+
+impl Cursor {
+    fn at(sort: &Sort, todo: &Todo, backward: bool) -> Self {
+        Cursor { sort: sort.as_string(), key: sort.key(todo), id: todo.id, backward }
+    }
+
+    // 클라이언트에 전달하는 불투명(opaque) 문자열
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+// 조회 결과 한 페이지
+pub struct Page {
+    pub todos: Vec<Todo>,
+    pub next: Option<Cursor>,
+    pub prev: Option<Cursor>,
+}
+
+// 한 페이지 조회
+// 저장소에서 limit+1개를 가져와 다음(또는 이전) 페이지가 있는지 판단
+pub async fn list(repo: &dyn TodoRepository, owner_id: i64, filter: &Filter, sort: Sort, cursor: Option<&Cursor>, limit: i64) -> Result<Page, sqlx::Error> {
+    let mut todos = repo.list_todos(owner_id, filter, sort, cursor, limit+1).await?;
+    let more = todos.len() as i64>limit;
+    todos.truncate(limit as usize);
+    let backward = cursor.is_some_and(|c| c.backward);
+    // 이전 페이지는 역순으로 조회되므로 다시 정렬 순서로 되돌림
+    if backward {
+        todos.reverse();
+    }
+    // 앞쪽 방향: 커서가 있으면 이전 페이지 존재, 가져온 항목이 limit보다 많으면 다음 페이지 존재
+    // 뒤쪽 방향: 그 반대(커서가 가리키는 todo가 다음 페이지에 있음)
+    let (has_prev, has_next) = if backward { (more, true) } else { (cursor.is_some(), more) };
+    let next = todos.last().filter(|_| has_next).map(|t| Cursor::at(&sort, t, false));
+    let prev = todos.first().filter(|_| has_prev).map(|t| Cursor::at(&sort, t, true));
+    Ok(Page { todos, next, prev })
+}
@@ -0,0 +1,72 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use qrcode::{render::svg, QrCode};
+use rand::RngCore;
+use sha1::Sha1;
+
+// RFC 6238 TOTP(HMAC-SHA1, 6자리, 30초 간격) 구현
+// 대부분의 인증 앱(Google Authenticator 등)이 지원하는 기본 파라미터 사용
+pub const DIGITS: u32 = 6;
+pub const PERIOD_SECS: u64 = 30;
+
+// 비밀 키 길이(바이트, RFC 4226 권장 160비트)
+const SECRET_BYTES: usize = 20;
+
+// 기기 시계 오차를 고려하여 앞뒤로 허용할 시간 단계(step) 수
+const ALLOWED_SKEW_STEPS: i64 = 1;
+
+// 새 비밀 키 생성(인증 앱 등록에 사용하는 base32 문자열)
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+// 시간 단계(Unix 시간 / 30초)에 해당하는 코드 계산(RFC 4226 HOTP)
+pub fn code_at(secret: &[u8], step: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+    // dynamic truncation: 마지막 바이트 하위 4비트를 오프셋으로 31비트 정수 추출
+    let offset = (digest[digest.len()-1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([digest[offset], digest[offset+1], digest[offset+2], digest[offset+3]]) & 0x7fff_ffff;
+    value % 10u32.pow(DIGITS)
+}
+
+// 현재 시각 기준 허용 범위 안에서 코드가 일치하면 해당 시간 단계 반환
+// 호출자는 반환된 단계를 저장해 같은 코드의 재사용(replay)을 막아야 함
+pub fn verify(secret: &str, code: &str, unix_time: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len()!=DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let secret = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let current = (unix_time/PERIOD_SECS) as i64;
+    (-ALLOWED_SKEW_STEPS..=ALLOWED_SKEW_STEPS)
+        .map(|skew| current+skew)
+        .filter(|step| *step>=0)
+        .map(|step| step as u64)
+        .find(|step| code_at(&secret, *step)==code)
+}
+
+// 인증 앱 등록용 otpauth:// URI(Key Uri Format)
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let label = format!("{}:{}", percent_encode(issuer), percent_encode(account));
+    format!("otpauth://totp/{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        label, secret, percent_encode(issuer), DIGITS, PERIOD_SECS)
+}
+
+// otpauth URI를 담은 QR 코드 SVG 이미지
+pub fn qr_svg(uri: &str) -> Option<String> {
+    let code = QrCode::new(uri.as_bytes()).ok()?;
+    Some(code.render::<svg::Color>().min_dimensions(200, 200).build())
+}
+
+// URI 구성 요소용 퍼센트 인코딩(RFC 3986 unreserved 문자 외 모두 인코딩)
+fn percent_encode(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
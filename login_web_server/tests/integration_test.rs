@@ -0,0 +1,2073 @@
+use actix_web::{dev::{Service, ServiceResponse}, test, web, App};
+use login_web_server::{auth::init_keys, config::{Config, HashSelfTest}, keys::{DataKey, KeySet}, mfa, password_hasher::{self, Algorithm, Argon2Hasher, BcryptHasher, PasswordHasher, PasswordHashers}, totp, mailer::{Email, FileMailer, Mailer}, password_policy::{LocalBreachList, PasswordPolicy}, password_reset, lockout, rbac, routes::init, metrics::MetricsAccess, middleware::request_metrics::RequestMetrics, maintenance, scheduler::Scheduler, recurrence::Rule, reminder::{self, FileNotifier, Notifier}, repository::{self, Database, Repository, SqliteRepository}};
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+use std::sync::Arc;
+
+// 임시 in-memory DB(연결마다 별도 DB가 생성되므로 연결 수를 1개로 제한) 생성 후 마이그레이션 적용
+async fn temp_pool() -> SqlitePool {
+    let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:")
+        .await.unwrap();
+    SqliteRepository::new(pool.clone()).migrate().await.unwrap();
+    pool
+}
+
+// SQLite 연결 풀을 핸들러가 사용하는 저장소로 변환
+fn sqlite_repo(pool: &SqlitePool) -> Arc<dyn Repository> {
+    Arc::new(SqliteRepository::new(pool.clone()))
+}
+
+// 테스트용 서비스 객체 생성
+async fn init_app() -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error> {
+    init_app_with_policy(PasswordPolicy::default()).await
+}
+
+async fn init_app_with_policy(policy: PasswordPolicy) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error> {
+    init_app_with(temp_pool().await, policy, test_mailer()).await
+}
+
+// DB에 직접 접근해야 하는 테스트(역할 부여 등)용
+async fn init_app_with_pool(pool: SqlitePool) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error> {
+    init_app_with(pool, PasswordPolicy::default(), test_mailer()).await
+}
+
+// 테스트 프로세스 전체가 공유하는 메일 디렉터리(수신 주소로 메일 구분)
+fn test_mail_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("login-web-server-mail-{}", std::process::id()))
+}
+
+fn test_mailer() -> Arc<dyn Mailer> {
+    Arc::new(FileMailer::new(test_mail_dir()).unwrap())
+}
+
+// 수신 주소로 온 메일 중 가장 최근 메일에서 "<label>: <token>" 줄의 토큰 추출
+fn read_mail_token(to: &str, label: &str) -> Option<String> {
+    let mut mails: Vec<_> = std::fs::read_dir(test_mail_dir()).ok()?.map(|e| e.unwrap().path()).collect();
+    mails.sort();   // 파일 이름이 나노초 타임스탬프로 시작하므로 이름순이 발송 순서
+    mails.iter().rev()
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .filter(|mail| mail.starts_with(&format!("To: {}\r\n", to)))
+        .find_map(|mail| mail.lines().find_map(|line| line.strip_prefix(&format!("{}: ", label)).map(|t| t.trim().to_string())))
+}
+
+fn email_of(username: &str) -> String {
+    format!("{}@example.com", username)
+}
+
+// 회원가입 후 메일로 받은 토큰으로 이메일 인증
+async fn register_verified<S>(app: &S, username: &str)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    let info = serde_json::json!({"username": username, "password": "Passw0rd!", "email": email_of(username)});
+    let req = test::TestRequest::post().uri("/api/register").set_json(&info).to_request();
+    let resp = test::call_service(app, req).await;
+    assert!(resp.status().is_success());
+
+    let token = read_mail_token(&email_of(username), "Verification token").unwrap();
+    let req = test::TestRequest::post().uri("/api/email/verify").set_json(serde_json::json!({"token": token})).to_request();
+    let resp = test::call_service(app, req).await;
+    assert!(resp.status().is_success());
+}
+
+async fn init_app_with(pool: SqlitePool, policy: PasswordPolicy, mailer: Arc<dyn Mailer>) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error> {
+    init_app_with_repo(sqlite_repo(&pool), policy, mailer).await
+}
+
+async fn init_app_with_repo(repo: Arc<dyn Repository>, policy: PasswordPolicy, mailer: Arc<dyn Mailer>) -> impl Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error> {
+    init_keys(KeySet::ephemeral().unwrap());
+    mfa::init_data_key(DataKey::ephemeral().unwrap());
+    test::init_service(
+        App::new().app_data(web::Data::<dyn Repository>::from(repo))
+        .app_data(web::Data::new(policy))
+        .app_data(web::Data::<dyn Mailer>::from(mailer))
+        .app_data(web::Data::new(MetricsAccess::default()))
+        .configure(init)
+    ).await
+}
+
+// 회원가입 후 로그인하여 JWT 토큰 획득
+async fn register_and_login<S>(app: &S, username: &str) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    register_verified(app, username).await;
+    let info = serde_json::json!({"username": username, "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&info).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(app, req).await;
+    resp["token"].as_str().unwrap().to_string()
+}
+
+// 회원가입 후 admin 역할을 부여하고 다시 로그인하여 관리자 권한이 담긴 토큰 획득
+async fn register_admin<S>(app: &S, pool: &SqlitePool, username: &str) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    register_admin_with(app, sqlite_repo(pool).as_ref(), username).await
+}
+
+async fn register_admin_with<S>(app: &S, repo: &dyn Repository, username: &str) -> String
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    register_and_login(app, username).await;
+    assert!(rbac::assign_role(repo, username, rbac::ADMIN_ROLE).await.unwrap());
+    let info = serde_json::json!({"username": username, "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&info).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(app, req).await;
+    resp["token"].as_str().unwrap().to_string()
+}
+
+#[actix_web::test]
+async fn test_todos_are_scoped_to_owner() {
+    let app = init_app().await;
+
+    let alice = register_and_login(&app, "alice").await;
+    let bob = register_and_login(&app, "bob").await;
+
+    // alice의 todo 생성
+    let req = test::TestRequest::post().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", alice)))
+        .set_json(serde_json::json!({"title": "Write report", "due_date": "2025-06-30"})).to_request();
+    let todo: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(todo["title"], "Write report");
+    assert_eq!(todo["completed"], false);
+    assert_eq!(todo["due_date"], "2025-06-30");
+    let id = todo["id"].as_i64().unwrap();
+
+    // bob은 alice의 todo를 볼 수도, 수정할 수도 없음
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", bob))).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["todos"].as_array().unwrap().len(), 0);
+    assert_eq!(resp["total"], 0);
+
+    let req = test::TestRequest::patch().uri(&format!("/api/todos/{}", id))
+        .insert_header(("Authorization", format!("Bearer {}", bob)))
+        .set_json(serde_json::json!({"completed": true})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    // alice는 완료 처리 및 마감일 삭제 가능
+    let req = test::TestRequest::patch().uri(&format!("/api/todos/{}", id))
+        .insert_header(("Authorization", format!("Bearer {}", alice)))
+        .set_json(serde_json::json!({"completed": true, "due_date": null})).to_request();
+    let todo: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(todo["completed"], true);
+    assert!(todo["due_date"].is_null());
+
+    let req = test::TestRequest::delete().uri(&format!("/api/todos/{}", id))
+        .insert_header(("Authorization", format!("Bearer {}", alice))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+
+    let req = test::TestRequest::get().uri(&format!("/api/todos/{}", id))
+        .insert_header(("Authorization", format!("Bearer {}", alice))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn test_owner_migration_keeps_legacy_todos() {
+    // 소유자 추가 마이그레이션 직전까지만 적용한 DB에 기존 todo 기록
+    let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+    let repo = SqliteRepository::new(pool.clone());
+    let before_owner = sqlx::migrate::Migrator {
+        migrations: std::borrow::Cow::Owned(repo.migrator().iter().filter(|m| m.version<202506021200).cloned().collect()),
+        ignore_missing: false,
+        locking: true,
+    };
+    before_owner.run(&pool).await.unwrap();
+    for title in ["Buy milk", "Call mom"] {
+        sqlx::query("insert into todos(title) values (?)").bind(title).execute(&pool).await.unwrap();
+    }
+
+    // 나머지 마이그레이션 적용 후에도 기존 행은 소유자 없이 남아 있음
+    repo.migrate().await.unwrap();
+    let ownerless: i64 = sqlx::query_scalar("select count(*) from todos where owner_id is null").fetch_one(&pool).await.unwrap();
+    assert_eq!(ownerless, 2);
+
+    // 관리자가 사용자에게 이관하면 그 사용자의 todo로 조회됨
+    let app = init_app_with_pool(pool.clone()).await;
+    let root = register_admin(&app, &pool, "root").await;
+    let uma = register_and_login(&app, "uma").await;
+    let req = test::TestRequest::post().uri("/api/admin/users/uma/legacy-todos")
+        .insert_header(("Authorization", format!("Bearer {}", root))).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["assigned_todos"], 2);
+
+    let req = test::TestRequest::get().uri("/api/todos?sort=title")
+        .insert_header(("Authorization", format!("Bearer {}", uma))).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let titles: Vec<&str> = resp["todos"].as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, ["Buy milk", "Call mom"]);
+}
+
+// Link 헤더에서 rel이 일치하는 링크 추출
+fn link_of(resp: &ServiceResponse, rel: &str) -> Option<String> {
+    let links = resp.headers().get("Link")?.to_str().ok()?;
+    links.split(", ").find(|l| l.ends_with(&format!("rel=\"{}\"", rel)))
+        .and_then(|l| Some(l[l.find('<')?+1..l.find('>')?].to_string()))
+}
+
+// todo 목록 필터, 정렬, 검색, 커서 기반 페이지 나누기(사용자에게 todo가 없는 상태에서 시작)
+async fn exercise_todo_list<S>(app: &S, token: &str)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    let bearer = ("Authorization", format!("Bearer {}", token));
+    let todos = [
+        serde_json::json!({"title": "alpha report", "notes": "quarterly numbers", "due_date": "2025-09-10", "tags": ["work"]}),
+        serde_json::json!({"title": "buy milk", "due_date": "2025-09-01", "tags": ["home", "errand", "home"]}),
+        serde_json::json!({"title": "call plumber", "tags": ["home"]}),
+        serde_json::json!({"title": "draft slides", "notes": "report for the board", "due_date": "2025-09-20", "tags": ["work"]}),
+        serde_json::json!({"title": "email bob", "due_date": "2025-09-05"}),
+    ];
+    let mut ids = Vec::new();
+    for todo in &todos {
+        let req = test::TestRequest::post().uri("/api/todos").insert_header(bearer.clone()).set_json(todo).to_request();
+        let created: serde_json::Value = test::call_and_read_body_json(app, req).await;
+        ids.push(created["id"].as_i64().unwrap());
+    }
+    let req = test::TestRequest::patch().uri(&format!("/api/todos/{}", ids[3])).insert_header(bearer.clone())
+        .set_json(serde_json::json!({"completed": true})).to_request();
+    let updated: serde_json::Value = test::call_and_read_body_json(app, req).await;
+    assert_eq!(updated["tags"], serde_json::json!(["work"]));
+    assert_eq!(updated["notes"], "report for the board");
+
+    let titles = |uri: &str| {
+        let req = test::TestRequest::get().uri(uri).insert_header(bearer.clone()).to_request();
+        async move {
+            let resp: serde_json::Value = test::call_and_read_body_json(app, req).await;
+            resp["todos"].as_array().unwrap().iter().map(|t| t["title"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(titles("/api/todos?status=completed").await, ["draft slides"]);
+    assert_eq!(titles("/api/todos?status=open&tag=home").await, ["buy milk", "call plumber"]);
+    assert_eq!(titles("/api/todos?due_from=2025-09-02&due_to=2025-09-10").await, ["alpha report", "email bob"]);
+    // 제목과 메모 전문 검색(검색식 연산자는 일반 단어로 취급)
+    assert_eq!(titles("/api/todos?q=report").await, ["alpha report", "draft slides"]);
+    assert_eq!(titles("/api/todos?q=Quarterly%20report").await, ["alpha report"]);
+    assert!(titles("/api/todos?q=report%20OR%20milk").await.is_empty());
+    // 마감일이 없는 todo는 정렬 방향과 관계없이 마지막
+    assert_eq!(titles("/api/todos?sort=due_date").await, ["buy milk", "email bob", "alpha report", "draft slides", "call plumber"]);
+    assert_eq!(titles("/api/todos?sort=-due_date").await, ["draft slides", "alpha report", "email bob", "buy milk", "call plumber"]);
+
+    // 커서 기반 페이지 나누기(Link 헤더로 다음/이전 페이지 이동)
+    let req = test::TestRequest::get().uri("/api/todos?sort=title&limit=2").insert_header(bearer.clone()).to_request();
+    let resp = test::call_service(app, req).await;
+    let next = link_of(&resp, "next").unwrap();
+    assert!(link_of(&resp, "prev").is_none());
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], 5);
+    assert_eq!(body["todos"][1]["tags"], serde_json::json!(["errand", "home"]));
+    assert!(body["prev_cursor"].is_null());
+    assert!(next.starts_with("/api/todos?sort=title&limit=2&cursor="));
+
+    let resp = test::call_service(app, test::TestRequest::get().uri(&next).insert_header(bearer.clone()).to_request()).await;
+    let last = link_of(&resp, "next").unwrap();
+    assert!(link_of(&resp, "prev").is_some());
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["todos"][0]["title"], "call plumber");
+    assert_eq!(body["todos"][1]["title"], "draft slides");
+
+    let resp = test::call_service(app, test::TestRequest::get().uri(&last).insert_header(bearer.clone()).to_request()).await;
+    assert!(link_of(&resp, "next").is_none());
+    let prev = link_of(&resp, "prev").unwrap();
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["todos"].as_array().unwrap().len(), 1);
+    assert_eq!(body["todos"][0]["title"], "email bob");
+    assert_eq!(titles(&prev).await, ["call plumber", "draft slides"]);
+
+    // 다른 정렬 기준의 커서나 잘못된 조건은 거부
+    let cursor = next.rsplit_once("cursor=").unwrap().1;
+    let req = test::TestRequest::get().uri(&format!("/api/todos?sort=-title&cursor={}", cursor)).insert_header(bearer.clone()).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(app, req).await;
+    assert_eq!(resp["code"], "invalid_cursor");
+    for query in ["status=done", "sort=priority", "due_from=tomorrow", "cursor=abc"] {
+        let req = test::TestRequest::get().uri(&format!("/api/todos?{}", query)).insert_header(bearer.clone()).to_request();
+        assert_eq!(test::call_service(app, req).await.status(), 400, "{}", query);
+    }
+
+    for id in ids {
+        let req = test::TestRequest::delete().uri(&format!("/api/todos/{}", id)).insert_header(bearer.clone()).to_request();
+        assert_eq!(test::call_service(app, req).await.status(), 204);
+    }
+    assert!(titles("/api/todos?q=report").await.is_empty());
+}
+
+#[actix_web::test]
+async fn test_todo_filters_pagination_and_search() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "todo_lister").await;
+    exercise_todo_list(&app, &token).await;
+}
+
+// 프로젝트, 하위 todo, 태그(사용자에게 todo, 프로젝트, 태그가 없는 상태에서 시작)
+async fn exercise_projects_and_tags<S>(app: &S, token: &str)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    let bearer = ("Authorization", format!("Bearer {}", token));
+    let call = |method: actix_web::http::Method, uri: String, body: Option<serde_json::Value>| {
+        let mut req = test::TestRequest::default().method(method).uri(&uri).insert_header(bearer.clone());
+        if let Some(body) = body {
+            req = req.set_json(body);
+        }
+        async move {
+            let resp = test::call_service(app, req.to_request()).await;
+            let status = resp.status().as_u16();
+            let body = test::read_body(resp).await;
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap_or(serde_json::Value::Null))
+        }
+    };
+    use actix_web::http::Method;
+    let titles = |body: &serde_json::Value| body["todos"].as_array().unwrap().iter()
+        .map(|t| t["title"].as_str().unwrap().to_string()).collect::<Vec<_>>();
+
+    // 프로젝트(사용자별 이름 유일)
+    let (status, home) = call(Method::POST, "/api/projects".into(), Some(serde_json::json!({"name": "Home"}))).await;
+    assert_eq!(status, 201);
+    let (_, work) = call(Method::POST, "/api/projects".into(), Some(serde_json::json!({"name": " Work "}))).await;
+    assert_eq!(work["name"], "Work");
+    let (status, body) = call(Method::POST, "/api/projects".into(), Some(serde_json::json!({"name": "Home"}))).await;
+    assert_eq!((status, body["code"].as_str()), (409, Some("project_exists")));
+    let (home, work) = (home["id"].as_i64().unwrap(), work["id"].as_i64().unwrap());
+
+    // 하위 todo는 상위 todo의 프로젝트를 따름
+    let (_, parent) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "plan trip", "project_id": home}))).await;
+    let parent = parent["id"].as_i64().unwrap();
+    let (_, child) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "book flights", "parent_id": parent}))).await;
+    assert_eq!(child["project_id"], home);
+    assert_eq!(child["parent_id"], parent);
+    let child = child["id"].as_i64().unwrap();
+    let (_, grandchild) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "compare prices", "parent_id": child}))).await;
+    let grandchild = grandchild["id"].as_i64().unwrap();
+
+    // 상위 todo와 다른 프로젝트, 존재하지 않는 프로젝트, 순환은 거부
+    let (status, body) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "x", "parent_id": parent, "project_id": work}))).await;
+    assert_eq!((status, body["code"].as_str()), (409, Some("project_mismatch")));
+    let (status, body) = call(Method::PATCH, format!("/api/todos/{}", child), Some(serde_json::json!({"project_id": work}))).await;
+    assert_eq!((status, body["code"].as_str()), (409, Some("project_mismatch")));
+    let (status, body) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "x", "project_id": 999999}))).await;
+    assert_eq!((status, body["code"].as_str()), (400, Some("invalid_project")));
+    for target in [parent, grandchild] {
+        let (status, body) = call(Method::PATCH, format!("/api/todos/{}", parent), Some(serde_json::json!({"parent_id": target}))).await;
+        assert_eq!((status, body["code"].as_str()), (409, Some("invalid_parent")));
+    }
+
+    // 상위 todo를 옮기면 하위 todo 전체가 함께 이동
+    let (status, _) = call(Method::PATCH, format!("/api/todos/{}", parent), Some(serde_json::json!({"project_id": work}))).await;
+    assert_eq!(status, 200);
+    let (_, body) = call(Method::GET, format!("/api/todos?project_id={}", work), None).await;
+    assert_eq!(titles(&body), ["plan trip", "book flights", "compare prices"]);
+    let (_, body) = call(Method::GET, format!("/api/todos?project_id={}&parent_id=none", work), None).await;
+    assert_eq!(titles(&body), ["plan trip"]);
+    let (_, body) = call(Method::GET, format!("/api/todos?parent_id={}", parent), None).await;
+    assert_eq!(titles(&body), ["book flights"]);
+
+    // 하위 todo를 최상위로 바꿔도 프로젝트는 유지, 다른 프로젝트로 이동 가능
+    let (_, body) = call(Method::PATCH, format!("/api/todos/{}", child), Some(serde_json::json!({"parent_id": null}))).await;
+    assert!(body["parent_id"].is_null());
+    assert_eq!(body["project_id"], work);
+    let (_, projects) = call(Method::GET, "/api/projects".into(), None).await;
+    assert_eq!(projects[0]["name"], "Home");
+    assert_eq!(projects[0]["todo_count"], 0);
+    assert_eq!(projects[1]["todo_count"], 3);
+
+    // 프로젝트 삭제: todo가 있으면 처리 방법 지정 필요
+    let (status, body) = call(Method::DELETE, format!("/api/projects/{}", work), None).await;
+    assert_eq!((status, body["code"].as_str()), (409, Some("project_not_empty")));
+    let (status, _) = call(Method::DELETE, format!("/api/projects/{}?todos=reassign&to={}", work, work), None).await;
+    assert_eq!(status, 400);
+    let (status, _) = call(Method::DELETE, format!("/api/projects/{}?todos=reassign&to={}", work, home), None).await;
+    assert_eq!(status, 204);
+    assert_eq!(call(Method::GET, format!("/api/projects/{}", work), None).await.0, 404);
+    let (_, body) = call(Method::GET, format!("/api/todos?project_id={}", home), None).await;
+    assert_eq!(body["total"], 3);
+    let (status, _) = call(Method::DELETE, format!("/api/projects/{}?todos=delete", home), None).await;
+    assert_eq!(status, 204);
+    let (_, body) = call(Method::GET, "/api/todos".into(), None).await;
+    assert_eq!(body["total"], 0);
+
+    // 상위 todo를 삭제하면 하위 todo도 삭제
+    let (_, parent) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "parent"}))).await;
+    let (_, child) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "child", "parent_id": parent["id"]}))).await;
+    assert_eq!(call(Method::DELETE, format!("/api/todos/{}", parent["id"]), None).await.0, 204);
+    assert_eq!(call(Method::GET, format!("/api/todos/{}", child["id"]), None).await.0, 404);
+
+    // 색상 태그
+    let (status, urgent) = call(Method::POST, "/api/tags".into(), Some(serde_json::json!({"name": "urgent", "color": "#FF0000"}))).await;
+    assert_eq!(status, 201);
+    assert_eq!(urgent["color"], "#ff0000");
+    let (status, body) = call(Method::POST, "/api/tags".into(), Some(serde_json::json!({"name": "urgent"}))).await;
+    assert_eq!((status, body["code"].as_str()), (409, Some("tag_exists")));
+    let (status, body) = call(Method::POST, "/api/tags".into(), Some(serde_json::json!({"name": "blue", "color": "blue"}))).await;
+    assert_eq!((status, body["code"].as_str()), (400, Some("invalid_color")));
+    let (_, todo) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "tagged", "tags": ["urgent", "later"]}))).await;
+    let (_, tags) = call(Method::GET, "/api/tags".into(), None).await;
+    let tag = |name: &str| tags.as_array().unwrap().iter().find(|t| t["name"]==name).unwrap().clone();
+    let later = tag("later");
+    assert_eq!(later, serde_json::json!({"id": later["id"], "name": "later", "color": "#9e9e9e", "todo_count": 1}));
+    assert_eq!(tag("urgent")["todo_count"], 1);
+    let (status, renamed) = call(Method::PATCH, format!("/api/tags/{}", urgent["id"]), Some(serde_json::json!({"name": "asap"}))).await;
+    assert_eq!(status, 200);
+    assert_eq!(renamed["color"], "#ff0000");
+    let (status, _) = call(Method::PATCH, format!("/api/tags/{}", urgent["id"]), Some(serde_json::json!({"name": "later"}))).await;
+    assert_eq!(status, 409);
+    assert_eq!(call(Method::DELETE, format!("/api/tags/{}", later["id"]), None).await.0, 204);
+    let (_, todo) = call(Method::GET, format!("/api/todos/{}", todo["id"]), None).await;
+    assert_eq!(todo["tags"], serde_json::json!(["asap"]));
+    assert_eq!(call(Method::DELETE, format!("/api/todos/{}", todo["id"]), None).await.0, 204);
+    assert_eq!(call(Method::DELETE, format!("/api/tags/{}", urgent["id"]), None).await.0, 204);
+}
+
+#[actix_web::test]
+async fn test_projects_subtasks_and_tags() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "project_owner").await;
+    exercise_projects_and_tags(&app, &token).await;
+}
+
+#[actix_web::test]
+async fn test_recurrence_rules() {
+    let date = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    let next = |rule: &Rule, due: &str| rule.next(date(due)).map(|(d, r)| (d.to_string(), r.to_string()));
+
+    // 간단한 이름과 RRULE은 같은 형식으로 정규화
+    assert_eq!(Rule::parse("daily").unwrap().to_string(), "FREQ=DAILY");
+    assert_eq!(Rule::parse("Weekdays").unwrap().to_string(), "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR");
+    assert_eq!(Rule::parse("rrule:freq=weekly;byday=fr,mo,fr;interval=2").unwrap().to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR");
+    assert_eq!(Rule::parse("FREQ=DAILY;UNTIL=20250905T000000Z").unwrap().to_string(), "FREQ=DAILY;UNTIL=20250905");
+
+    // 매일, 매주(요일 지정 시 같은 주의 다음 요일, 없으면 interval주 뒤의 첫 요일)
+    assert_eq!(next(&Rule::parse("FREQ=DAILY;INTERVAL=3").unwrap(), "2025-09-30"), Some(("2025-10-03".into(), "FREQ=DAILY;INTERVAL=3".into())));
+    assert_eq!(next(&Rule::parse("weekly").unwrap(), "2025-09-03").unwrap().0, "2025-09-10");
+    let biweekly = Rule::parse("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR").unwrap();
+    assert_eq!(next(&biweekly, "2025-09-01").unwrap().0, "2025-09-05");     // 월 -> 같은 주 금
+    assert_eq!(next(&biweekly, "2025-09-05").unwrap().0, "2025-09-15");     // 금 -> 2주 뒤 월
+    assert_eq!(next(&biweekly, "2025-09-03").unwrap().0, "2025-09-05");     // 지정하지 않은 요일(수)에서 시작
+
+    // 매월: 마감일의 날짜에 고정하여 짧은 달에서는 말일로 당긴 뒤 원래 날짜로 복귀
+    let monthly = Rule::parse("monthly").unwrap().anchored(date("2025-01-31"));
+    assert_eq!(monthly.to_string(), "FREQ=MONTHLY;BYMONTHDAY=31");
+    assert_eq!(next(&monthly, "2025-01-31").unwrap().0, "2025-02-28");
+    assert_eq!(next(&monthly, "2025-02-28").unwrap().0, "2025-03-31");
+    assert_eq!(next(&Rule::parse("FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=29").unwrap(), "2024-02-29").unwrap().0, "2025-02-28");
+
+    // COUNT는 남은 횟수(마지막 occurrence면 다음 없음), UNTIL을 넘으면 다음 없음
+    let counted = Rule::parse("FREQ=DAILY;COUNT=2").unwrap();
+    let (_, last) = counted.next(date("2025-09-01")).unwrap();
+    assert_eq!(last.to_string(), "FREQ=DAILY;COUNT=1");
+    assert_eq!(last.next(date("2025-09-02")), None);
+    let until = Rule::parse("FREQ=WEEKLY;UNTIL=20250910").unwrap();
+    assert_eq!(next(&until, "2025-09-03").unwrap().0, "2025-09-10");
+    assert_eq!(next(&until, "2025-09-10"), None);
+
+    // 지원하지 않는 규칙
+    for invalid in ["", "hourly", "FREQ=YEARLY", "INTERVAL=2", "FREQ=DAILY;INTERVAL=0", "FREQ=DAILY;BYDAY=MO",
+        "FREQ=WEEKLY;BYDAY=XX", "FREQ=WEEKLY;BYMONTHDAY=3", "FREQ=MONTHLY;BYMONTHDAY=32", "FREQ=DAILY;COUNT=2;UNTIL=20250101",
+        "FREQ=DAILY;UNTIL=2025-01-01", "FREQ=DAILY;BYSETPOS=1", "FREQ"] {
+        assert!(Rule::parse(invalid).is_err(), "{} should be rejected", invalid);
+    }
+}
+
+// 반복 todo, 마감 알림 이벤트 기록 및 전달(저장소의 다른 사용자에게 알림 설정된 todo가 없는 상태에서 시작)
+// notifications: 전달한 이벤트를 저장할 빈 디렉터리
+async fn exercise_recurrence_and_reminders<S>(app: &S, repo: &dyn Repository, token: &str, notifications: &std::path::Path)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    let bearer = ("Authorization", format!("Bearer {}", token));
+    let call = |method: actix_web::http::Method, uri: String, body: Option<serde_json::Value>| {
+        let mut req = test::TestRequest::default().method(method).uri(&uri).insert_header(bearer.clone());
+        if let Some(body) = body {
+            req = req.set_json(body);
+        }
+        async move {
+            let resp = test::call_service(app, req.to_request()).await;
+            let status = resp.status().as_u16();
+            let body = test::read_body(resp).await;
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap_or(serde_json::Value::Null))
+        }
+    };
+    use actix_web::http::Method;
+
+    // 반복 규칙은 RRULE로 정규화, 알림 시각은 마감일 0시(UTC) 기준
+    let (status, plants) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({
+        "title": "water plants", "due_date": "2025-09-01", "tags": ["home"],
+        "recurrence": "rrule:freq=daily;count=2", "reminder_offset_minutes": 540}))).await;
+    assert_eq!(status, 201);
+    assert_eq!(plants["recurrence"], "FREQ=DAILY;COUNT=2");
+    assert_eq!(plants["remind_at"], "2025-09-01 09:00:00");
+    let (_, rent) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({
+        "title": "pay rent", "due_date": "2999-01-01", "recurrence": "monthly", "reminder_offset_minutes": -60}))).await;
+    assert_eq!(rent["recurrence"], "FREQ=MONTHLY;BYMONTHDAY=1");
+    assert_eq!(rent["remind_at"], "2998-12-31 23:00:00");
+    let (_, plain) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({"title": "plain"}))).await;
+    assert!(plain["recurrence"].is_null() && plain["remind_at"].is_null());
+
+    // 반복과 알림은 마감일 필요
+    for (body, code) in [
+        (serde_json::json!({"title": "x", "recurrence": "daily"}), "invalid_recurrence"),
+        (serde_json::json!({"title": "x", "due_date": "2025-09-01", "recurrence": "FREQ=YEARLY"}), "invalid_recurrence"),
+        (serde_json::json!({"title": "x", "reminder_offset_minutes": 60}), "invalid_reminder"),
+        (serde_json::json!({"title": "x", "due_date": "2025-09-01", "reminder_offset_minutes": 1440}), "invalid_reminder"),
+    ] {
+        let (status, resp) = call(Method::POST, "/api/todos".into(), Some(body)).await;
+        assert_eq!((status, resp["code"].as_str()), (400, Some(code)));
+    }
+    let (status, resp) = call(Method::PATCH, format!("/api/todos/{}", rent["id"]), Some(serde_json::json!({"due_date": null}))).await;
+    assert_eq!((status, resp["code"].as_str()), (400, Some("invalid_recurrence")));
+
+    // 알림 시각이 지난 todo마다 한 번만 이벤트 기록
+    assert_eq!(reminder::emit_due(repo).await.unwrap(), 1);
+    assert_eq!(reminder::emit_due(repo).await.unwrap(), 0);
+    let (_, events) = call(Method::GET, "/api/events".into(), None).await;
+    assert_eq!(events["events"].as_array().unwrap().len(), 1);
+    let event = &events["events"][0];
+    assert_eq!((event["kind"].as_str(), event["todo_id"].as_i64()), (Some("reminder"), plants["id"].as_i64()));
+    assert_eq!((event["title"].as_str(), event["due_date"].as_str(), event["remind_at"].as_str()),
+        (Some("water plants"), Some("2025-09-01"), Some("2025-09-01 09:00:00")));
+    let after = events["next_after"].as_i64().unwrap();
+    assert_eq!(after, event["id"].as_i64().unwrap());
+
+    // 완료하면 반복 규칙이 다음 occurrence로 옮겨짐(COUNT 1 감소)
+    let (status, done) = call(Method::PATCH, format!("/api/todos/{}", plants["id"]), Some(serde_json::json!({"completed": true}))).await;
+    assert_eq!(status, 200);
+    assert_eq!(done["completed"], true);
+    assert!(done["recurrence"].is_null());
+    let next = &done["next_occurrence"];
+    assert_eq!((next["title"].as_str(), next["due_date"].as_str(), next["completed"].as_bool()), (Some("water plants"), Some("2025-09-02"), Some(false)));
+    assert_eq!((next["recurrence"].as_str(), next["reminder_offset_minutes"].as_i64()), (Some("FREQ=DAILY;COUNT=1"), Some(540)));
+    assert_eq!(next["tags"], serde_json::json!(["home"]));
+    // 이미 완료한 todo를 다시 완료해도 새로 만들지 않음
+    let (_, again) = call(Method::PATCH, format!("/api/todos/{}", plants["id"]), Some(serde_json::json!({"completed": true}))).await;
+    assert!(again.get("next_occurrence").is_none());
+
+    // 다음 occurrence의 알림, 마지막 occurrence를 완료하면 반복 종료
+    assert_eq!(reminder::emit_due(repo).await.unwrap(), 1);
+    let (_, last) = call(Method::PATCH, format!("/api/todos/{}", next["id"]), Some(serde_json::json!({"completed": true}))).await;
+    assert!(last.get("next_occurrence").is_none());
+    let (_, events) = call(Method::GET, format!("/api/events?after={}", after), None).await;
+    assert_eq!(events["events"].as_array().unwrap().len(), 1);
+    assert_eq!(events["events"][0]["due_date"], "2025-09-02");
+
+    // 마감일이나 알림 시각을 바꾸면 다시 알림
+    let (_, moved) = call(Method::PATCH, format!("/api/todos/{}", rent["id"]), Some(serde_json::json!({"due_date": "2025-01-15"}))).await;
+    assert_eq!(moved["remind_at"], "2025-01-14 23:00:00");
+    assert_eq!(moved["recurrence"], "FREQ=MONTHLY;BYMONTHDAY=15");
+    assert_eq!(reminder::emit_due(repo).await.unwrap(), 1);
+    call(Method::PATCH, format!("/api/todos/{}", rent["id"]), Some(serde_json::json!({"title": "pay rent!"}))).await;
+    assert_eq!(reminder::emit_due(repo).await.unwrap(), 0);
+    call(Method::PATCH, format!("/api/todos/{}", rent["id"]), Some(serde_json::json!({"reminder_offset_minutes": 0}))).await;
+    assert_eq!(reminder::emit_due(repo).await.unwrap(), 1);
+
+    // 전달하지 않은 이벤트를 기록순으로 notifier에 전달(한 번만)
+    let notifier = FileNotifier::new(notifications).unwrap();
+    assert_eq!(reminder::deliver(repo, &notifier).await.unwrap(), 4);
+    assert_eq!(reminder::deliver(repo, &notifier).await.unwrap(), 0);
+    let mut files: Vec<_> = std::fs::read_dir(notifications).unwrap().map(|e| e.unwrap().path()).collect();
+    files.sort();
+    assert_eq!(files.len(), 4);
+    let delivered: serde_json::Value = serde_json::from_slice(&std::fs::read(&files[0]).unwrap()).unwrap();
+    assert_eq!((delivered["id"].as_i64(), delivered["kind"].as_str()), (Some(after), Some("reminder")));
+    assert!(delivered["user_id"].is_i64());
+
+    // 반복 규칙 없이 마감일만 옮기면 매월 반복 날짜도 새 마감일로 바뀜(31일 -> 10일)
+    let (_, bills) = call(Method::POST, "/api/todos".into(), Some(serde_json::json!({
+        "title": "pay bills", "due_date": "2999-01-31", "recurrence": "FREQ=MONTHLY;COUNT=3"}))).await;
+    assert_eq!(bills["recurrence"], "FREQ=MONTHLY;BYMONTHDAY=31;COUNT=3");
+    let (status, bills) = call(Method::PATCH, format!("/api/todos/{}", bills["id"]), Some(serde_json::json!({"due_date": "2999-02-10"}))).await;
+    assert_eq!(status, 200);
+    assert_eq!(bills["recurrence"], "FREQ=MONTHLY;BYMONTHDAY=10;COUNT=3");
+    let (_, paid) = call(Method::PATCH, format!("/api/todos/{}", bills["id"]), Some(serde_json::json!({"completed": true}))).await;
+    let next_bills = &paid["next_occurrence"];
+    assert_eq!((next_bills["due_date"].as_str(), next_bills["recurrence"].as_str()), (Some("2999-03-10"), Some("FREQ=MONTHLY;BYMONTHDAY=10;COUNT=2")));
+    // 반복 규칙을 함께 보내면 보낸 규칙을 사용
+    let (_, moved) = call(Method::PATCH, format!("/api/todos/{}", next_bills["id"]), Some(serde_json::json!({
+        "due_date": "2999-04-05", "recurrence": "FREQ=MONTHLY;BYMONTHDAY=20"}))).await;
+    assert_eq!(moved["recurrence"], "FREQ=MONTHLY;BYMONTHDAY=20");
+
+    // todo를 삭제해도 이벤트는 남음
+    for todo in [&plants, next, &rent, &plain, &bills, next_bills] {
+        assert_eq!(call(Method::DELETE, format!("/api/todos/{}", todo["id"]), None).await.0, 204);
+    }
+    let (_, events) = call(Method::GET, "/api/events?limit=2".into(), None).await;
+    assert_eq!(events["events"].as_array().unwrap().len(), 2);
+    assert!(events["events"][0]["todo_id"].is_null());
+}
+
+// 전달에 실패한 이벤트는 다음 실행에서 다시 전달
+struct FlakyNotifier(std::sync::atomic::AtomicUsize);
+
+impl Notifier for FlakyNotifier {
+    fn notify(&self, _: &repository::TodoEvent) -> anyhow::Result<()> {
+        if self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst)==0 { anyhow::bail!("unreachable") } else { Ok(()) }
+    }
+}
+
+#[actix_web::test]
+async fn test_recurring_todos_and_reminders() {
+    let pool = temp_pool().await;
+    let repo = sqlite_repo(&pool);
+    let app = init_app_with_pool(pool.clone()).await;
+    let token = register_and_login(&app, "recurring_owner").await;
+    let notifications = std::env::temp_dir().join(format!("login-web-server-notifications-{}-sqlite", std::process::id()));
+    let _ = std::fs::remove_dir_all(&notifications);
+    exercise_recurrence_and_reminders(&app, repo.as_ref(), &token, &notifications).await;
+
+    // 알림 작업(시작 시 한 번 실행): 기록 후 전달
+    let bearer = ("Authorization", format!("Bearer {}", token));
+    let req = test::TestRequest::post().uri("/api/todos").insert_header(bearer)
+        .set_json(serde_json::json!({"title": "standup", "due_date": "2025-09-01", "recurrence": "weekdays", "reminder_offset_minutes": 0})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+    let flaky = FlakyNotifier(std::sync::atomic::AtomicUsize::new(0));
+    assert!(reminder::deliver(repo.as_ref(), &flaky).await.is_ok());
+    assert_eq!(reminder::emit_due(repo.as_ref()).await.unwrap(), 1);
+    assert!(reminder::deliver(repo.as_ref(), &flaky).await.is_err());
+    let notifier: Arc<dyn Notifier> = Arc::new(FileNotifier::new(&notifications).unwrap());
+    let jobs = reminder::register(Scheduler::new(), &repo, &notifier, std::time::Duration::from_secs(3600)).start();
+    actix_web::rt::time::sleep(std::time::Duration::from_millis(200)).await;
+    jobs.shutdown().await;
+    assert_eq!(std::fs::read_dir(&notifications).unwrap().count(), 5);
+
+    // 전달한 지 보관 기간이 지난 이벤트는 정리 작업에서 삭제
+    sqlx::query("update todo_events set delivered_at=datetime('now', '-8 days') where todo_id is null").execute(&pool).await.unwrap();
+    assert_eq!(reminder::prune_delivered(repo.as_ref()).await.unwrap(), 4);
+    let _ = std::fs::remove_dir_all(&notifications);
+}
+
+#[actix_web::test]
+async fn test_refresh_token_rotation_and_reuse_detection() {
+    let app = init_app().await;
+
+    register_verified(&app, "carol").await;
+    let info = serde_json::json!({"username": "carol", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let first = login["refresh_token"].as_str().unwrap().to_string();
+
+    // 교체 성공 시 새 access/refresh 토큰 발급
+    let req = test::TestRequest::post().uri("/api/token/refresh")
+        .set_json(serde_json::json!({"refresh_token": first})).to_request();
+    let refreshed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(refreshed["username"], "carol");
+    let second = refreshed["refresh_token"].as_str().unwrap().to_string();
+    assert_ne!(first, second);
+
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", refreshed["token"].as_str().unwrap()))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // 이미 사용된 토큰 재사용 시 거부되고 family 전체(second 포함) 폐기
+    let req = test::TestRequest::post().uri("/api/token/refresh")
+        .set_json(serde_json::json!({"refresh_token": first})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::post().uri("/api/token/refresh")
+        .set_json(serde_json::json!({"refresh_token": second})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_logout_revokes_only_current_session() {
+    let app = init_app().await;
+
+    // 같은 사용자가 두 기기에서 로그인
+    let laptop = register_and_login(&app, "dave").await;
+    let info = serde_json::json!({"username": "dave", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&info).to_request();
+    let phone: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let phone = phone["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::post().uri("/api/logout")
+        .insert_header(("Authorization", format!("Bearer {}", laptop))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // 로그아웃한 토큰만 거부되고 다른 기기의 토큰은 유효
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", laptop))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", phone))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+}
+
+#[actix_web::test]
+async fn test_list_and_revoke_sessions() {
+    let app = init_app().await;
+
+    let laptop = register_and_login(&app, "erin").await;
+    let info = serde_json::json!({"username": "erin", "password": "Passw0rd!", "device_label": "Erin's phone"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&info)
+        .insert_header(("User-Agent", "TestPhone/1.0")).to_request();
+    let phone: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let phone = phone["token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get().uri("/api/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", phone))).to_request();
+    let sessions: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let sessions = sessions.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    let current: Vec<_> = sessions.iter().filter(|s| s["current"] == true).collect();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0]["device_label"], "Erin's phone");
+    assert_eq!(current[0]["user_agent"], "TestPhone/1.0");
+
+    // 현재 세션(phone)을 제외한 모든 세션 폐기 -> laptop 토큰 거부
+    let req = test::TestRequest::delete().uri("/api/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", phone))).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["revoked"], 1);
+
+    let req = test::TestRequest::get().uri("/api/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", laptop))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    // 다른 사용자의 세션은 폐기할 수 없음
+    let frank = register_and_login(&app, "frank").await;
+    let phone_session = current[0]["id"].as_str().unwrap();
+    let req = test::TestRequest::delete().uri(&format!("/api/sessions/{}", phone_session))
+        .insert_header(("Authorization", format!("Bearer {}", frank))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn test_login_lockout_and_admin_unlock() {
+    let pool = temp_pool().await;
+    let app = init_app_with_pool(pool.clone()).await;
+
+    let root = register_admin(&app, &pool, "root").await;
+    register_and_login(&app, "grace").await;
+
+    // 연속 5회 실패 시 잠금
+    let wrong = serde_json::json!({"username": "grace", "password": "wrong"});
+    for _ in 0..5 {
+        let req = test::TestRequest::post().uri("/api/login").set_json(&wrong).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+
+    // 잠금 중에는 올바른 비밀번호도 429 + Retry-After
+    let right = serde_json::json!({"username": "grace", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&right).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 429);
+    let retry_after: u64 = resp.headers().get("Retry-After").unwrap().to_str().unwrap().parse().unwrap();
+    assert!(retry_after > 0 && retry_after <= 30);
+
+    // 관리자가 아니면 잠금 해제 불가
+    let heidi = register_and_login(&app, "heidi").await;
+    let req = test::TestRequest::post().uri("/api/admin/users/grace/unlock")
+        .insert_header(("Authorization", format!("Bearer {}", heidi))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+
+    let req = test::TestRequest::post().uri("/api/admin/users/grace/unlock")
+        .insert_header(("Authorization", format!("Bearer {}", root))).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["unlocked"], true);
+
+    let req = test::TestRequest::post().uri("/api/login").set_json(&right).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+}
+
+#[actix_web::test]
+async fn test_password_policy_violations_and_change_password() {
+    // SHA-1("Summer2024!")를 포함한 유출 비밀번호 목록
+    let path = std::env::temp_dir().join(format!("breached-{}.txt", std::process::id()));
+    std::fs::write(&path, "# test list\n7E8B0A3433F1210A9699D85420E363A1B162ECAC:42\n").unwrap();
+    let policy = PasswordPolicy {
+        breach_source: Some(Arc::new(LocalBreachList::load(path.to_str().unwrap()).unwrap())),
+        ..PasswordPolicy::default()
+    };
+    std::fs::remove_file(&path).unwrap();
+    let app = init_app_with_policy(policy).await;
+
+    // 위반한 규칙마다 항목이 반환됨
+    let req = test::TestRequest::post().uri("/api/register")
+        .set_json(serde_json::json!({"username": "ivan", "password": "ivan", "email": email_of("ivan")})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "password_policy_violation");
+    let rules: Vec<&str> = body["violations"].as_array().unwrap().iter().map(|v| v["rule"].as_str().unwrap()).collect();
+    assert_eq!(rules, vec!["min_length", "uppercase", "special", "entropy", "contains_username"]);
+
+    let req = test::TestRequest::post().uri("/api/register")
+        .set_json(serde_json::json!({"username": "ivan", "password": "Summer2024!", "email": email_of("ivan")})).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["violations"][0]["rule"], "breached");
+
+    // 최대 길이는 바이트 단위(30글자지만 한글이라 90바이트)
+    let rules: Vec<&str> = PasswordPolicy::default().check("ivan", &format!("Aa1!{}", "가".repeat(26))).iter().map(|v| v.rule).collect();
+    assert_eq!(rules, vec!["max_length"]);
+
+    // 비밀번호 변경도 같은 정책 적용
+    let token = register_and_login(&app, "ivan").await;
+    let req = test::TestRequest::post().uri("/api/password/change")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({"current_password": "Passw0rd!", "new_password": "Summer2024!"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    // 다른 기기의 세션은 비밀번호 변경 시 폐기되고 현재 세션은 유지
+    let req = test::TestRequest::post().uri("/api/login")
+        .set_json(serde_json::json!({"username": "ivan", "password": "Passw0rd!"})).to_request();
+    let other: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+    let req = test::TestRequest::post().uri("/api/password/change")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({"current_password": "Passw0rd!", "new_password": "N3w-Secret!"})).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["revoked_sessions"], 1);
+
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", other["token"].as_str().unwrap()))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::post().uri("/api/login")
+        .set_json(serde_json::json!({"username": "ivan", "password": "N3w-Secret!"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+}
+
+#[actix_web::test]
+async fn test_password_reset_flow() {
+    let pool = temp_pool().await;
+    let app = init_app_with_pool(pool.clone()).await;
+    let token = register_and_login(&app, "judy").await;
+
+    // 존재하지 않는 사용자도 같은 응답(메일은 발송되지 않음)
+    let req = test::TestRequest::post().uri("/api/password/reset/request")
+        .set_json(serde_json::json!({"username": "nobody"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    assert!(read_mail_token("nobody", "Reset token").is_none());
+
+    let req = test::TestRequest::post().uri("/api/password/reset/request")
+        .set_json(serde_json::json!({"username": "judy"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // 가입 시 등록한 주소로 온 메일에서 재설정 토큰 추출
+    let reset_token = read_mail_token(&email_of("judy"), "Reset token").unwrap();
+
+    // 정책 위반 시 토큰은 소모되지 않음
+    let req = test::TestRequest::post().uri("/api/password/reset/confirm")
+        .set_json(serde_json::json!({"token": reset_token, "new_password": "weak"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::post().uri("/api/password/reset/confirm")
+        .set_json(serde_json::json!({"token": reset_token, "new_password": "R3set-Secret!"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // 일회용이므로 재사용 불가, 기존 세션은 모두 폐기됨
+    let req = test::TestRequest::post().uri("/api/password/reset/confirm")
+        .set_json(serde_json::json!({"token": reset_token, "new_password": "An0ther-Secret!"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::post().uri("/api/login")
+        .set_json(serde_json::json!({"username": "judy", "password": "R3set-Secret!"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // 메일 발송에 실패해도 없는 사용자와 같은 응답
+    let failing = init_app_with(pool, PasswordPolicy::default(), Arc::new(FailingMailer)).await;
+    let req = test::TestRequest::post().uri("/api/password/reset/request")
+        .set_json(serde_json::json!({"username": "judy"})).to_request();
+    let resp = test::call_service(&failing, req).await;
+    assert!(resp.status().is_success());
+
+    // 사용자 이름 기준 요청 횟수 제한은 존재 여부와 관계없이 적용(15분에 3회)
+    for expected in [200, 200, 429] {
+        let req = test::TestRequest::post().uri("/api/password/reset/request")
+            .set_json(serde_json::json!({"username": "nobody"})).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), expected);
+    }
+}
+
+// 항상 발송에 실패하는 메일 발송기
+struct FailingMailer;
+
+impl Mailer for FailingMailer {
+    fn send(&self, _email: &Email) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("smtp unavailable"))
+    }
+}
+
+// 현재 시각 기준 skew 단계만큼 이동한 시간 단계의 TOTP 코드
+fn totp_code(secret: &str, skew: i64) -> String {
+    let key = data_encoding::BASE32_NOPAD.decode(secret.as_bytes()).unwrap();
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    let step = (now/totp::PERIOD_SECS) as i64 + skew;
+    format!("{:06}", totp::code_at(&key, step as u64))
+}
+
+#[actix_web::test]
+async fn test_totp_rfc6238_vectors() {
+    // RFC 6238 부록 B의 SHA-1 테스트 벡터(8자리 코드의 하위 6자리)
+    let key = b"12345678901234567890";
+    assert_eq!(totp::code_at(key, 59/totp::PERIOD_SECS), 287082);
+    assert_eq!(totp::code_at(key, 1111111109/totp::PERIOD_SECS), 81804);
+    assert_eq!(totp::code_at(key, 2000000000/totp::PERIOD_SECS), 279037);
+}
+
+#[actix_web::test]
+async fn test_data_key_encryption() {
+    let key = DataKey::ephemeral().unwrap();
+    let encrypted = key.encrypt("JBSWY3DPEHPK3PXP", "user_totp:1").unwrap();
+    assert!(encrypted.starts_with("enc:v1:") && !encrypted.contains("JBSWY3DPEHPK3PXP"));
+    assert_eq!(key.decrypt(&encrypted, "user_totp:1").unwrap(), "JBSWY3DPEHPK3PXP");
+    // 다른 행에 복사했거나 다른 키로는 복호화 불가
+    assert!(key.decrypt(&encrypted, "user_totp:2").is_err());
+    assert!(DataKey::ephemeral().unwrap().decrypt(&encrypted, "user_totp:1").is_err());
+    // 암호화 도입 이전의 평문 값은 그대로 읽음
+    assert_eq!(key.decrypt("JBSWY3DPEHPK3PXP", "user_totp:1").unwrap(), "JBSWY3DPEHPK3PXP");
+}
+
+#[actix_web::test]
+async fn test_totp_enrollment_and_mfa_login() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "kate").await;
+
+    let req = test::TestRequest::post().uri("/api/mfa/totp/enroll")
+        .insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    let enrollment: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let secret = enrollment["secret"].as_str().unwrap().to_string();
+    assert!(enrollment["otpauth_uri"].as_str().unwrap().starts_with("otpauth://totp/Toy_Project:kate?secret="));
+    assert!(enrollment["qr_svg"].as_str().unwrap().contains("<svg"));
+
+    // 확인 전에는 로그인에 적용되지 않음
+    let login_info = serde_json::json!({"username": "kate", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login_info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(login["token"].is_string());
+
+    let first_code = totp_code(&secret, 0);
+    let req = test::TestRequest::post().uri("/api/mfa/totp/confirm")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({"code": first_code})).to_request();
+    let confirmed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let recovery_codes: Vec<String> = confirmed["recovery_codes"].as_array().unwrap().iter()
+        .map(|c| c.as_str().unwrap().to_string()).collect();
+    assert_eq!(recovery_codes.len(), 10);
+    assert!(recovery_codes.iter().all(|c| c.len()==21));   // 10바이트(hex 20자) + 하이픈
+
+    // 활성화 후에는 access 토큰 대신 challenge 토큰 발급, challenge 토큰으로는 API 접근 불가
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login_info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(login["mfa_required"], true);
+    assert!(login["token"].is_null());
+    let challenge = login["challenge_token"].as_str().unwrap().to_string();
+
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", challenge))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    // 확인에 사용한 코드는 재사용 불가
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": challenge, "code": first_code})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": challenge, "code": totp_code(&secret, 1)})).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["username"], "kate");
+    assert!(resp["token"].is_string());
+
+    // challenge 토큰은 일회용
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": challenge, "code": recovery_codes[0]})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    // 복구 코드도 한 번만 사용 가능
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login_info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": login["challenge_token"], "code": recovery_codes[0].to_uppercase()})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login_info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": login["challenge_token"], "code": recovery_codes[0]})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    let req = test::TestRequest::get().uri("/api/mfa")
+        .insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    let status: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(status["enabled"], true);
+    assert_eq!(status["recovery_codes_remaining"], 9);
+}
+
+#[actix_web::test]
+async fn test_mfa_code_failures_survive_password_relogin() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "liam").await;
+
+    let req = test::TestRequest::post().uri("/api/mfa/totp/enroll")
+        .insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    let enrollment: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let secret = enrollment["secret"].as_str().unwrap().to_string();
+    // 확인에 사용한 코드는 다시 쓸 수 없으므로 이후 틀린 코드로 사용
+    let used_code = totp_code(&secret, 0);
+    let req = test::TestRequest::post().uri("/api/mfa/totp/confirm")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({"code": used_code})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    let login_info = serde_json::json!({"username": "liam", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login_info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let first = login["challenge_token"].as_str().unwrap().to_string();
+    for _ in 0..4 {
+        let req = test::TestRequest::post().uri("/api/login/mfa")
+            .set_json(serde_json::json!({"challenge_token": first, "code": used_code})).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 401);
+    }
+
+    // 비밀번호로 다시 로그인해도 코드 실패 횟수는 초기화되지 않음
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login_info).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let second = login["challenge_token"].as_str().unwrap().to_string();
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": second, "code": used_code})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 429);
+    assert!(resp.headers().get("Retry-After").is_some());
+
+    // 잠금 중에는 올바른 코드도 거부
+    let req = test::TestRequest::post().uri("/api/login/mfa")
+        .set_json(serde_json::json!({"challenge_token": second, "code": totp_code(&secret, 1)})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 429);
+}
+
+#[actix_web::test]
+async fn test_errors_are_problem_json() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "leo").await;
+
+    // 응답 Content-Type과 RFC 7807 필드, 고정 code 확인
+    async fn problem<S>(app: &S, req: actix_http::Request, status: u16, code: &str) -> serde_json::Value
+    where
+        S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+    {
+        let resp = test::call_service(app, req).await;
+        assert_eq!(resp.status(), status);
+        assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/problem+json");
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["status"], status);
+        assert_eq!(body["code"], code);
+        assert!(body["title"].is_string() && body["detail"].is_string());
+        body
+    }
+
+    let req = test::TestRequest::post().uri("/api/login")
+        .set_json(serde_json::json!({"username": "leo", "password": "wrong"})).to_request();
+    problem(&app, req, 401, "invalid_credentials").await;
+
+    let req = test::TestRequest::post().uri("/api/register")
+        .set_json(serde_json::json!({"username": "leo", "password": "Passw0rd!", "email": "leo2@example.com"})).to_request();
+    problem(&app, req, 409, "username_taken").await;
+
+    let req = test::TestRequest::post().uri("/api/login")
+        .insert_header(("Content-Type", "application/json")).set_payload("{\"username\":").to_request();
+    problem(&app, req, 400, "invalid_request_body").await;
+
+    let req = test::TestRequest::get().uri("/api/todos").to_request();
+    problem(&app, req, 401, "missing_token").await;
+
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", "Bearer not-a-jwt")).to_request();
+    problem(&app, req, 401, "invalid_token").await;
+
+    let req = test::TestRequest::get().uri("/api/todos?limit=abc")
+        .insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    problem(&app, req, 400, "invalid_query").await;
+}
+
+#[actix_web::test]
+async fn test_rbac_admin_api() {
+    let pool = temp_pool().await;
+    let app = init_app_with_pool(pool.clone()).await;
+
+    let admin = register_admin(&app, &pool, "mallory").await;
+    let user = register_and_login(&app, "nina").await;
+
+    // 일반 사용자는 관리 API 접근 불가(권한 이름과 함께 403)
+    let req = test::TestRequest::get().uri("/api/admin/users")
+        .insert_header(("Authorization", format!("Bearer {}", user))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "permission_required");
+
+    let req = test::TestRequest::get().uri("/api/admin/users?limit=10")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["users"][0]["roles"], serde_json::json!(["admin", "user"]));
+    assert_eq!(body["users"][1]["roles"], serde_json::json!(["user"]));
+
+    // 강제 로그아웃 시 기존 토큰 거부
+    let req = test::TestRequest::post().uri("/api/admin/users/nina/logout")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["revoked_sessions"], 1);
+    let req = test::TestRequest::get().uri("/api/todos")
+        .insert_header(("Authorization", format!("Bearer {}", user))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+
+    // 비활성화된 계정은 로그인 불가, 다시 활성화하면 가능
+    let nina = serde_json::json!({"username": "nina", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/admin/users/nina/disable")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let req = test::TestRequest::post().uri("/api/login").set_json(&nina).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+
+    let req = test::TestRequest::post().uri("/api/admin/users/nina/enable")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+    let req = test::TestRequest::post().uri("/api/login").set_json(&nina).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert!(resp.status().is_success());
+
+    // 역할 부여는 다시 로그인한 토큰부터 반영, 역할 회수는 세션을 폐기하므로 기존 토큰도 즉시 거부
+    let req = test::TestRequest::put().uri("/api/admin/users/nina/roles/admin")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::post().uri("/api/login").set_json(&nina).to_request();
+    let login: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let nina_admin = ("Authorization", format!("Bearer {}", login["token"].as_str().unwrap()));
+    let req = test::TestRequest::get().uri("/api/admin/users").insert_header(nina_admin.clone()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::delete().uri("/api/admin/users/nina/roles/admin")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get().uri("/api/admin/users").insert_header(nina_admin).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // 관리자는 자기 자신을 삭제할 수 없음
+    let req = test::TestRequest::delete().uri("/api/admin/users/mallory")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+
+    let req = test::TestRequest::delete().uri("/api/admin/users/nina")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 204);
+    let req = test::TestRequest::post().uri("/api/login").set_json(&nina).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_jwks_and_key_rotation() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "olivia").await;
+
+    // 토큰 헤더의 kid가 JWKS에 공개됨
+    let kid = jsonwebtoken::decode_header(&token).unwrap().kid.unwrap();
+    let req = test::TestRequest::get().uri("/.well-known/jwks.json").to_request();
+    let jwks: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let key = jwks["keys"].as_array().unwrap().iter().find(|k| k["kid"]==kid.as_str()).unwrap();
+    assert_eq!(key["kty"], "OKP");
+    assert_eq!(key["alg"], "EdDSA");
+    assert!(key.get("d").is_none());    // 개인 키는 노출되지 않음
+
+    // 키 디렉터리에 새 키를 추가하면 새 키로 서명하고, 이전 키로 서명된 토큰도 계속 검증
+    let dir = std::env::temp_dir().join(format!("jwt-keys-{}", std::process::id()));
+    let old_keys = KeySet::load_or_generate(&dir, None).unwrap();
+    let old = old_keys.signing_key();
+    let mut header = jsonwebtoken::Header::new(old.algorithm);
+    header.kid = Some(old.kid.clone());
+    let claims = serde_json::json!({"sub": "olivia", "exp": 4102444800u64});
+    let old_token = jsonwebtoken::encode(&header, &claims, &old.encoding).unwrap();
+
+    // 자동 생성된 개인 키와 data.key는 소유자만 읽을 수 있음
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        DataKey::load_or_generate(&dir).unwrap();
+        for path in [dir.join(format!("{}.pem", old.kid)), dir.join("data.key")] {
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    std::fs::write(dir.join("99999999999999-new.pem"), login_web_server::keys::generate_ed25519_pem().unwrap()).unwrap();
+    let new_keys = KeySet::load_or_generate(&dir, None).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(new_keys.signing_key().kid, "99999999999999-new");
+    assert_eq!(new_keys.jwks().keys.len(), 2);
+
+    let key = new_keys.verification_key(&old.kid).unwrap();
+    let decoded = jsonwebtoken::decode::<serde_json::Value>(&old_token, &key.decoding, &jsonwebtoken::Validation::new(key.algorithm)).unwrap();
+    assert_eq!(decoded.claims["sub"], "olivia");
+}
+
+#[actix_web::test]
+async fn test_token_claims_and_validation() {
+    let app = init_app().await;
+    let token = register_and_login(&app, "peter").await;
+
+    // 발급자, 대상, 발급/사용 가능 시간, 사용자 id 클레임 포함
+    let claims = login_web_server::auth::decode_claims(&token).unwrap();
+    let settings = &login_web_server::config::get().tokens;
+    assert_eq!(claims.iss, settings.issuer);
+    assert_eq!(claims.aud, settings.audience);
+    assert!(claims.iat<=claims.nbf && claims.nbf<claims.exp);
+    let auth = login_web_server::auth::decode_jwt(&token).unwrap();
+    assert_eq!(auth.username, "peter");
+    assert_eq!(auth.user_id, claims.uid);
+    assert!(auth.has_role(rbac::DEFAULT_ROLE));
+
+    // 같은 키로 다시 서명한 토큰은 허용되지만, 대상 또는 발급자가 다르거나 사용 가능 시간 이전이면 거부
+    let keys = login_web_server::auth::key_set().unwrap();
+    let sign = |claims: &login_web_server::auth::Claims| {
+        let key = keys.signing_key();
+        let mut header = jsonwebtoken::Header::new(key.algorithm);
+        header.kid = Some(key.kid.clone());
+        jsonwebtoken::encode(&header, claims, &key.encoding).unwrap()
+    };
+    let later = claims.nbf+settings.leeway_secs as usize+600;
+    let cases = [
+        (claims.clone(), 200),
+        (login_web_server::auth::Claims { aud: "other_service".to_string(), ..claims.clone() }, 401),
+        (login_web_server::auth::Claims { iss: "someone_else".to_string(), ..claims.clone() }, 401),
+        (login_web_server::auth::Claims { nbf: later, ..claims.clone() }, 401),
+    ];
+    for (forged, status) in cases {
+        let req = test::TestRequest::get().uri("/api/todos")
+            .insert_header(("Authorization", format!("Bearer {}", sign(&forged)))).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), status);
+        if status==401 {
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["code"], "invalid_token");
+        }
+    }
+}
+
+// 인가 요청 파라미터와 사용자 이름, 비밀번호로 로그인 폼 제출(POST /oauth/authorize)
+fn authorize_form(username: &str, password: &str, query: &str) -> Vec<(String, String)> {
+    let mut form: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+    form.push(("username".to_string(), username.to_string()));
+    form.push(("password".to_string(), password.to_string()));
+    form
+}
+
+// 인가 화면에서 로그인 후 redirect Location의 쿼리 파라미터 반환(스텁 클라이언트의 redirect_uri 처리)
+async fn authorize_redirect<S>(app: &S, username: &str, query: &str) -> std::collections::HashMap<String, String>
+where
+    S: Service<actix_http::Request, Response = ServiceResponse, Error = actix_web::Error>,
+{
+    let req = test::TestRequest::post().uri("/oauth/authorize").set_form(authorize_form(username, "Passw0rd!", query)).to_request();
+    let resp = test::call_service(app, req).await;
+    assert_eq!(resp.status(), 302);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap();
+    assert!(location.starts_with("http://localhost:9000/callback?"));
+    url::Url::parse(location).unwrap().query_pairs().into_owned().collect()
+}
+
+#[actix_web::test]
+async fn test_oauth_authorization_code_flow_with_pkce() {
+    let pool = temp_pool().await;
+    let app = init_app_with_pool(pool.clone()).await;
+    let admin = register_admin(&app, &pool, "quinn").await;
+    let user = register_and_login(&app, "rachel").await;
+
+    // 관리자가 공개 클라이언트(스텁 앱) 등록
+    let req = test::TestRequest::post().uri("/api/admin/oauth/clients")
+        .insert_header(("Authorization", format!("Bearer {}", admin)))
+        .set_json(serde_json::json!({"name": "Stub App", "redirect_uris": ["http://localhost:9000/callback"]})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let client: serde_json::Value = test::read_body_json(resp).await;
+    let client_id = client["client_id"].as_str().unwrap().to_string();
+    assert!(client.get("client_secret").is_none());
+    // 일반 사용자는 클라이언트를 등록할 수 없음
+    let req = test::TestRequest::get().uri("/api/admin/oauth/clients")
+        .insert_header(("Authorization", format!("Bearer {}", user))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+    // discovery 문서
+    let req = test::TestRequest::get().uri("/.well-known/openid-configuration").to_request();
+    let config: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(config["issuer"], login_web_server::config::get().tokens.issuer.as_str());
+    assert!(config["token_endpoint"].as_str().unwrap().ends_with("/oauth/token"));
+    assert_eq!(config["code_challenge_methods_supported"], serde_json::json!(["S256"]));
+
+    // 등록되지 않은 redirect_uri는 redirect 없이 400
+    let req = test::TestRequest::get().uri(&format!("/oauth/authorize?response_type=code&client_id={}&redirect_uri=http://evil.example/cb", client_id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+    // PKCE 없는 요청은 redirect_uri로 에러 전달
+    let params = authorize_redirect(&app, "rachel", &format!("response_type=code&client_id={}&state=xyz", client_id)).await;
+    assert_eq!(params["error"], "invalid_request");
+    assert_eq!(params["state"], "xyz");
+
+    // 인가 코드 발급
+    let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    let challenge = login_web_server::oauth::s256_challenge(verifier);
+    assert_eq!(challenge, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");   // RFC 7636 부록 B
+    let query = format!("response_type=code&client_id={}&redirect_uri=http://localhost:9000/callback&scope=openid%20profile&state=abc&nonce=n-0S6&code_challenge={}&code_challenge_method=S256", client_id, challenge);
+    // 브라우저는 Bearer 토큰 없이 로그인 폼을 받음(인가 요청 값은 hidden 필드, frame 표시 금지)
+    let req = test::TestRequest::get().uri(&format!("/oauth/authorize?{}", query)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("X-Frame-Options").unwrap(), "DENY");
+    let page = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(page.contains("Sign in to Stub App"));
+    assert!(page.contains(&format!("name=\"code_challenge\" value=\"{}\"", challenge)));
+    // 잘못된 비밀번호는 redirect 없이 에러와 함께 폼을 다시 표시
+    let req = test::TestRequest::post().uri("/oauth/authorize").set_form(authorize_form("rachel", "wrong", &query)).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+    assert!(resp.headers().get("Location").is_none());
+    let page = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(page.contains("Invalid username or password."));
+    let params = authorize_redirect(&app, "rachel", &query).await;
+    assert_eq!(params["state"], "abc");
+    let code = params["code"].clone();
+
+    // 잘못된 code_verifier로는 교환 불가(코드도 소모됨)
+    let wrong_verifier = "x".repeat(43);
+    let form = [("grant_type", "authorization_code"), ("code", code.as_str()), ("redirect_uri", "http://localhost:9000/callback"),
+        ("client_id", client_id.as_str()), ("code_verifier", wrong_verifier.as_str())];
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "invalid_grant");
+
+    // 새 코드를 올바른 code_verifier로 토큰 교환
+    let code = authorize_redirect(&app, "rachel", &query).await["code"].clone();
+    let form = [("grant_type", "authorization_code"), ("code", code.as_str()), ("redirect_uri", "http://localhost:9000/callback"),
+        ("client_id", client_id.as_str()), ("code_verifier", verifier)];
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-store");
+    let tokens: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(tokens["token_type"], "Bearer");
+    assert_eq!(tokens["scope"], "openid profile");
+
+    // id_token은 JWKS의 공개 키로 검증 가능하며 aud는 client_id
+    let id_token = tokens["id_token"].as_str().unwrap();
+    let kid = jsonwebtoken::decode_header(id_token).unwrap().kid.unwrap();
+    let key = login_web_server::auth::key_set().unwrap().verification_key(&kid).unwrap();
+    let mut validation = jsonwebtoken::Validation::new(key.algorithm);
+    validation.set_audience(&[&client_id]);
+    let id_claims = jsonwebtoken::decode::<login_web_server::auth::IdTokenClaims>(id_token, &key.decoding, &validation).unwrap().claims;
+    assert_eq!(id_claims.nonce.as_deref(), Some("n-0S6"));
+    assert_eq!(id_claims.preferred_username.as_deref(), Some("rachel"));
+
+    // 같은 코드는 다시 사용할 수 없음
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // access 토큰으로 userinfo 조회(sub는 id_token과 동일, 역할은 제공하지 않음)
+    let access_token = tokens["access_token"].as_str().unwrap();
+    let req = test::TestRequest::get().uri("/oauth/userinfo")
+        .insert_header(("Authorization", format!("Bearer {}", access_token))).to_request();
+    let info: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(info["sub"], id_claims.sub.as_str());
+    assert_eq!(info["preferred_username"], "rachel");
+    assert!(info.get("roles").is_none());
+    // 클라이언트의 access 토큰은 승인된 scope만 담으므로 이 서버의 API에는 사용 불가
+    let req = test::TestRequest::get().uri("/api/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", access_token))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "insufficient_scope");
+    // 토큰 검증 API도 같은 기준으로 판단
+    let req = test::TestRequest::post().uri("/api/auth/verify-token")
+        .set_json(serde_json::json!({"token": access_token})).to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["valid"], false);
+    // 세션 목록에 앱 이름 표시
+    let req = test::TestRequest::get().uri("/api/sessions")
+        .insert_header(("Authorization", format!("Bearer {}", user))).to_request();
+    let sessions: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(sessions.as_array().unwrap().iter().any(|s| s["device_label"]=="Stub App" && s["current"]==false));
+
+    // 다른 클라이언트나 /api/token/refresh로는 refresh 토큰을 교체할 수 없음(토큰은 소모되지 않음)
+    let req = test::TestRequest::post().uri("/api/admin/oauth/clients")
+        .insert_header(("Authorization", format!("Bearer {}", admin)))
+        .set_json(serde_json::json!({"name": "Other App", "redirect_uris": ["http://localhost:9001/callback"]})).to_request();
+    let other: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let refresh_token = tokens["refresh_token"].as_str().unwrap();
+    let form = [("grant_type", "refresh_token"), ("refresh_token", refresh_token), ("client_id", other["client_id"].as_str().unwrap())];
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "invalid_grant");
+    let req = test::TestRequest::post().uri("/api/token/refresh")
+        .set_json(serde_json::json!({"refresh_token": refresh_token})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // 발급받은 클라이언트는 refresh 토큰 교체 가능(scope 유지)
+    let form = [("grant_type", "refresh_token"), ("refresh_token", refresh_token), ("client_id", client_id.as_str())];
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    let refreshed: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(refreshed["access_token"].is_string());
+    assert_eq!(refreshed["scope"], "openid profile");
+
+    // 클라이언트 삭제 시 발급된 토큰도 사용 불가
+    let req = test::TestRequest::delete().uri(&format!("/api/admin/oauth/clients/{}", client_id))
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get().uri("/oauth/userinfo")
+        .insert_header(("Authorization", format!("Bearer {}", refreshed["access_token"].as_str().unwrap()))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+    let form = [("grant_type", "refresh_token"), ("refresh_token", refreshed["refresh_token"].as_str().unwrap()), ("client_id", other["client_id"].as_str().unwrap())];
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // 알 수 없는 클라이언트는 401 invalid_client
+    let form = [("grant_type", "authorization_code"), ("code", "x"), ("client_id", "unknown")];
+    let req = test::TestRequest::post().uri("/oauth/token").set_form(form).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 401);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "invalid_client");
+}
+
+#[actix_web::test]
+async fn test_email_verification() {
+    let app = init_app().await;
+    let info = serde_json::json!({"username": "sam", "password": "Passw0rd!", "email": " Sam@Example.com "});
+    let req = test::TestRequest::post().uri("/api/register").set_json(&info).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    // 같은 주소(대소문자 무관)로는 다시 가입할 수 없고, 형식이 잘못된 주소는 거부
+    let req = test::TestRequest::post().uri("/api/register")
+        .set_json(serde_json::json!({"username": "sam2", "password": "Passw0rd!", "email": "sam@example.COM"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "email_taken");
+    let req = test::TestRequest::post().uri("/api/register")
+        .set_json(serde_json::json!({"username": "sam3", "password": "Passw0rd!", "email": "not-an-email"})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // 인증 전에는 로그인 불가
+    let login = serde_json::json!({"username": "sam", "password": "Passw0rd!"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "email_not_verified");
+
+    // 가입 직후 재발송 요청은 횟수 제한(429, Retry-After)
+    let req = test::TestRequest::post().uri("/api/email/verify/resend")
+        .set_json(serde_json::json!({"email": "sam@example.com"})).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 429);
+    assert!(resp.headers().get("Retry-After").is_some());
+    // 존재하지 않는 주소도 같은 성공 응답과 같은 횟수 제한(1분에 1통)
+    for expected in [200, 429] {
+        let req = test::TestRequest::post().uri("/api/email/verify/resend")
+            .set_json(serde_json::json!({"email": "nobody@example.com"})).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), expected);
+    }
+
+    let req = test::TestRequest::post().uri("/api/email/verify")
+        .set_json(serde_json::json!({"token": "not-a-token"})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // 정규화된 주소로 받은 토큰으로 인증 후 로그인(토큰은 일회용)
+    let token = read_mail_token("sam@example.com", "Verification token").unwrap();
+    let req = test::TestRequest::post().uri("/api/email/verify").set_json(serde_json::json!({"token": token})).to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+    let req = test::TestRequest::post().uri("/api/email/verify").set_json(serde_json::json!({"token": token})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+    let req = test::TestRequest::post().uri("/api/login").set_json(&login).to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    // 인증 메일 발송에 실패해도 가입은 완료(201, 재발송 안내), 재발송 요청도 같은 성공 응답
+    let pool = temp_pool().await;
+    let failing = init_app_with(pool.clone(), PasswordPolicy::default(), Arc::new(FailingMailer)).await;
+    let info = serde_json::json!({"username": "tess", "password": "Passw0rd!", "email": "tess@example.com"});
+    let req = test::TestRequest::post().uri("/api/register").set_json(&info).to_request();
+    let resp = test::call_service(&failing, req).await;
+    assert_eq!(resp.status(), 201);
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains("/api/email/verify/resend"));
+    // 가입 메일의 발송 횟수 기록을 지우고 재발송 요청
+    sqlx::query("delete from rate_limits").execute(&pool).await.unwrap();
+    let req = test::TestRequest::post().uri("/api/email/verify/resend")
+        .set_json(serde_json::json!({"email": "tess@example.com"})).to_request();
+    assert_eq!(test::call_service(&failing, req).await.status(), 200);
+}
+
+#[actix_web::test]
+async fn test_config_file_env_overrides_and_validation() {
+    let content = r#"
+        [server]
+        bind = "0.0.0.0:9000"
+        cors_allowed_origins = ["https://app.example.com"]
+
+        [database]
+        url = "sqlite://login.db"
+
+        [tokens]
+        access_ttl_secs = 900
+
+        [bcrypt]
+        cost = 12
+        self_test = { password = "self-test", fixed_salt = "0123456789abcdef", expected_hash = "$2b$12$..." }
+
+        [argon2]
+        self_test = { password = "self-test", fixed_salt = "0123456789abcdef", expected_hash = "$argon2id$..." }
+    "#;
+    let mut config = Config::parse(content).unwrap();
+    assert_eq!(config.server.bind, "0.0.0.0:9000");
+    assert_eq!(config.tokens.access_ttl_secs, 900);
+    assert_eq!(config.tokens.refresh_ttl_secs, 14*24*3600);    // 파일에 없는 항목은 기본값
+    assert_eq!(config.bcrypt.cost, 12);
+    config.validate().unwrap();
+
+    // 환경 변수가 파일 값보다 우선
+    let env: std::collections::HashMap<&str, &str> = [
+        ("DATABASE_URL", "sqlite://override.db"),
+        ("CORS_ALLOWED_ORIGINS", "https://a.example.com, https://b.example.com"),
+        ("ACCESS_TOKEN_TTL_SECS", "600"),
+        ("ADMIN_USERNAMES", "alice,,bob"),
+        ("LOG_LEVEL", "debug,sqlx=warn"),
+        ("LOG_FORMAT", "json"),
+        ("METRICS_BEARER_TOKEN", "scrape-secret"),
+        ("REMINDER_INTERVAL_SECS", "30"),
+    ].into_iter().collect();
+    config.apply_env_overrides(&|name| env.get(name).map(|v| v.to_string())).unwrap();
+    assert_eq!(config.database.url, "sqlite://override.db");
+    assert_eq!(config.server.cors_allowed_origins, ["https://a.example.com", "https://b.example.com"]);
+    assert_eq!(config.tokens.access_ttl_secs, 600);
+    assert_eq!(config.admin.usernames, ["alice", "bob"]);
+    assert_eq!(config.logging.level, "debug,sqlx=warn");
+    assert_eq!(config.logging.format, web_tracing::LogFormat::Json);
+    assert_eq!(config.metrics.bearer_token.as_deref(), Some("scrape-secret"));
+    assert_eq!(config.jobs.reminder_interval_secs, 30);
+
+    // 숫자가 아닌 환경 변수 값은 변수 이름과 함께 에러
+    let err = config.apply_env_overrides(&|name| (name=="BCRYPT_COST").then(|| "high".to_string())).unwrap_err();
+    assert!(err.to_string().contains("BCRYPT_COST"));
+
+    // 저장소의 설정 예시 파일도 파싱 및 검증 가능하고, 자가시험 벡터는 실제 해시와 일치해야 함
+    let example = Config::parse(include_str!("../login_web_server.example.toml")).unwrap();
+    example.validate().unwrap();
+    let vectors = [(Algorithm::Bcrypt, example.bcrypt.self_test.as_ref().unwrap()), (Algorithm::Argon2id, example.argon2.self_test.as_ref().unwrap())];
+    PasswordHashers::from_config(&example).unwrap().self_test(&vectors).unwrap();
+    // CORS 허용 출처는 기본값이 빈 목록, 해시 알고리즘은 기본값 bcrypt(argon2.self_test 없이 시작 가능)
+    let defaults = Config::parse("[database]\nurl = \"sqlite::memory:\"\n[bcrypt.self_test]\npassword = \"p\"\nfixed_salt = \"0123456789abcdef\"\nexpected_hash = \"x\"").unwrap();
+    assert!(defaults.server.cors_allowed_origins.is_empty());
+    assert_eq!(defaults.password_hash.algorithm, Algorithm::Bcrypt);
+    defaults.validate().unwrap();
+
+    // 알 수 없는 항목(오타)은 파싱 에러
+    assert!(Config::parse("[server]\nbnid = \"0.0.0.0:9000\"").is_err());
+
+    // 잘못된 항목은 한 번에 모두 보고
+    let invalid = Config::parse(r#"
+        [server]
+        bind = "not an address"
+        cors_allowed_origins = ["app.example.com"]
+
+        [tokens]
+        access_ttl_secs = 0
+
+        [password_hash]
+        algorithm = "argon2id"
+
+        [bcrypt]
+        cost = 40
+
+        [argon2]
+        memory_kib = 1
+
+        [logging]
+        level = "=["
+    "#).unwrap();
+    let message = invalid.validate().unwrap_err().to_string();
+    for expected in ["server.bind", "cors_allowed_origins", "database.url", "tokens.access_ttl_secs", "bcrypt.cost", "bcrypt.self_test", "ARGON2_MEMORY_KIB", "argon2.self_test", "logging.level"] {
+        assert!(message.contains(expected), "{} missing from: {}", expected, message);
+    }
+
+    // --config 인자
+    let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    assert_eq!(login_web_server::config::path_from_args(args(&["--config", "a.toml"])).unwrap(), Some("a.toml".into()));
+    assert_eq!(login_web_server::config::path_from_args(args(&["--config=b.toml"])).unwrap(), Some("b.toml".into()));
+    assert_eq!(login_web_server::config::path_from_args(args(&[])).unwrap(), None);
+    assert!(login_web_server::config::path_from_args(args(&["--config"])).is_err());
+}
+
+#[actix_web::test]
+async fn test_password_hashers_and_rehash_on_login() {
+    // 테스트 시간을 줄이기 위해 낮은 매개변수 사용
+    let hashers = PasswordHashers::new(Algorithm::Argon2id, vec![
+        Box::new(BcryptHasher::new(4)),
+        Box::new(Argon2Hasher::new(1024, 1, 1).unwrap()),
+    ]).unwrap();
+    let bcrypt_hash = BcryptHasher::new(4).hash("Passw0rd!").unwrap();
+    let argon2_hash = hashers.hash("Passw0rd!").unwrap();
+    assert!(argon2_hash.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+
+    // 해시 형식으로 알고리즘을 판별하여 검증
+    assert!(hashers.verify("Passw0rd!", &bcrypt_hash).unwrap());
+    assert!(!hashers.verify("wrong", &bcrypt_hash).unwrap());
+    assert!(hashers.verify("Passw0rd!", &argon2_hash).unwrap());
+    assert!(!hashers.verify("wrong", &argon2_hash).unwrap());
+    assert!(hashers.verify("Passw0rd!", "plaintext").is_err());
+
+    // 다른 알고리즘이나 이전 매개변수로 만든 해시는 다시 해싱 필요
+    assert!(hashers.needs_rehash(&bcrypt_hash));
+    assert!(!hashers.needs_rehash(&argon2_hash));
+    assert!(hashers.needs_rehash(&Argon2Hasher::new(2048, 1, 1).unwrap().hash("Passw0rd!").unwrap()));
+    let bcrypt_only = PasswordHashers::new(Algorithm::Bcrypt, vec![Box::new(BcryptHasher::new(5))]).unwrap();
+    assert!(bcrypt_only.needs_rehash(&bcrypt_hash));
+
+    // 자가시험: 고정 솔트 해시가 기대값과 다르면 실패
+    let expected_hash = Argon2Hasher::new(1024, 1, 1).unwrap().hash_with_salt("self-test", b"0123456789abcdef").unwrap();
+    let mut vector = HashSelfTest { password: "self-test".to_string(), fixed_salt: "0123456789abcdef".to_string(), expected_hash };
+    hashers.self_test(&[(Algorithm::Argon2id, &vector)]).unwrap();
+    vector.password = "other".to_string();
+    assert!(hashers.self_test(&[(Algorithm::Argon2id, &vector)]).is_err());
+
+    // 이전 매개변수(cost 4)로 만든 bcrypt 해시는 로그인 성공 시 설정된 알고리즘과 매개변수(기본 bcrypt, cost 10)로 교체
+    let pool = temp_pool().await;
+    let app = init_app_with_pool(pool.clone()).await;
+    register_verified(&app, "quinn").await;
+    sqlx::query("update users set password_hash=? where username='quinn'").bind(&bcrypt_hash)
+        .execute(&pool).await.unwrap();
+    let login = serde_json::json!({"username": "quinn", "password": "Passw0rd!"});
+    for _ in 0..2 {
+        let req = test::TestRequest::post().uri("/api/login").set_json(&login).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+        let hash: String = sqlx::query_scalar("select password_hash from users where username='quinn'")
+            .fetch_one(&pool).await.unwrap();
+        assert!(hash.starts_with("$2b$10$"));
+        assert!(!password_hasher::hashers().needs_rehash(&hash));
+    }
+}
+
+#[actix_web::test]
+async fn test_audit_log() {
+    let pool = temp_pool().await;
+    let app = init_app_with_pool(pool.clone()).await;
+    let admin = register_admin(&app, &pool, "auditor").await;
+    let user = register_and_login(&app, "rita").await;
+
+    // 로그인 실패(존재하지 않는 사용자 포함), 토큰 검증 실패, 로그아웃 기록
+    for username in ["rita", "nobody"] {
+        let info = serde_json::json!({"username": username, "password": "wrong"});
+        let req = test::TestRequest::post().uri("/api/login").set_json(&info)
+            .insert_header(("User-Agent", "audit-test")).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+    }
+    let req = test::TestRequest::post().uri("/api/auth/verify-token").set_json(serde_json::json!({"token": "garbage"})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::post().uri("/api/logout")
+        .insert_header(("Authorization", format!("Bearer {}", user))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let audit = |query: &str| test::TestRequest::get().uri(&format!("/api/admin/audit{}", query))
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+
+    // 이벤트 종류 및 결과로 필터링(최신순)
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, audit("?event=login&outcome=failure")).await;
+    assert_eq!(resp["total"], 2);
+    let events = resp["events"].as_array().unwrap();
+    assert_eq!(events[0]["actor"], "nobody");
+    assert!(events[0]["actor_id"].is_null());
+    assert_eq!(events[1]["actor"], "rita");
+    assert!(events[1]["actor_id"].is_i64());
+    assert_eq!(events[1]["detail"], "invalid_credentials");
+    assert_eq!(events[1]["user_agent"], "audit-test");
+
+    // 행위자로 필터링 및 페이지 나누기
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, audit("?actor=rita&limit=2")).await;
+    let kinds: Vec<&str> = resp["events"].as_array().unwrap().iter().map(|e| e["event"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["logout", "login"]);
+    assert_eq!(resp["total"], 4);   // register, login 성공, login 실패, logout
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, audit("?actor=rita&limit=2&offset=2")).await;
+    assert_eq!(resp["events"].as_array().unwrap().len(), 2);
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, audit("?event=verify_token")).await;
+    assert_eq!(resp["events"][0]["outcome"], "failure");
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, audit("?since=2999-01-01")).await;
+    assert_eq!(resp["total"], 0);
+    assert_eq!(test::call_service(&app, audit("?since=yesterday")).await.status(), 400);
+
+    // JSON Lines 내보내기(기록순)
+    let req = test::TestRequest::get().uri("/api/admin/audit/export?actor=rita")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("content-type").unwrap(), "application/x-ndjson");
+    let body = test::read_body(resp).await;
+    let lines: Vec<serde_json::Value> = std::str::from_utf8(&body).unwrap().lines()
+        .map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0]["event"], "register");
+
+    // 권한 없는 사용자는 조회 불가, 기록은 수정/삭제 불가
+    let other = register_and_login(&app, "sam").await;
+    let req = test::TestRequest::get().uri("/api/admin/audit")
+        .insert_header(("Authorization", format!("Bearer {}", other))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 403);
+
+    // 관리자에 의한 계정 삭제는 관리자를 행위자로, 대상 사용자를 detail로 기록
+    let req = test::TestRequest::delete().uri("/api/admin/users/sam")
+        .insert_header(("Authorization", format!("Bearer {}", admin))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, audit("?event=admin_delete_user")).await;
+    assert_eq!(resp["total"], 1);
+    assert_eq!(resp["events"][0]["outcome"], "success");
+    assert_eq!(resp["events"][0]["actor"], "auditor");
+    assert!(resp["events"][0]["actor_id"].is_i64());
+    assert_eq!(resp["events"][0]["detail"], "user=sam");
+
+    assert!(sqlx::query("delete from audit_events").execute(&pool).await.is_err());
+    assert!(sqlx::query("update audit_events set outcome='success'").execute(&pool).await.is_err());
+}
+
+// 메트릭에서 이름과 라벨이 일치하는 줄의 값(라벨 순서는 정의 순서)
+fn metric_value(body: &str, series: &str) -> Option<f64> {
+    body.lines().find_map(|line| line.strip_prefix(series)?.strip_prefix(' ')?.parse().ok())
+}
+
+#[actix_web::test]
+async fn test_metrics_endpoint() {
+    init_keys(KeySet::ephemeral().unwrap());
+    let app = test::init_service(
+        App::new().app_data(web::Data::<dyn Repository>::from(sqlite_repo(&temp_pool().await)))
+        .app_data(web::Data::new(PasswordPolicy::default()))
+        .app_data(web::Data::<dyn Mailer>::from(test_mailer()))
+        .app_data(web::Data::new(MetricsAccess::new(Some("scrape-secret"))))
+        .configure(init)
+        .wrap(RequestMetrics)
+    ).await;
+
+    register_verified(&app, "metrics_user").await;
+    let token = {
+        let info = serde_json::json!({"username": "metrics_user", "password": "Passw0rd!"});
+        let req = test::TestRequest::post().uri("/api/login").set_json(&info).to_request();
+        let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        resp["token"].as_str().unwrap().to_string()
+    };
+    let info = serde_json::json!({"username": "metrics_user", "password": "wrong"});
+    let req = test::TestRequest::post().uri("/api/login").set_json(&info).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+    let req = test::TestRequest::post().uri("/api/logout").insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+    let req = test::TestRequest::get().uri("/api/todos/1").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // 메트릭 토큰이 없거나 틀리면(사용자 access 토큰 포함) 401
+    let req = test::TestRequest::get().uri("/metrics").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+    let req = test::TestRequest::get().uri("/metrics").insert_header(("Authorization", format!("Bearer {}", token))).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    let req = test::TestRequest::get().uri("/metrics").insert_header(("Authorization", "Bearer scrape-secret")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("content-type").unwrap().to_str().unwrap().starts_with("text/plain"));
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+
+    // 메트릭은 프로세스 전체에서 공유하므로(다른 테스트와 병렬 실행) 최소값으로 확인
+    assert!(metric_value(&body, r#"http_requests_total{method="POST",route="/api/login",status="200"}"#).unwrap()>=1.0);
+    assert!(metric_value(&body, r#"http_requests_total{method="GET",route="/api/todos/{id}",status="401"}"#).unwrap()>=1.0);
+    assert!(metric_value(&body, r#"http_request_duration_seconds_count{method="POST",route="/api/login"}"#).unwrap()>=2.0);
+    assert!(metric_value(&body, r#"auth_login_attempts_total{event="login",outcome="success",reason=""}"#).unwrap()>=1.0);
+    assert!(metric_value(&body, r#"auth_login_attempts_total{event="login",outcome="failure",reason="invalid_credentials"}"#).unwrap()>=1.0);
+    assert!(metric_value(&body, r#"password_hash_duration_seconds_count{algorithm="bcrypt",operation="hash"}"#).unwrap()>=1.0);
+    assert!(metric_value(&body, r#"password_hash_duration_seconds_count{algorithm="bcrypt",operation="verify"}"#).unwrap()>=2.0);
+    // 로그아웃으로 폐기한 토큰(이 테스트의 DB 기준)
+    assert_eq!(metric_value(&body, "auth_revoked_tokens"), Some(1.0));
+    assert!(metric_value(&body, r#"db_pool_connections{state="idle"}"#).is_some());
+}
+
+#[actix_web::test]
+async fn test_health_and_readiness() {
+    let app = init_app().await;
+    let req = test::TestRequest::get().uri("/healthz").to_request();
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(resp["status"], "ok");
+
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "ready");
+    for component in ["database", "migrations", "keys"] {
+        assert_eq!(body["components"][component]["status"], "ok", "{}", body);
+    }
+    assert_eq!(body["components"]["migrations"]["pending"], serde_json::json!([]));
+
+    // 마이그레이션이 적용되지 않은 DB
+    let pool = SqlitePoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+    let app = init_app_with_pool(pool.clone()).await;
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(body["components"]["database"]["status"], "ok");
+    assert_eq!(body["components"]["migrations"]["status"], "error");
+
+    // 일부 마이그레이션만 적용된 경우 남은 버전 보고
+    let repo = SqliteRepository::new(pool.clone());
+    repo.migrate().await.unwrap();
+    let latest = repo.migrator().iter().map(|m| m.version).max().unwrap();
+    sqlx::query("delete from _sqlx_migrations where version=?").bind(latest).execute(&pool).await.unwrap();
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["components"]["migrations"]["pending"], serde_json::json!([latest]));
+
+    // DB 연결 불가
+    pool.close().await;
+    let req = test::TestRequest::get().uri("/readyz").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["components"]["database"]["status"], "error");
+    // 상세 에러는 로그에만 남기고 응답에는 일반 메시지만 포함
+    assert_eq!(body["components"]["database"]["error"], "unavailable");
+    // 생존 확인은 DB 상태와 무관
+    let req = test::TestRequest::get().uri("/healthz").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+}
+
+#[actix_web::test]
+async fn test_scheduler_runs_jobs_until_shutdown() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let failures = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(AtomicUsize::new(0));
+    let (r, f, done) = (runs.clone(), failures.clone(), finished.clone());
+    let jobs = Scheduler::new()
+        .every("count", Duration::from_millis(20), move || {
+            let r = r.clone();
+            async move { Ok(format!("run {}", r.fetch_add(1, Ordering::SeqCst)+1)) }
+        })
+        // 실패한 작업도 다음 주기에 다시 실행
+        .every("fail", Duration::from_millis(20), move || {
+            let f = f.clone();
+            async move { f.fetch_add(1, Ordering::SeqCst); Err(anyhow::anyhow!("boom")) }
+        })
+        // 종료 신호 시점에 실행 중인 작업은 끝까지 실행
+        .every("slow", Duration::from_secs(3600), move || {
+            let done = done.clone();
+            async move {
+                actix_web::rt::time::sleep(Duration::from_millis(100)).await;
+                done.fetch_add(1, Ordering::SeqCst);
+                Ok("done".to_string())
+            }
+        })
+        .start();
+
+    // 시작 즉시 한 번 실행 후 주기마다 반복
+    actix_web::rt::time::sleep(Duration::from_millis(70)).await;
+    assert!(runs.load(Ordering::SeqCst)>=2);
+    assert!(failures.load(Ordering::SeqCst)>=2);
+    assert_eq!(finished.load(Ordering::SeqCst), 0);
+
+    jobs.shutdown().await;
+    assert_eq!(finished.load(Ordering::SeqCst), 1);
+    let stopped_at = runs.load(Ordering::SeqCst);
+    actix_web::rt::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), stopped_at);
+}
+
+#[actix_web::test]
+async fn test_maintenance_jobs_prune_expired_rows() {
+    let pool = temp_pool().await;
+    sqlx::query("insert into revoked_tokens(jti, expires_at) values ('expired', datetime('now', '-1 hour')), ('active', datetime('now', '+1 hour'))")
+        .execute(&pool).await.unwrap();
+
+    // 시작 시 한 번 실행
+    let jobs = maintenance::register(Scheduler::new(), &sqlite_repo(&pool), std::time::Duration::from_secs(3600)).start();
+    actix_web::rt::time::sleep(std::time::Duration::from_millis(200)).await;
+    jobs.shutdown().await;
+
+    let remaining: Vec<String> = sqlx::query_scalar("select jti from revoked_tokens").fetch_all(&pool).await.unwrap();
+    assert_eq!(remaining, ["active"]);
+}
+
+// 저장소 구현(SQLite, PostgreSQL)에 관계없이 같은 결과가 나와야 하는 흐름
+// prefix: 메일 디렉터리를 공유하므로 구현마다 다른 사용자 이름 사용
+async fn exercise_backend(repo: Arc<dyn Repository>, prefix: &str) {
+    let app = init_app_with_repo(repo.clone(), PasswordPolicy::default(), test_mailer()).await;
+    let user = format!("{}_user", prefix);
+    let admin = register_admin_with(&app, repo.as_ref(), &format!("{}_admin", prefix)).await;
+    register_verified(&app, &user).await;
+
+    // 중복 가입(username, email 유일성 제약 구분)
+    let duplicate = serde_json::json!({"username": user, "password": "Passw0rd!", "email": format!("other_{}", email_of(&user))});
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, test::TestRequest::post().uri("/api/register").set_json(&duplicate).to_request()).await;
+    assert_eq!(resp["code"], "username_taken");
+    let duplicate = serde_json::json!({"username": format!("{}_other", prefix), "password": "Passw0rd!", "email": email_of(&user)});
+    let resp: serde_json::Value = test::call_and_read_body_json(&app, test::TestRequest::post().uri("/api/register").set_json(&duplicate).to_request()).await;
+    assert_eq!(resp["code"], "email_taken");
+
+    // 로그인 및 todo CRUD(날짜와 시각 형식 포함)
+    let login = serde_json::json!({"username": user, "password": "Passw0rd!", "device_label": "laptop"});
+    let tokens: serde_json::Value = test::call_and_read_body_json(&app, test::TestRequest::post().uri("/api/login").set_json(&login).to_request()).await;
+    let token = tokens["token"].as_str().unwrap().to_string();
+    let bearer = ("Authorization", format!("Bearer {}", token));
+    let req = test::TestRequest::post().uri("/api/todos").insert_header(bearer.clone())
+        .set_json(serde_json::json!({"title": "Buy milk", "due_date": "2025-09-01"})).to_request();
+    let todo: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(todo["due_date"], "2025-09-01");
+    assert_eq!(todo["completed"], false);
+    assert_eq!(todo["created_at"].as_str().unwrap().len(), "YYYY-MM-DD HH:MM:SS".len());
+    let id = todo["id"].as_i64().unwrap();
+    let req = test::TestRequest::patch().uri(&format!("/api/todos/{}", id)).insert_header(bearer.clone())
+        .set_json(serde_json::json!({"completed": true, "due_date": null})).to_request();
+    let todo: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(todo["completed"], true);
+    assert!(todo["due_date"].is_null());
+    let req = test::TestRequest::get().uri("/api/todos").insert_header(bearer.clone()).to_request();
+    let todos: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(todos["todos"].as_array().unwrap().len(), 1);
+    let req = test::TestRequest::delete().uri(&format!("/api/todos/{}", id)).insert_header(bearer.clone()).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+
+    exercise_todo_list(&app, &token).await;
+    exercise_projects_and_tags(&app, &token).await;
+    let notifications = std::env::temp_dir().join(format!("login-web-server-notifications-{}-{}", std::process::id(), prefix));
+    let _ = std::fs::remove_dir_all(&notifications);
+    exercise_recurrence_and_reminders(&app, repo.as_ref(), &token, &notifications).await;
+    let _ = std::fs::remove_dir_all(&notifications);
+
+    // refresh 토큰 교체와 재사용 감지
+    let refresh = serde_json::json!({"refresh_token": tokens["refresh_token"]});
+    let req = test::TestRequest::post().uri("/api/token/refresh").set_json(&refresh).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::post().uri("/api/token/refresh").set_json(&refresh).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+    // 세션 목록
+    let req = test::TestRequest::get().uri("/api/sessions").insert_header(bearer.clone()).to_request();
+    let sessions: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(sessions[0]["device_label"], "laptop");
+    assert_eq!(sessions[0]["current"], true);
+
+    // 로그인 실패 잠금
+    let wrong = serde_json::json!({"username": user, "password": "wrong"});
+    for _ in 0..lockout::USERNAME_FAILURE_THRESHOLD {
+        test::call_service(&app, test::TestRequest::post().uri("/api/login").set_json(&wrong).to_request()).await;
+    }
+    let resp = test::call_service(&app, test::TestRequest::post().uri("/api/login").set_json(&login).to_request()).await;
+    assert_eq!(resp.status(), 429);
+    let retry_after: u64 = resp.headers().get("Retry-After").unwrap().to_str().unwrap().parse().unwrap();
+    assert!((1..=30).contains(&retry_after));
+
+    // 비밀번호 재설정 요청 횟수 제한(없는 사용자도 적용)
+    let reset_request = serde_json::json!({"username": format!("{}_nobody", prefix)});
+    for expected in [200, 200, 200, 429] {
+        let req = test::TestRequest::post().uri("/api/password/reset/request").set_json(&reset_request).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), expected);
+    }
+
+    // 재설정 토큰 소모와 비밀번호 변경은 함께 처리되고, 소모된 토큰으로는 다시 바꿀 수 없음
+    let user_id = repo.find_user_id(&user).await.unwrap().unwrap();
+    let reset_token = password_reset::create(repo.as_ref(), user_id).await.unwrap();
+    assert_eq!(password_reset::consume(repo.as_ref(), &reset_token, "new-hash").await.unwrap(), Some(user_id));
+    assert_eq!(password_reset::consume(repo.as_ref(), &reset_token, "other-hash").await.unwrap(), None);
+    assert_eq!(repo.find_credentials(&user).await.unwrap().unwrap().password_hash, "new-hash");
+
+    // 관리자 사용자 목록(역할, 잠금 상태) 및 감사 로그 조회
+    let admin_bearer = ("Authorization", format!("Bearer {}", admin));
+    let req = test::TestRequest::get().uri("/api/admin/users").insert_header(admin_bearer.clone()).to_request();
+    let users: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(users["total"], 2);
+    let listed = users["users"].as_array().unwrap().iter().find(|u| u["username"]==user.as_str()).unwrap();
+    assert_eq!(listed["roles"], serde_json::json!(["user"]));
+    assert!(listed["locked_until"].is_string());
+    let req = test::TestRequest::get().uri(&format!("/api/admin/audit?event=login&outcome=failure&actor={}&since=2000-01-01%2000:00:00", user))
+        .insert_header(admin_bearer.clone()).to_request();
+    let audit: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(audit["total"].as_i64().unwrap()>=lockout::USERNAME_FAILURE_THRESHOLD);
+
+    // 프로젝트와 하위 todo가 남아 있어도 사용자 삭제 가능
+    let req = test::TestRequest::post().uri("/api/projects").insert_header(bearer.clone())
+        .set_json(serde_json::json!({"name": "Leftovers"})).to_request();
+    let project: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let req = test::TestRequest::post().uri("/api/todos").insert_header(bearer.clone())
+        .set_json(serde_json::json!({"title": "parent", "project_id": project["id"], "tags": ["kept"]})).to_request();
+    let parent: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let req = test::TestRequest::post().uri("/api/todos").insert_header(bearer.clone())
+        .set_json(serde_json::json!({"title": "child", "parent_id": parent["id"]})).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    // 사용자 삭제 후 세션 무효화
+    let req = test::TestRequest::delete().uri(&format!("/api/admin/users/{}", user)).insert_header(admin_bearer).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 204);
+    let req = test::TestRequest::get().uri("/api/todos").insert_header(bearer).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 401);
+}
+
+#[actix_web::test]
+async fn test_sqlite_backend() {
+    exercise_backend(sqlite_repo(&temp_pool().await), "sqlite").await;
+}
+
+// TEST_POSTGRES_URL을 설정하고 cargo test -- --ignored로 실행(테스트마다 새 데이터베이스를 만들고 끝나면 삭제)
+#[actix_web::test]
+#[ignore = "requires PostgreSQL: set TEST_POSTGRES_URL and run with --ignored"]
+async fn test_postgres_backend() {
+    test_postgres::with_database("login", |url| async move {
+        let repo = repository::connect(&url).await.unwrap();
+        assert_eq!(repo.backend(), repository::Backend::Postgres);
+        repo.migrate().await.unwrap();
+        exercise_backend(repo.clone(), "postgres").await;
+        // 감사 로그는 추가만 가능
+        let pool = sqlx::PgPool::connect(&url).await.unwrap();
+        assert!(sqlx::query("delete from audit_events").execute(&pool).await.is_err());
+        assert!(sqlx::query("update audit_events set outcome='success'").execute(&pool).await.is_err());
+        pool.close().await;
+        repo.close().await;
+    }).await;
+}
@@ -13,9 +13,9 @@ struct GreetResponse {
 #[get("/")]
 pub async fn hello(req: HttpRequest) -> impl Responder {
     if let Some(addr) = req.peer_addr() {
-        println!("'/': Get Request client: {}", addr);
+        tracing::info!("'/': Get Request client: {}", addr);
     } else {
-        println!("'/': Get Request from unknown client.");
+        tracing::info!("'/': Get Request from unknown client.");
     }
     HttpResponse::Ok().body("Hello, World!")
 }
@@ -23,9 +23,9 @@ pub async fn hello(req: HttpRequest) -> impl Responder {
 // web::Query Extractor를 사용하여 요청의 쿼리 파라미터(?key=value&key2=value2) 자동 파싱 후 HashMap 형태로 제작
 pub async fn greet_handler(query: web::Query<std::collections::HashMap<String, String>>, req: HttpRequest) -> impl Responder {
     if let Some(addr) = req.peer_addr() {
-        println!("'/api/greet': Get Request client: {}", addr);
+        tracing::info!("'/api/greet': Get Request client: {}", addr);
     } else {
-        println!("'/api/greet': Get Request from unknown client.");
+        tracing::info!("'/api/greet': Get Request from unknown client.");
     }
     // cloned(): Option 내의 있는 값의 복사본
     // unwrap_or_else(): Option이 Some(value)일 경우 value 추출, None일 경우 매개변수로 전달한 값 반환
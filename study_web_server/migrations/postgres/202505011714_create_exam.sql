@@ -0,0 +1,7 @@
+-- PostgreSQL 스키마(migrations/sqlite와 같은 구조)
+-- created_at은 조회 시 "YYYY-MM-DD HH:MM:SS"(UTC) 문자열로 변환
+create table if not exists exam (
+    id bigint generated by default as identity primary key,
+    title text not null,
+    created_at timestamptz not null default current_timestamp
+);
@@ -1 +1,2 @@
-pub mod routes;
\ No newline at end of file
+pub mod routes;
+pub mod repository; // src/repository 모듈 import
@@ -1,32 +1,35 @@
 use actix_web::{web, App, HttpServer};
 use anyhow::Result;
-use sqlx::{migrate::Migrator, SqlitePool};
+use web_tracing::{LogConfig, RequestTracing};
 
-// sqlx 마이그레이터 정의
-// 컴파일 타임에 ./migrations 폴더를 읽음
-static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
-
-mod routes;
+// 라이브러리 크레이트(src/lib.rs)에 정의된 라우트와 저장소 사용
+use study_web_server::{repository, routes};
 
 #[actix_web::main]
 async fn main() -> Result<()> {
-    // DB url 정의(sqlite 파일 경로 + 권한 옵션)
-    let db_url = "sqlite:./exam.db?mode=rwc";
-    // DB 연결 풀 생성: db_url 경로의 sqlite 파일을 찾거나 새로 만들고 연결 풀 생성
+    // 로그 설정: LOG_LEVEL(없으면 RUST_LOG, 기본값 "info,sqlx=warn"), LOG_FORMAT(text 또는 json)
+    web_tracing::init(&LogConfig::from_env()?)?;
+    // DB url 정의: DATABASE_URL(없으면 sqlite 파일 경로 + 권한 옵션)
+        // sqlite:... 이면 SQLite, postgres:// 또는 postgresql:// 이면 PostgreSQL 사용
+    let db_url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:./exam.db?mode=rwc".to_string());
+    // DB 연결 풀 생성: url의 scheme에 맞는 저장소 생성(sqlite 파일은 없으면 새로 만듦)
         // await로 비동기 완료 대기
-    let pool = SqlitePool::connect(db_url).await?;
-    println!("DB connection successful!");
+    let repo = repository::connect(&db_url).await?;
+    tracing::info!("DB connection successful!");
     
     // Execute Migration
         // sqlx_migrations 테이블 확인 후 적용되지 않은 마이그레이션 스크립트 실행
-    MIGRATOR.run(&pool).await?;
+    repo.migrate().await?;
+    // 핸들러에서 web::Data<dyn Repository>로 꺼내 쓸 수 있도록 Arc를 그대로 감쌈
+    let app_repo: web::Data<dyn repository::Repository> = web::Data::from(repo);
     
     // HTTP 서버 생성 및 구동
     HttpServer::new(move || {
         App::new()
-            // app_data를 통해 핸들러 함수에서 web::Data<SqlitePool>로 접근 가능
-            .app_data(web::Data::new(pool.clone())) // 풀을 복제하여 App 인스턴스마다 풀 공유
+            // app_data를 통해 핸들러 함수에서 web::Data<dyn Repository>로 접근 가능
+            .app_data(app_repo.clone()) // 저장소를 복제하여 App 인스턴스마다 연결 풀 공유
             .configure(routes::init)    // routes 모듈의 init 함수를 호출하여 라우트 및 서비스 설정
+            .wrap(RequestTracing)   // 요청 추적(X-Request-Id, 요청별 span 및 완료 로그)
     }).bind("127.0.0.1:8080")?.run().await?;
     
     Ok(())
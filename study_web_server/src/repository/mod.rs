@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::migrate::{MigrateError, Migrator};
+use std::sync::Arc;
+
+mod sqlite;     // src/repository/sqlite.rs 사용
+mod postgres;   // src/repository/postgres.rs 사용
+
+pub use sqlite::SqliteRepository;
+pub use postgres::PgRepository;
+
+// DB 접근 계층
+// 핸들러는 SQL 대신 저장소 트레이트를 사용하고, DATABASE_URL의 scheme으로 구현을 선택
+//   sqlite:...              -> SqliteRepository(migrations/sqlite)
+//   postgres://, postgresql:// -> PgRepository(migrations/postgres)
+
+// DB 종류
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+}
+
+impl Backend {
+    // DB url의 scheme으로 종류 판단(지원하지 않는 scheme이면 None)
+    pub fn from_url(url: &str) -> Option<Self> {
+        let (scheme, _) = url.split_once(':')?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "sqlite" => Some(Backend::Sqlite),
+            "postgres" | "postgresql" => Some(Backend::Postgres),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Backend::Sqlite => "sqlite",
+            Backend::Postgres => "postgres",
+        }
+    }
+}
+
+// DB url에 맞는 저장소 생성(연결 풀 생성까지, 마이그레이션은 migrate로 별도 실행)
+pub async fn connect(url: &str) -> anyhow::Result<Arc<dyn Repository>> {
+    let repository: Arc<dyn Repository> = match Backend::from_url(url) {
+        Some(Backend::Sqlite) => Arc::new(SqliteRepository::connect(url).await?),
+        Some(Backend::Postgres) => Arc::new(PgRepository::connect(url).await?),
+        None => anyhow::bail!("Unsupported database url scheme (expected sqlite: or postgres://)"),
+    };
+    Ok(repository)
+}
+
+// 모든 저장소 트레이트를 구현한 DB(핸들러에서 web::Data<dyn Repository>로 사용)
+pub trait Repository: Database + ExamRepository {}
+
+impl<T> Repository for T where T: Database + ExamRepository {}
+
+// 연결 풀 및 마이그레이션(준비 상태 확인, 서버 시작)
+#[async_trait]
+pub trait Database: Send+Sync {
+    fn backend(&self) -> Backend;
+    // 이 DB 종류의 마이그레이션 목록(빌드에 포함됨)
+    fn migrator(&self) -> &'static Migrator;
+    async fn migrate(&self) -> Result<(), MigrateError>;
+    async fn ping(&self) -> Result<(), sqlx::Error>;
+    // 적용에 성공한 마이그레이션 버전 목록
+    async fn applied_migrations(&self) -> Result<Vec<i64>, sqlx::Error>;
+}
+
+#[derive(Serialize)]
+pub struct Exam {   // exam 테이블 행
+    pub id: i64,
+    pub title: String,
+    pub created_at: String, // "YYYY-MM-DD HH:MM:SS"(UTC)
+}
+
+#[async_trait]
+pub trait ExamRepository: Send+Sync {
+    async fn create_exam(&self, title: &str) -> Result<i64, sqlx::Error>;
+    async fn list_exams(&self) -> Result<Vec<Exam>, sqlx::Error>;
+}
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
+use sqlx::Row;
+use std::str::FromStr;
+use super::*;
+
+// PostgreSQL 마이그레이션(컴파일 타임에 ./migrations/postgres 폴더를 읽음)
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+// PostgreSQL 저장소
+// 연결마다 세션 시간대를 UTC로 설정하여 created_at 문자열이 SQLite 구현과 같은 UTC 기준이 되도록 함
+#[derive(Clone)]
+pub struct PgRepository {
+    pool: PgPool,
+}
+
+impl PgRepository {
+    pub fn new(pool: PgPool) -> Self {
+        PgRepository { pool }
+    }
+
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let options = PgConnectOptions::from_str(url)?.options([("timezone", "UTC")]);
+        Ok(PgRepository::new(PgPoolOptions::new().connect_with(options).await?))
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl Database for PgRepository {
+    fn backend(&self) -> Backend {
+        Backend::Postgres
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &MIGRATOR
+    }
+
+    async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("select 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query("select version from _sqlx_migrations where success").fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("version")).collect())
+    }
+}
+
+#[async_trait]
+impl ExamRepository for PgRepository {
+    async fn create_exam(&self, title: &str) -> Result<i64, sqlx::Error> {
+        let row = sqlx::query("insert into exam (title) values ($1) returning id").bind(title).fetch_one(&self.pool).await?;
+        Ok(row.get("id"))
+    }
+
+    async fn list_exams(&self) -> Result<Vec<Exam>, sqlx::Error> {
+        let rows = sqlx::query("select id, title, to_char(created_at, 'YYYY-MM-DD HH24:MI:SS') as created_at from exam order by id")
+            .fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(|r| Exam {
+            id: r.get("id"),
+            title: r.get("title"),
+            created_at: r.get("created_at"),
+        }).collect())
+    }
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::{Row, SqlitePool};
+use super::*;
+
+// SQLite 마이그레이션(컴파일 타임에 ./migrations/sqlite 폴더를 읽음)
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+// SQLite 저장소
+#[derive(Clone)]
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        SqliteRepository { pool }
+    }
+
+    // DATABASE_URL 경로의 sqlite 파일을 찾거나(mode=rwc면 새로 만들고) 연결 풀 생성
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        Ok(SqliteRepository::new(SqlitePool::connect(url).await?))
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl Database for SqliteRepository {
+    fn backend(&self) -> Backend {
+        Backend::Sqlite
+    }
+
+    fn migrator(&self) -> &'static Migrator {
+        &MIGRATOR
+    }
+
+    async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("select 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn applied_migrations(&self) -> Result<Vec<i64>, sqlx::Error> {
+        let rows = sqlx::query("select version from _sqlx_migrations where success=1").fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|r| r.get("version")).collect())
+    }
+}
+
+#[async_trait]
+impl ExamRepository for SqliteRepository {
+    async fn create_exam(&self, title: &str) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query("insert into exam (title) values (?)").bind(title).execute(&self.pool).await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn list_exams(&self) -> Result<Vec<Exam>, sqlx::Error> {
+        let rows = sqlx::query("select id, title, created_at from exam order by id").fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(|r| Exam {
+            id: r.get("id"),
+            title: r.get("title"),
+            created_at: r.get("created_at"),
+        }).collect())
+    }
+}
@@ -0,0 +1,17 @@
+use actix_web::{web, HttpResponse};
+use web_tracing::health::{check_database, check_migrations, readiness_response};
+use crate::repository::Repository;
+
+pub use web_tracing::health::healthz;
+
+// GET /readyz
+// 요청을 처리할 준비가 되었는지 확인(DB 연결, 마이그레이션 적용)
+// 하나라도 실패하면 503과 함께 구성 요소별 결과 응답
+pub async fn readyz(repo: web::Data<dyn Repository>) -> HttpResponse {
+    let database = check_database(repo.backend().as_str(), repo.ping()).await;
+    let expected = repo.migrator().iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version);
+    let migrations = check_migrations(expected, repo.applied_migrations()).await;
+    readiness_response(vec![("database", database), ("migrations", migrations)])
+}
@@ -0,0 +1,38 @@
+use std::future::Future;
+
+// PostgreSQL 저장소 테스트용 임시 데이터베이스
+// 테스트는 #[ignore]로 표시하고 TEST_POSTGRES_URL을 설정한 뒤 cargo test -- --ignored로 실행
+// 연결 및 마이그레이션은 각 서버의 저장소 구현으로 수행하므로 여기서는 데이터베이스 생성과 삭제만 담당
+
+pub const POSTGRES_URL_VAR: &str = "TEST_POSTGRES_URL";    // 예: postgres://postgres@127.0.0.1:5432/postgres
+
+// url의 데이터베이스 이름(마지막 경로)만 교체(쿼리 문자열은 유지)
+pub fn database_url(admin_url: &str, database: &str) -> String {
+    let (base, query) = match admin_url.split_once('?') {
+        Some((base, query)) => (base, format!("?{}", query)),
+        None => (admin_url, String::new()),
+    };
+    let (server, _) = base.rsplit_once('/').expect("TEST_POSTGRES_URL must include a database path");
+    format!("{}/{}{}", server, database, query)
+}
+
+// 새 데이터베이스를 만들고 그 url로 exercise 실행 후 데이터베이스 삭제
+// TEST_POSTGRES_URL이 없으면 건너뛰지 않고 실패(--ignored로 명시적으로 실행한 경우에만 호출됨)
+pub async fn with_database<F, Fut>(prefix: &str, exercise: F)
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let admin_url = std::env::var(POSTGRES_URL_VAR)
+        .unwrap_or_else(|_| panic!("{} must be set to run PostgreSQL backend tests", POSTGRES_URL_VAR));
+    let database = format!("{}_test_{}", prefix, std::process::id());
+    let admin_pool = sqlx::PgPool::connect(&admin_url).await.unwrap();
+    sqlx::query(&format!("drop database if exists {} with (force)", database)).execute(&admin_pool).await.unwrap();
+    sqlx::query(&format!("create database {}", database)).execute(&admin_pool).await.unwrap();
+
+    exercise(database_url(&admin_url, &database)).await;
+
+    // 테스트의 연결 풀이 남아 있어도 삭제(이전 실행에서 남은 데이터베이스는 시작 시 삭제)
+    sqlx::query(&format!("drop database {} with (force)", database)).execute(&admin_pool).await.unwrap();
+    admin_pool.close().await;
+}
@@ -0,0 +1,7 @@
+use test_postgres::database_url;
+
+#[test]
+fn test_database_url_replaces_only_the_database() {
+    assert_eq!(database_url("postgres://postgres@127.0.0.1:5432/postgres", "login_test_1"), "postgres://postgres@127.0.0.1:5432/login_test_1");
+    assert_eq!(database_url("postgres://u:p@db/postgres?sslmode=disable", "study_test_2"), "postgres://u:p@db/study_test_2?sslmode=disable");
+}
@@ -0,0 +1,84 @@
+use actix_web::{http::header, rt::time::timeout, HttpResponse};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+
+// 생존(/healthz) 및 준비 상태(/readyz) 확인에 쓰는 공통 점검
+// 서버마다 저장소 타입이 다르므로 DB 호출 결과(Future)를 받아서 점검
+
+// 준비 상태 확인에서 DB 응답을 기다리는 최대 시간(연결 풀 대기 포함)
+pub const DB_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+// 구성 요소 점검 결과
+#[derive(Serialize)]
+pub struct Component {
+    status: &'static str,   // "ok" 또는 "error"
+    #[serde(flatten)]
+    detail: serde_json::Value,  // 구성 요소별 정보(실패 시 error)
+}
+
+impl Component {
+    pub fn ok(detail: serde_json::Value) -> Self {
+        Component { status: "ok", detail }
+    }
+
+    // 점검 실패, 상세 에러(DB 주소, 드라이버 메시지 등)는 로그에만 남기고 응답에는 일반 메시지만 포함
+    pub fn error(component: &str, error: impl std::fmt::Display) -> Self {
+        tracing::error!(component, error = %error, "Readiness check failed");
+        Component { status: "error", detail: json!({"error": "unavailable"}) }
+    }
+
+    fn timed_out(component: &str) -> Self {
+        tracing::error!(component, "Readiness check timed out");
+        Component { status: "error", detail: json!({"error": "timed out"}) }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status=="ok"
+    }
+}
+
+// DB 연결 확인(ping: 저장소의 연결 확인 쿼리)
+pub async fn check_database<E: std::fmt::Display>(backend: &str, ping: impl Future<Output=Result<(), E>>) -> Component {
+    match timeout(DB_CHECK_TIMEOUT, ping).await {
+        Ok(Ok(())) => Component::ok(json!({"backend": backend})),
+        Ok(Err(e)) => Component::error("database", e),
+        Err(_) => Component::timed_out("database"),
+    }
+}
+
+// 빌드에 포함된 마이그레이션(expected)이 모두 적용되었는지 확인(applied: 적용된 버전 조회)
+pub async fn check_migrations<E: std::fmt::Display>(expected: impl IntoIterator<Item=i64>, applied: impl Future<Output=Result<Vec<i64>, E>>) -> Component {
+    let applied: HashSet<i64> = match timeout(DB_CHECK_TIMEOUT, applied).await {
+        Ok(Ok(versions)) => versions.into_iter().collect(),
+        Ok(Err(e)) => return Component::error("migrations", e),
+        Err(_) => return Component::timed_out("migrations"),
+    };
+    let pending: Vec<i64> = expected.into_iter().filter(|version| !applied.contains(version)).collect();
+    let detail = json!({"applied": applied.len(), "pending": pending});
+    if pending.is_empty() { Component::ok(detail) } else { Component { status: "error", detail } }
+}
+
+// GET /healthz
+// 프로세스 생존 확인(외부 의존성은 확인하지 않음)
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(json!({"status": "ok"}))
+}
+
+// GET /readyz 응답: 모든 구성 요소가 정상이면 200, 하나라도 실패하면 503과 함께 구성 요소별 결과
+pub fn readiness_response(components: Vec<(&str, Component)>) -> HttpResponse {
+    let ready = components.iter().all(|(_, component)| component.is_ok());
+    let components: serde_json::Map<String, serde_json::Value> = components.into_iter()
+        .map(|(name, component)| (name.to_string(), json!(component)))
+        .collect();
+    let body = json!({
+        "status": if ready { "ready" } else { "not_ready" },
+        "components": components,
+    });
+    let mut response = if ready { HttpResponse::Ok() } else { HttpResponse::ServiceUnavailable() };
+    response.insert_header((header::CACHE_CONTROL, "no-store")).json(body)
+}
@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+
+pub mod health;     // src/health.rs 사용
+pub mod middleware; // src/middleware.rs 사용
+pub mod redact;     // src/redact.rs 사용
+
+pub use middleware::{RequestId, RequestTracing, REQUEST_ID_HEADER};
+
+// 로그 출력 형식
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Text,   // 사람이 읽기 쉬운 한 줄 형식(개발용)
+    Json,   // 한 줄에 JSON 객체 하나(로그 수집기용)
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(anyhow!("Unknown log format: {} (expected text or json)", s)),
+        }
+    }
+}
+
+// 로그 수준 및 형식(설정 파일의 [logging] 섹션으로도 사용)
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    // EnvFilter 지시문(예: "info", "debug,sqlx=warn", "login_web_server=debug")
+    pub level: String,
+    pub format: LogFormat,
+}
+
+// sqlx는 실행한 쿼리를 info 수준으로 남기므로 기본값에서는 경고 이상만 출력
+pub const DEFAULT_LEVEL: &str = "info,sqlx=warn";
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig { level: DEFAULT_LEVEL.to_string(), format: LogFormat::Text }
+    }
+}
+
+impl LogConfig {
+    // 환경 변수 LOG_LEVEL(없으면 RUST_LOG), LOG_FORMAT(text 또는 json)으로 기본값 재정의
+    pub fn from_env() -> Result<Self> {
+        let default = LogConfig::default();
+        let level = std::env::var("LOG_LEVEL").or_else(|_| std::env::var("RUST_LOG")).unwrap_or(default.level);
+        let format = match std::env::var("LOG_FORMAT") {
+            Ok(format) => format.parse()?,
+            Err(_) => default.format,
+        };
+        Ok(LogConfig { level, format })
+    }
+
+    // 로그 수준 지시문 검증(서버 시작 전 설정 검증용)
+    pub fn filter(&self) -> Result<EnvFilter> {
+        EnvFilter::try_new(&self.level).map_err(|e| anyhow!("Invalid log level {:?}: {}", self.level, e))
+    }
+}
+
+// 전역 로그 구독자 등록(프로세스에서 한 번만 호출)
+// 요청 처리 중 남긴 로그에는 RequestTracing이 만든 request span(request_id, method, path)이 함께 출력됨
+pub fn init(config: &LogConfig) -> Result<()> {
+    let filter = config.filter()?;
+    let result = match config.format {
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).with_target(false).try_init(),
+        LogFormat::Json => tracing_subscriber::fmt().json()
+            .with_env_filter(filter)
+            .flatten_event(true)        // message 등 이벤트 필드를 최상위에 출력
+            .with_current_span(true)    // 현재 span(request)의 필드 포함
+            .with_span_list(false)
+            .try_init(),
+    };
+    result.map_err(|e| anyhow!("Failed to initialize logging: {}", e))
+}
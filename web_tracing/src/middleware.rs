@@ -0,0 +1,174 @@
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, FromRequest, ResponseError,
+    body::{BoxBody, MessageBody},
+    dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header::{HeaderName, HeaderValue}, StatusCode},
+    Error,
+};
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use std::rc::Rc;
+use std::time::Instant;
+use tracing::{field, Instrument};
+use crate::redact::{redact_headers, redact_query};
+
+// 요청 id 헤더(클라이언트나 프록시가 보낸 값을 이어서 사용하고, 응답에도 같은 값을 담음)
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+// 받은 요청 id의 최대 길이(로그 오염 방지)
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+// 현재 요청의 id(핸들러에서 익스트랙터로 사용 가능)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    // 128비트 난수(hex)
+    fn generate() -> Self {
+        RequestId(format!("{:032x}", rand::random::<u128>()))
+    }
+
+    // 영문, 숫자, '-', '_', '.'로만 된 값만 이어서 사용(그 외에는 새로 생성)
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?;
+        let valid = !value.is_empty() && value.len()<=MAX_REQUEST_ID_LEN
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+        valid.then(|| RequestId(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn header_value(&self) -> Option<HeaderValue> {
+        HeaderValue::from_str(&self.0).ok()
+    }
+}
+
+impl FromRequest for RequestId {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    // RequestTracing을 거치지 않은 요청이면 새로 생성
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(req.extensions().get::<RequestId>().cloned().unwrap_or_else(RequestId::generate)))
+    }
+}
+
+// 안쪽 서비스(인증 미들웨어 등)가 반환한 에러
+// 응답으로 바뀔 때 X-Request-Id 헤더가 붙도록 요청 id와 함께 감쌈
+#[derive(Debug)]
+struct TracedError {
+    inner: Error,
+    request_id: RequestId,
+}
+
+impl std::fmt::Display for TracedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl ResponseError for TracedError {
+    fn status_code(&self) -> StatusCode {
+        self.inner.as_response_error().status_code()
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        let mut res = self.inner.error_response();
+        if let Some(value) = self.request_id.header_value() {
+            res.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+        res
+    }
+}
+
+// 요청 추적 미들웨어
+// 요청마다 request span(request_id, method, path, query, status, latency_ms)을 만들어 핸들러 실행을 감싸고,
+// 완료 시 상태 코드와 처리 시간을 로그로 남긴 뒤 응답에 X-Request-Id 헤더 추가
+// 다른 미들웨어의 에러 응답에도 헤더가 붙도록 가장 바깥쪽(마지막 wrap)에 등록
+pub struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>+'static,
+    S::Future: 'static,
+    B: MessageBody+'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestTracingService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestTracingService { service: Rc::new(service) }))
+    }
+}
+
+pub struct RequestTracingService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingService<S>
+where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error>+'static,
+    S::Future: 'static,
+    B: MessageBody+'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req.headers().get(REQUEST_ID_HEADER).and_then(RequestId::from_header)
+            .unwrap_or_else(RequestId::generate);
+        req.extensions_mut().insert(request_id.clone());
+        // 쿼리 문자열의 토큰, 인가 코드 등은 가린 뒤 기록
+        let query = req.query_string();
+        let span = tracing::info_span!("request",
+            request_id = %request_id.as_str(),
+            method = %req.method(),
+            path = %req.path(),
+            query = field::Empty,
+            status = field::Empty,
+            latency_ms = field::Empty,
+        );
+        if !query.is_empty() {
+            span.record("query", field::display(redact_query(query)));
+        }
+        // 헤더는 debug 수준에서만 기록(Authorization, Cookie 등은 가림)
+        span.in_scope(|| tracing::debug!(headers = %redact_headers(req.headers()), "request headers"));
+        let started = Instant::now();
+        let fut = span.in_scope(|| self.service.call(req));
+        Box::pin(async move {
+            let result = fut.instrument(span.clone()).await;
+            let status = match &result {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+            span.record("status", status.as_u16());
+            span.record("latency_ms", started.elapsed().as_millis() as u64);
+            span.in_scope(|| {
+                if status.is_server_error() {
+                    tracing::error!("request failed");
+                } else {
+                    tracing::info!("request completed");
+                }
+            });
+            match result {
+                Ok(mut res) => {
+                    span.in_scope(|| tracing::debug!(headers = %redact_headers(res.headers()), "response headers"));
+                    if let Some(value) = request_id.header_value() {
+                        res.headers_mut().insert(REQUEST_ID_HEADER, value);
+                    }
+                    Ok(res)
+                }
+                Err(inner) => Err(TracedError { inner, request_id }.into()),
+            }
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use actix_web::http::header::HeaderMap;
+use percent_encoding::percent_decode_str;
+
+// 로그에 남기면 안 되는 값(비밀번호, 토큰, 인가 코드 등) 가리기
+
+pub const REDACTED: &str = "[REDACTED]";
+
+// 이름에 이 단어가 들어가면 민감한 값으로 간주(password, new_password, refresh_token, client_secret 등)
+const SENSITIVE_PARTS: [&str; 5] = ["password", "token", "secret", "authorization", "cookie"];
+// 정확히 일치해야 하는 이름(OAuth 인가 코드, PKCE code_verifier, 2단계 인증 코드)
+const SENSITIVE_NAMES: [&str; 3] = ["code", "code_verifier", "otp"];
+
+pub fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SENSITIVE_PARTS.iter().any(|part| name.contains(part)) || SENSITIVE_NAMES.contains(&name.as_str())
+}
+
+// 쿼리 문자열(a=1&token=...)의 민감한 값 가리기
+// 이름은 퍼센트 인코딩을 풀어서 비교(access%5Ftoken=... 도 가림)
+pub fn redact_query(query: &str) -> String {
+    query.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive(&percent_decode_str(name).decode_utf8_lossy()) => format!("{}={}", name, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// 헤더 목록을 "name: value" 형식으로 나열하고 민감한 값(Authorization, Cookie, Set-Cookie 등) 가리기
+pub fn redact_headers(headers: &HeaderMap) -> String {
+    headers.iter()
+        .map(|(name, value)| {
+            let value = if is_sensitive(name.as_str()) { REDACTED } else { value.to_str().unwrap_or("<non-ascii>") };
+            format!("{}: {}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
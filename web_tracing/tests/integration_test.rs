@@ -0,0 +1,85 @@
+use actix_web::{dev::Service, test, web, App, HttpResponse};
+use futures_util::future::{ready, Either};
+use web_tracing::{redact::{redact_headers, redact_query}, LogConfig, LogFormat, RequestId, RequestTracing};
+
+// 요청 id를 그대로 응답하는 핸들러
+async fn echo_request_id(request_id: RequestId) -> HttpResponse {
+    HttpResponse::Ok().body(request_id.0)
+}
+
+async fn fail() -> Result<HttpResponse, actix_web::Error> {
+    Err(actix_web::error::ErrorInternalServerError("boom"))
+}
+
+#[actix_web::test]
+async fn test_request_id_propagation() {
+    let app = test::init_service(
+        App::new()
+            .route("/echo", web::get().to(echo_request_id))
+            .route("/fail", web::get().to(fail))
+            // 인증 미들웨어처럼 핸들러 전에 에러를 반환하는 미들웨어
+            .wrap_fn(|req, srv| {
+                if req.path()=="/denied" {
+                    Either::Left(ready(Err(actix_web::error::ErrorUnauthorized("denied"))))
+                } else {
+                    Either::Right(srv.call(req))
+                }
+            })
+            .wrap(RequestTracing)
+    ).await;
+
+    // 요청 id가 없으면 새로 생성하여 핸들러와 응답 헤더에 같은 값 전달
+    let req = test::TestRequest::get().uri("/echo").to_request();
+    let resp = test::call_service(&app, req).await;
+    let header = resp.headers().get("x-request-id").unwrap().to_str().unwrap().to_string();
+    assert_eq!(header.len(), 32);
+    assert_eq!(test::read_body(resp).await, header.as_bytes());
+
+    // 받은 요청 id는 그대로 이어서 사용
+    let req = test::TestRequest::get().uri("/echo").insert_header(("X-Request-Id", "upstream-123")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "upstream-123");
+
+    // 허용되지 않는 문자가 있으면 새로 생성
+    let req = test::TestRequest::get().uri("/echo").insert_header(("X-Request-Id", "bad id\"")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_ne!(resp.headers().get("x-request-id").unwrap(), "bad id\"");
+
+    // 에러 응답에도 헤더 포함
+    let req = test::TestRequest::get().uri("/fail").insert_header(("X-Request-Id", "failing")).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 500);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "failing");
+
+    // 안쪽 미들웨어가 반환한 에러도 응답으로 바뀔 때 헤더 포함
+    let req = test::TestRequest::get().uri("/denied").insert_header(("X-Request-Id", "denied-1")).to_request();
+    let err = app.call(req).await.err().unwrap();
+    let resp = err.error_response();
+    assert_eq!(resp.status(), 401);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "denied-1");
+}
+
+#[actix_web::test]
+async fn test_redaction_and_log_config() {
+    assert_eq!(
+        redact_query("state=abc&code=secret-code&access_token=t&name=kim&new_password=p"),
+        "state=abc&code=[REDACTED]&access_token=[REDACTED]&name=kim&new_password=[REDACTED]");
+    assert_eq!(redact_query("flag"), "flag");
+    // 퍼센트 인코딩된 이름도 풀어서 비교
+    assert_eq!(redact_query("access%5Ftoken=t&%63ode=c&pass%77ord=p"), "access%5Ftoken=[REDACTED]&%63ode=[REDACTED]&pass%77ord=[REDACTED]");
+
+    let mut headers = actix_web::http::header::HeaderMap::new();
+    for (name, value) in [("authorization", "Bearer t"), ("cookie", "sid=s"), ("set-cookie", "sid=s"), ("user-agent", "test")] {
+        headers.insert(name.parse().unwrap(), actix_web::http::header::HeaderValue::from_static(value));
+    }
+    let redacted = redact_headers(&headers);
+    for expected in ["authorization: [REDACTED]", "cookie: [REDACTED]", "set-cookie: [REDACTED]", "user-agent: test"] {
+        assert!(redacted.contains(expected), "{} missing from: {}", expected, redacted);
+    }
+    assert!(!redacted.contains("Bearer t") && !redacted.contains("sid=s"));
+
+    assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+    assert!("xml".parse::<LogFormat>().is_err());
+    assert!(LogConfig { level: "debug,sqlx=warn".to_string(), format: LogFormat::Text }.filter().is_ok());
+    assert!(LogConfig { level: "=[".to_string(), format: LogFormat::Text }.filter().is_err());
+}